@@ -16,6 +16,43 @@ pub struct Config {
     /// hardware wallets config.
     /// LEGACY: Use Settings module instead.
     pub hardware_wallets: Option<Vec<HardwareWalletConfig>>,
+    /// The coordination server the PSBTs and descriptors are shared through, if any.
+    pub coordinator: Option<CoordinatorConfig>,
+    /// The Tor daemon the onion service of the daemon RPC interface is published through, if
+    /// any.
+    pub tor: Option<TorConfig>,
+}
+
+/// A coordination server, speaking JSON-RPC over HTTP, used by the participants of a multisig
+/// to pass around the PSBTs and the descriptor.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CoordinatorConfig {
+    pub url: String,
+    /// The credentials of the basic HTTP authentication, if the server requires it.
+    pub user: Option<String>,
+    pub password: Option<String>,
+}
+
+/// The control port of a Tor daemon, asked for an ephemeral onion service forwarding to the RPC
+/// socket of lianad. Tor forgets the service when it restarts: the GUI requests it again with the
+/// same key when it starts, for the onion address to stay the same. The configuration file holds
+/// the password of the control port and the key of the service: it is readable by the user only.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct TorConfig {
+    /// The address of the control port, such as `127.0.0.1:9051`.
+    pub control_address: String,
+    /// The authentication to the control port: the cookie file of Tor, or the password set with
+    /// `HashedControlPassword`. None of them if the control port has no authentication.
+    pub cookie_path: Option<PathBuf>,
+    pub password: Option<String>,
+    /// The port of the onion service forwarded to the RPC socket.
+    pub onion_port: u16,
+    /// The onion service published at install, with its private key as returned by Tor.
+    pub onion_address: Option<String>,
+    pub service_key: Option<String>,
+    /// The x25519 public key of the client authorized to the onion service, encoded in base32.
+    /// The service is never published without client authorization.
+    pub client_auth_key: Option<String>,
 }
 
 pub const DEFAULT_FILE_NAME: &str = "gui.toml";
@@ -28,6 +65,8 @@ impl Config {
             log_level: None,
             debug: None,
             hardware_wallets: None,
+            coordinator: None,
+            tor: None,
         }
     }
 
@@ -3,6 +3,7 @@ pub mod config;
 pub mod menu;
 pub mod message;
 pub mod settings;
+pub mod signer_map;
 pub mod state;
 pub mod view;
 pub mod wallet;
@@ -66,7 +67,7 @@ impl App {
         )
     }
 
-    fn load_state(&mut self, menu: &Menu) -> Command<Message> {
+    pub fn load_state(&mut self, menu: &Menu) -> Command<Message> {
         self.state = match menu {
             menu::Menu::Settings => {
                 state::SettingsState::new(self.data_dir.clone(), self.wallet.clone()).into()
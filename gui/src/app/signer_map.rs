@@ -0,0 +1,130 @@
+use std::path::PathBuf;
+
+use liana::miniscript::bitcoin::{util::bip32::Fingerprint, Network};
+use serde::{Deserialize, Serialize};
+
+use crate::app::settings::SettingsError;
+
+///! The signer map is written by the installer next to the settings file. It tells the GUI the
+///! kind of signer holding each key of the descriptor, without re-deriving it. It contains no
+///! secret: neither mnemonic nor the tokens of the devices.
+pub const DEFAULT_FILE_NAME: &str = "signers.json";
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SignerMap {
+    /// The network and the checksum of the descriptor the map was written for.
+    pub network: Network,
+    pub descriptor_checksum: String,
+    pub signers: Vec<SignerEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SignerEntry {
+    pub master_fingerprint: Fingerprint,
+    pub kind: SignerKind,
+    #[serde(default)]
+    pub label: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SignerKind {
+    /// The mnemonic is stored in the data directory of this computer.
+    HotSigner,
+    /// A signing device the descriptor was registered on, of the given model.
+    HardwareWallet { model: String },
+    /// A key held by another participant, or a device not connected during the install.
+    ExternalCosigner,
+}
+
+impl std::fmt::Display for SignerKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::HotSigner => write!(f, "Hot signer on this computer"),
+            Self::HardwareWallet { model } => write!(f, "{} signing device", model),
+            Self::ExternalCosigner => write!(f, "External cosigner"),
+        }
+    }
+}
+
+impl SignerMap {
+    pub fn from_file(datadir: PathBuf, network: Network) -> Result<Self, SettingsError> {
+        let mut path = datadir;
+        path.push(network.to_string());
+        path.push(DEFAULT_FILE_NAME);
+
+        std::fs::read(path)
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => SettingsError::NotFound,
+                _ => SettingsError::ReadingFile(format!("Reading signer map file: {}", e)),
+            })
+            .and_then(|file_content| {
+                serde_json::from_slice::<SignerMap>(&file_content).map_err(|e| {
+                    SettingsError::ReadingFile(format!("Parsing signer map file: {}", e))
+                })
+            })
+    }
+
+    /// Whether the map was written for the wallet of this network and descriptor.
+    pub fn matches(&self, network: Network, descriptor_checksum: &str) -> bool {
+        self.network == network && self.descriptor_checksum == descriptor_checksum
+    }
+
+    pub fn signer(&self, fingerprint: &Fingerprint) -> Option<&SignerEntry> {
+        self.signers
+            .iter()
+            .find(|signer| signer.master_fingerprint == *fingerprint)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn signer_map_round_trip() {
+        let map = SignerMap {
+            network: Network::Testnet,
+            descriptor_checksum: "g7vk9r5l".to_string(),
+            signers: vec![
+                SignerEntry {
+                    master_fingerprint: Fingerprint::from_str("abcdef01").unwrap(),
+                    kind: SignerKind::HotSigner,
+                    label: "This computer".to_string(),
+                },
+                SignerEntry {
+                    master_fingerprint: Fingerprint::from_str("4df3f0e3").unwrap(),
+                    kind: SignerKind::HardwareWallet {
+                        model: "specter".to_string(),
+                    },
+                    label: "My Specter".to_string(),
+                },
+                SignerEntry {
+                    master_fingerprint: Fingerprint::from_str("f5acc2fd").unwrap(),
+                    kind: SignerKind::ExternalCosigner,
+                    label: String::new(),
+                },
+            ],
+        };
+        let json = serde_json::to_string_pretty(&map).unwrap();
+        assert!(json.contains("\"type\": \"hardware_wallet\""));
+        assert_eq!(serde_json::from_str::<SignerMap>(&json).unwrap(), map);
+        assert!(map.matches(Network::Testnet, "g7vk9r5l"));
+        assert!(!map.matches(Network::Bitcoin, "g7vk9r5l"));
+        assert!(!map.matches(Network::Testnet, "00000000"));
+        assert_eq!(
+            map.signer(&Fingerprint::from_str("4df3f0e3").unwrap())
+                .unwrap()
+                .label,
+            "My Specter"
+        );
+
+        // The label is optional.
+        let entry: SignerEntry = serde_json::from_str(
+            r#"{"master_fingerprint":"f5acc2fd","kind":{"type":"external_cosigner"}}"#,
+        )
+        .unwrap();
+        assert_eq!(entry, map.signers[2]);
+    }
+}
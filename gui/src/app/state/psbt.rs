@@ -28,6 +28,7 @@ use crate::{
         Daemon,
     },
     hw::{list_hardware_wallets, HardwareWallet},
+    signer::Signer,
 };
 
 pub trait Action {
@@ -272,6 +273,8 @@ pub struct SignAction {
     hws: Vec<HardwareWallet>,
     error: Option<Error>,
     signed: HashSet<Fingerprint>,
+    /// The passphrase of the locked hot signer, while it is asked for.
+    passphrase: Option<form::Value<String>>,
 }
 
 impl SignAction {
@@ -283,8 +286,18 @@ impl SignAction {
             hws: Vec::new(),
             error: None,
             signed,
+            passphrase: None,
         }
     }
+
+    /// The fingerprint of the hot signer, once unlocked for the locked one.
+    fn hot_signer_fingerprint(&self) -> Option<Fingerprint> {
+        self.wallet
+            .signer
+            .as_ref()
+            .map(|s| s.fingerprint())
+            .or_else(|| self.wallet.locked_signer.as_ref().map(|(_, fg)| *fg))
+    }
 }
 
 impl Action for SignAction {
@@ -323,12 +336,44 @@ impl Action for SignAction {
                 }
             }
             Message::View(view::Message::Spend(view::SpendTxMessage::SelectHotSigner)) => {
+                if self.wallet.signer.is_none() && self.wallet.locked_signer.is_some() {
+                    self.passphrase = Some(form::Value::default());
+                    return Command::none();
+                }
                 self.processing = true;
                 return Command::perform(
                     sign_psbt_with_hot_signer(self.wallet.clone(), tx.psbt.clone()),
                     Message::Signed,
                 );
             }
+            Message::View(view::Message::Spend(
+                view::SpendTxMessage::HotSignerPassphraseEdited(value),
+            )) => {
+                if let Some(passphrase) = self.passphrase.as_mut() {
+                    passphrase.value = value;
+                    passphrase.valid = true;
+                }
+            }
+            Message::View(view::Message::Spend(view::SpendTxMessage::UnlockHotSigner)) => {
+                if let (Some((signer, fingerprint)), Some(passphrase)) =
+                    (&self.wallet.locked_signer, self.passphrase.as_mut())
+                {
+                    match signer.with_passphrase(&passphrase.value) {
+                        // The signer is only unlocked for this signature, and wiped once done.
+                        Ok(unlocked) if unlocked.fingerprint() == *fingerprint => {
+                            self.passphrase = None;
+                            self.processing = true;
+                            let psbt = tx.psbt.clone();
+                            return Command::perform(
+                                async move { sign_psbt_with_signer(&unlocked, psbt) },
+                                Message::Signed,
+                            );
+                        }
+                        Ok(_) => passphrase.valid = false,
+                        Err(e) => self.error = Some(WalletError::HotSigner(e.to_string()).into()),
+                    }
+                }
+            }
             Message::Signed(res) => match res {
                 Err(e) => self.error = Some(e),
                 Ok((psbt, fingerprint)) => {
@@ -379,11 +424,10 @@ impl Action for SignAction {
         view::psbt::sign_action(
             self.error.as_ref(),
             &self.hws,
-            self.wallet.signer.as_ref().map(|s| s.fingerprint()),
-            self.wallet
-                .signer
-                .as_ref()
-                .and_then(|signer| self.wallet.keys_aliases.get(&signer.fingerprint)),
+            self.hot_signer_fingerprint(),
+            self.hot_signer_fingerprint()
+                .and_then(|fingerprint| self.wallet.keys_aliases.get(&fingerprint)),
+            self.passphrase.as_ref(),
             self.processing,
             self.chosen_hw,
             &self.signed,
@@ -396,15 +440,19 @@ async fn sign_psbt_with_hot_signer(
     psbt: Psbt,
 ) -> Result<(Psbt, Fingerprint), Error> {
     if let Some(signer) = &wallet.signer {
-        let psbt = signer.sign_psbt(psbt).map_err(|e| {
-            WalletError::HotSigner(format!("Hot signer failed to sign psbt: {}", e))
-        })?;
-        Ok((psbt, signer.fingerprint()))
+        sign_psbt_with_signer(signer, psbt)
     } else {
         Err(WalletError::HotSigner("Hot signer not loaded".to_string()).into())
     }
 }
 
+fn sign_psbt_with_signer(signer: &Signer, psbt: Psbt) -> Result<(Psbt, Fingerprint), Error> {
+    let psbt = signer
+        .sign_psbt(psbt)
+        .map_err(|e| WalletError::HotSigner(format!("Hot signer failed to sign psbt: {}", e)))?;
+    Ok((psbt, signer.fingerprint()))
+}
+
 async fn sign_psbt(
     hw: std::sync::Arc<dyn async_hwi::HWI + Send + Sync>,
     fingerprint: Fingerprint,
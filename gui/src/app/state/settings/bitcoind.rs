@@ -208,9 +208,20 @@ impl Setting for BitcoindSettings {
 
                 if self.addr.valid & self.cookie_path.valid {
                     let mut daemon_config = daemon.config().cloned().unwrap();
+                    let descriptor_import = daemon_config
+                        .bitcoind_config
+                        .as_ref()
+                        .map(|c| c.descriptor_import.clone())
+                        .unwrap_or_default();
+                    let rpc_auth = daemon_config
+                        .bitcoind_config
+                        .as_ref()
+                        .and_then(|c| c.rpc_auth.clone());
                     daemon_config.bitcoind_config = Some(liana::config::BitcoindConfig {
                         cookie_path: new_path.unwrap(),
                         addr: new_addr.unwrap(),
+                        rpc_auth,
+                        descriptor_import,
                     });
                     self.processing = true;
                     return Command::perform(async move { daemon_config }, |cfg| {
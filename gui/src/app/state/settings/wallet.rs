@@ -79,6 +79,7 @@ impl State for WalletSettingsState {
             self.warning.as_ref(),
             &self.descriptor,
             &self.keys_aliases,
+            self.wallet.signer_map.as_ref(),
             self.processing,
             self.updated,
         );
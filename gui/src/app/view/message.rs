@@ -45,6 +45,9 @@ pub enum SpendTxMessage {
     Confirm,
     Cancel,
     SelectHotSigner,
+    /// The BIP39 passphrase of the hot signer, asked for to sign.
+    HotSignerPassphraseEdited(String),
+    UnlockHotSigner,
     EditPsbt,
     PsbtEdited(String),
     Next,
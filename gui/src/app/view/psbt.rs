@@ -688,6 +688,7 @@ pub fn sign_action<'a>(
     hws: &'a [HardwareWallet],
     signer: Option<Fingerprint>,
     signer_alias: Option<&'a String>,
+    passphrase: Option<&'a form::Value<String>>,
     processing: bool,
     chosen_hw: Option<usize>,
     signed: &HashSet<Fingerprint>,
@@ -734,6 +735,32 @@ pub fn sign_action<'a>(
                             .style(theme::Button::Border)
                             .width(Length::Fill)
                         }))
+                        .push_maybe(passphrase.map(|passphrase| {
+                            Column::new()
+                                .spacing(10)
+                                .push(text("Enter the passphrase of the hot key to sign:").small())
+                                .push(
+                                    Row::new()
+                                        .spacing(10)
+                                        .align_items(Alignment::Center)
+                                        .push(
+                                            form::Form::new("Passphrase", passphrase, |msg| {
+                                                Message::Spend(
+                                                    SpendTxMessage::HotSignerPassphraseEdited(msg),
+                                                )
+                                            })
+                                            .warning("Wrong passphrase")
+                                            .padding(10),
+                                        )
+                                        .push(
+                                            button::primary(None, "Sign")
+                                                .on_press(Message::Spend(
+                                                    SpendTxMessage::UnlockHotSigner,
+                                                ))
+                                                .width(Length::Units(100)),
+                                        ),
+                                )
+                        }))
                         .width(Length::Fill),
                 )
                 .spacing(20)
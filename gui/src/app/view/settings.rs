@@ -24,6 +24,7 @@ use crate::{
         cache::Cache,
         error::Error,
         menu::Menu,
+        signer_map::{SignerKind, SignerMap},
         view::{hw, warning::warn},
     },
     hw::HardwareWallet,
@@ -346,10 +347,13 @@ pub fn bitcoind<'a>(
     }
 
     let rows = vec![
-        (
-            "Cookie file path:",
-            config.cookie_path.to_str().unwrap().to_string(),
-        ),
+        match &config.rpc_auth {
+            Some(auth) => ("RPC user:", auth.user.clone()),
+            None => (
+                "Cookie file path:",
+                config.cookie_path.to_str().unwrap().to_string(),
+            ),
+        },
         ("Socket address:", config.addr.to_string()),
     ];
 
@@ -545,6 +549,7 @@ pub fn wallet_settings<'a>(
     warning: Option<&Error>,
     descriptor: &'a str,
     keys_aliases: &[(Fingerprint, form::Value<String>)],
+    signer_map: Option<&SignerMap>,
     processing: bool,
     updated: bool,
 ) -> Element<'a, Message> {
@@ -622,6 +627,11 @@ pub fn wallet_settings<'a>(
                                         .warning("Please enter correct alias")
                                         .size(20)
                                         .padding(10),
+                                    )
+                                    .push_maybe(
+                                        signer_map
+                                            .and_then(|map| map.signer(&fg))
+                                            .map(|signer| signer_kind(&signer.kind)),
                                     ),
                             )
                         },
@@ -652,6 +662,21 @@ pub fn wallet_settings<'a>(
     )
 }
 
+/// The kind of signer holding a key, with its icon.
+fn signer_kind<'a>(kind: &SignerKind) -> Element<'a, Message> {
+    Row::new()
+        .spacing(5)
+        .align_items(Alignment::Center)
+        .push(match kind {
+            SignerKind::HotSigner => icon::key_icon(),
+            SignerKind::HardwareWallet { .. } => icon::chip_icon(),
+            SignerKind::ExternalCosigner => icon::network_icon(),
+        })
+        .push(text(kind.to_string()).small())
+        .width(Length::Units(250))
+        .into()
+}
+
 pub fn register_wallet_modal<'a>(
     warning: Option<&Error>,
     hws: &'a [HardwareWallet],
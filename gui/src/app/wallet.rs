@@ -2,7 +2,7 @@ use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
 use crate::{
-    app::{config::Config, settings},
+    app::{config::Config, settings, signer_map::SignerMap},
     hw::HardwareWalletConfig,
     signer::Signer,
 };
@@ -21,6 +21,11 @@ pub struct Wallet {
     pub keys_aliases: HashMap<Fingerprint, String>,
     pub hardware_wallets: Vec<HardwareWalletConfig>,
     pub signer: Option<Signer>,
+    /// The hot signer whose seed is derived with a BIP39 passphrase, with the fingerprint of
+    /// that seed. The passphrase is not stored: the user enters it to sign.
+    pub locked_signer: Option<(Signer, Fingerprint)>,
+    /// The kind of signer of each key, as recorded by the installer.
+    pub signer_map: Option<SignerMap>,
 }
 
 impl Wallet {
@@ -31,6 +36,8 @@ impl Wallet {
             keys_aliases: HashMap::new(),
             hardware_wallets: Vec::new(),
             signer: None,
+            locked_signer: None,
+            signer_map: None,
         }
     }
 
@@ -49,6 +56,16 @@ impl Wallet {
         self
     }
 
+    pub fn with_locked_signer(mut self, signer: Signer, fingerprint: Fingerprint) -> Self {
+        self.locked_signer = Some((signer, fingerprint));
+        self
+    }
+
+    pub fn with_signer_map(mut self, signer_map: SignerMap) -> Self {
+        self.signer_map = Some(signer_map);
+        self
+    }
+
     pub fn descriptor_keys(&self) -> HashSet<Fingerprint> {
         let info = self.main_descriptor.policy();
         let mut descriptor_keys = HashSet::new();
@@ -106,7 +123,21 @@ impl Wallet {
             Err(e) => return Err(e.into()),
         };
 
-        let hot_signers = match HotSigner::from_datadir(datadir_path, network) {
+        // The map is only an help for the display, a missing or stale one is ignored.
+        match SignerMap::from_file(datadir_path.to_path_buf(), network) {
+            Ok(signer_map) => {
+                if signer_map.matches(network, &wallet.descriptor_checksum()) {
+                    wallet = wallet.with_signer_map(signer_map);
+                } else {
+                    tracing::warn!("Signer map written for another wallet, ignoring it");
+                }
+            }
+            Err(settings::SettingsError::NotFound) => {}
+            Err(e) => tracing::warn!("Failed to load the signer map: {}", e),
+        }
+
+        let mut hot_signers = match HotSigner::from_datadir_with_fingerprints(datadir_path, network)
+        {
             Ok(signers) => signers,
             Err(e) => match e {
                 liana::signer::SignerError::MnemonicStorage(e) => {
@@ -122,11 +153,20 @@ impl Wallet {
 
         let curve = bitcoin::secp256k1::Secp256k1::signing_only();
         let keys = wallet.descriptor_keys();
-        if let Some(hot_signer) = hot_signers
-            .into_iter()
-            .find(|s| keys.contains(&s.fingerprint(&curve)))
+        if let Some(i) = hot_signers
+            .iter()
+            .position(|(s, _)| keys.contains(&s.fingerprint(&curve)))
+        {
+            wallet = wallet.with_signer(Signer::new(hot_signers.swap_remove(i).0));
+        } else if let Some((hot_signer, fingerprint)) =
+            hot_signers.into_iter().find_map(|(s, fingerprint)| {
+                fingerprint
+                    .filter(|fingerprint| keys.contains(fingerprint))
+                    .map(|fingerprint| (s, fingerprint))
+            })
         {
-            wallet = wallet.with_signer(Signer::new(hot_signer));
+            // The mnemonic was stored for the seed derived with a passphrase.
+            wallet = wallet.with_locked_signer(Signer::new(hot_signer), fingerprint);
         }
 
         Ok(wallet)
@@ -236,7 +236,17 @@ fn ledger_version_supported(version: Option<&Version>) -> bool {
 pub async fn list_unregistered_hardware_wallets(
     aliases: Option<&HashMap<Fingerprint, String>>,
 ) -> Vec<HardwareWallet> {
+    enumerate_unregistered_hardware_wallets(aliases).await.0
+}
+
+/// List the connected devices along with the errors met while looking for them, which tell
+/// apart a failure of the enumeration itself (missing permissions, driver issues) from the
+/// absence of any device. The errors of the simulators are left out.
+pub async fn enumerate_unregistered_hardware_wallets(
+    aliases: Option<&HashMap<Fingerprint, String>>,
+) -> (Vec<HardwareWallet>, Vec<String>) {
     let mut hws: Vec<HardwareWallet> = Vec::new();
+    let mut errors: Vec<String> = Vec::new();
     match specter::SpecterSimulator::try_connect().await {
         Ok(device) => match HardwareWallet::new(Arc::new(device), aliases).await {
             Ok(hw) => hws.push(hw),
@@ -259,6 +269,7 @@ pub async fn list_unregistered_hardware_wallets(
         Err(HWIError::DeviceNotFound) => {}
         Err(e) => {
             debug!("{}", e);
+            errors.push(e.to_string());
         }
     }
     match ledger::LedgerSimulator::try_connect().await {
@@ -298,6 +309,7 @@ pub async fn list_unregistered_hardware_wallets(
     match ledger::HidApi::new() {
         Err(e) => {
             debug!("{}", e);
+            errors.push(format!("Failed to access the USB devices: {}", e));
         }
         Ok(api) => {
             for detected in ledger::Ledger::<ledger::TransportHID>::enumerate(&api) {
@@ -334,10 +346,11 @@ pub async fn list_unregistered_hardware_wallets(
                     Err(HWIError::DeviceNotFound) => {}
                     Err(e) => {
                         debug!("{}", e);
+                        errors.push(e.to_string());
                     }
                 }
             }
         }
     }
-    hws
+    (hws, errors)
 }
@@ -1,13 +1,17 @@
-use std::path::PathBuf;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use crate::{
     app::{
+        config::{CoordinatorConfig, TorConfig},
         settings::{KeySetting, Settings, WalletSetting},
+        signer_map::{SignerEntry, SignerKind, SignerMap},
         wallet::DEFAULT_WALLET_NAME,
     },
     hw::HardwareWalletConfig,
+    installer::{prompt, step::PolicyBuilder},
     signer::Signer,
 };
 use async_hwi::DeviceKind;
@@ -17,12 +21,16 @@ use liana::{
     descriptors::LianaDescriptor,
     miniscript::bitcoin,
 };
+use serde::{Deserialize, Serialize};
 
 #[derive(Clone)]
 pub struct Context {
     pub bitcoin_config: BitcoinConfig,
     pub bitcoind_config: Option<BitcoindConfig>,
     pub descriptor: Option<LianaDescriptor>,
+    /// The structured form of the descriptor, if it can be edited: the descriptor is generated
+    /// from it, or it was read from the descriptor.
+    pub policy_builder: Option<PolicyBuilder>,
     pub keys: Vec<KeySetting>,
     pub hws: Vec<(
         DeviceKind,
@@ -31,9 +39,76 @@ pub struct Context {
     )>,
     pub data_dir: PathBuf,
     pub hw_is_used: bool,
+    /// The cosigner devices the descriptor must be registered on, and whether the registration
+    /// was confirmed.
+    pub cosigners: Vec<(bitcoin::util::bip32::Fingerprint, bool)>,
     // In case a user entered a mnemonic,
     // we dont want to override the generated signer with it.
     pub recovered_signer: Option<Arc<Signer>>,
+    // Shared with the steps, so that going back and forth between them
+    // does not ask again for warnings already acknowledged.
+    pub acknowledged_warnings: Arc<Mutex<AcknowledgedWarnings>>,
+    /// The socket of an externally managed daemon the GUI must connect to. If not set, the
+    /// GUI uses the socket in the data directory and starts the daemon itself if needed.
+    pub daemon_rpc_path: Option<PathBuf>,
+    /// The coordination server of the participants of the wallet, if any.
+    pub coordinator: Option<CoordinatorConfig>,
+    /// The control port of Tor the onion service of the daemon is requested to, if any.
+    pub tor: Option<TorConfig>,
+    /// The private key of the client authorized to the onion service, shown to the user once the
+    /// service is published. It is not stored.
+    pub tor_client_key: Option<String>,
+    /// The rescan of the chain for the coins already received by the descriptor, if any.
+    pub rescan: Option<Rescan>,
+    /// The name of the wallet in the GUI settings.
+    pub wallet_name: String,
+    /// The wallets installed earlier in this installer session, the first one in the data
+    /// directory the installer was started with.
+    pub installed_wallets: Vec<InstalledWallet>,
+}
+
+/// A wallet installed during this installer session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstalledWallet {
+    pub name: String,
+    pub data_dir: PathBuf,
+    /// The path of its GUI configuration file, to start the GUI with.
+    pub config_path: PathBuf,
+}
+
+/// The folder, in the data directory of the first wallet, of the data directories of the
+/// other wallets set up in the same session.
+pub const WALLETS_FOLDER_NAME: &str = "wallets";
+
+/// The data directory of a wallet set up after the first one: a folder named after the wallet,
+/// in lowercase with its other characters than letters and digits replaced by dashes.
+pub fn wallet_data_dir(root: &Path, name: &str) -> PathBuf {
+    let folder: String = name
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    root.join(WALLETS_FOLDER_NAME).join(folder)
+}
+
+/// No Liana wallet could have received coins before the first release of Liana, in November
+/// 2022.
+pub const DEFAULT_RESCAN_TIMESTAMP: u32 = 1_667_260_800;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rescan {
+    /// The time of the first block rescanned, and its height.
+    pub timestamp: u32,
+    pub height: u32,
+    /// Whether the install is completed only once the rescan is, rather than letting it run in
+    /// the background.
+    pub wait: bool,
 }
 
 impl Context {
@@ -47,10 +122,74 @@ impl Context {
             keys: Vec::new(),
             bitcoind_config: None,
             descriptor: None,
+            policy_builder: None,
             data_dir,
             hw_is_used: false,
+            cosigners: Vec::new(),
             recovered_signer: None,
+            acknowledged_warnings: Arc::new(Mutex::new(AcknowledgedWarnings::default())),
+            daemon_rpc_path: None,
+            coordinator: None,
+            tor: None,
+            tor_client_key: None,
+            rescan: None,
+            wallet_name: DEFAULT_WALLET_NAME.to_string(),
+            installed_wallets: Vec::new(),
+        }
+    }
+
+    /// Record the wallet of this context as installed, with the given GUI configuration.
+    pub fn record_installed_wallet(&mut self, config_path: PathBuf) {
+        self.installed_wallets.push(InstalledWallet {
+            name: self.wallet_name.clone(),
+            data_dir: self.data_dir.clone(),
+            config_path,
+        });
+    }
+
+    /// Check the name of another wallet for its settings and its data directory not to collide
+    /// with the ones of a wallet already installed.
+    pub fn check_wallet_name(&self, name: &str) -> Result<(), &'static str> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Please enter a name for the wallet");
+        }
+        let root = match self.installed_wallets.first() {
+            Some(wallet) => &wallet.data_dir,
+            None => &self.data_dir,
+        };
+        let data_dir = wallet_data_dir(root, name);
+        if self.installed_wallets.iter().any(|wallet| {
+            wallet.name.trim().eq_ignore_ascii_case(name) || wallet.data_dir == data_dir
+        }) {
+            return Err("A wallet of this session already has this name");
+        }
+        if data_dir
+            .join(self.bitcoin_config.network.to_string())
+            .exists()
+        {
+            return Err("A wallet is already installed in the folder of this name");
         }
+        Ok(())
+    }
+
+    /// The context of another wallet to set up, on the same network and bitcoind.
+    pub fn next_wallet(&self, name: &str) -> Result<Context, &'static str> {
+        self.check_wallet_name(name)?;
+        let root = self
+            .installed_wallets
+            .first()
+            .map(|wallet| wallet.data_dir.clone())
+            .unwrap_or_else(|| self.data_dir.clone());
+        let mut ctx = Context::new(self.bitcoin_config.network, wallet_data_dir(&root, name));
+        ctx.bitcoin_config = self.bitcoin_config.clone();
+        ctx.bitcoind_config = self.bitcoind_config.clone().map(|mut config| {
+            config.descriptor_import = Default::default();
+            config
+        });
+        ctx.wallet_name = name.trim().to_string();
+        ctx.installed_wallets = self.installed_wallets.clone();
+        Ok(ctx)
     }
 
     pub fn extract_gui_settings(&self) -> Settings {
@@ -65,7 +204,7 @@ impl Context {
             .collect();
         Settings {
             wallets: vec![WalletSetting {
-                name: DEFAULT_WALLET_NAME.to_string(),
+                name: self.wallet_name.clone(),
                 descriptor_checksum: self
                     .descriptor
                     .as_ref()
@@ -81,6 +220,56 @@ impl Context {
         }
     }
 
+    /// The kind of signer of each key of the descriptor, the given hot signers being the ones
+    /// whose mnemonic is stored on this computer.
+    pub fn extract_signer_map(
+        &self,
+        hot_signers: &[bitcoin::util::bip32::Fingerprint],
+    ) -> SignerMap {
+        let descriptor = self.descriptor.as_ref().unwrap();
+        let policy = descriptor.policy();
+        let mut fingerprints: Vec<bitcoin::util::bip32::Fingerprint> =
+            std::iter::once(policy.primary_path())
+                .chain(policy.recovery_paths().values())
+                .flat_map(|path| path.thresh_origins().1.into_iter())
+                .map(|(fingerprint, _)| fingerprint)
+                .collect();
+        fingerprints.sort();
+        fingerprints.dedup();
+        SignerMap {
+            network: self.bitcoin_config.network,
+            descriptor_checksum: descriptor
+                .to_string()
+                .split_once('#')
+                .map(|(_, checksum)| checksum)
+                .unwrap()
+                .to_string(),
+            signers: fingerprints
+                .into_iter()
+                .map(|fingerprint| SignerEntry {
+                    master_fingerprint: fingerprint,
+                    kind: if hot_signers.contains(&fingerprint) {
+                        SignerKind::HotSigner
+                    } else if let Some((kind, _, _)) =
+                        self.hws.iter().find(|(_, fg, _)| *fg == fingerprint)
+                    {
+                        SignerKind::HardwareWallet {
+                            model: kind.to_string(),
+                        }
+                    } else {
+                        SignerKind::ExternalCosigner
+                    },
+                    label: self
+                        .keys
+                        .iter()
+                        .find(|k| k.master_fingerprint == fingerprint)
+                        .map(|k| k.name.clone())
+                        .unwrap_or_default(),
+                })
+                .collect(),
+        }
+    }
+
     pub fn extract_daemon_config(&self) -> Config {
         Config {
             #[cfg(unix)]
@@ -93,3 +282,167 @@ impl Context {
         }
     }
 }
+
+/// A security warning the user must acknowledge before going further.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecurityWarning {
+    /// A copied descriptor can be read by the other applications of the computer.
+    DescriptorCopy,
+    /// A key of the hot signer is in a recovery path.
+    HotRecoveryKey,
+}
+
+impl SecurityWarning {
+    pub fn message(&self) -> &'static str {
+        match self {
+            Self::DescriptorCopy => prompt::DESCRIPTOR_COPY_WARNING,
+            Self::HotRecoveryKey => prompt::HOT_RECOVERY_KEY_WARNING,
+        }
+    }
+}
+
+pub const ACKNOWLEDGED_WARNINGS_FILE_NAME: &str = "acknowledged_warnings.json";
+
+/// The warnings acknowledged by the user during the installation.
+/// An acknowledgement is only valid for the input it was given for, it is
+/// dropped as soon as this input changes.
+#[derive(Debug, Clone, Default)]
+pub struct AcknowledgedWarnings {
+    /// The data directory the acknowledgements are stored in by the install, so they are kept
+    /// when the installer is restarted. They are only kept in memory without one.
+    data_dir: Option<PathBuf>,
+    acknowledged: HashMap<SecurityWarning, String>,
+}
+
+impl AcknowledgedWarnings {
+    /// The acknowledgements stored in the data directory, if any.
+    pub fn from_data_dir(data_dir: &Path) -> Self {
+        let acknowledged = std::fs::read(data_dir.join(ACKNOWLEDGED_WARNINGS_FILE_NAME))
+            .ok()
+            .and_then(|content| serde_json::from_slice(&content).ok())
+            .unwrap_or_default();
+        Self {
+            data_dir: Some(data_dir.to_path_buf()),
+            acknowledged,
+        }
+    }
+
+    /// The data directory to store the acknowledgements in, with their content. Nothing is
+    /// written before the install, for a failed one to be rolled back with its other files.
+    pub fn to_store(&self) -> Option<(PathBuf, Vec<u8>)> {
+        let data_dir = self.data_dir.clone()?;
+        match serde_json::to_vec_pretty(&self.acknowledged) {
+            Ok(content) => Some((data_dir, content)),
+            Err(e) => {
+                tracing::warn!("Failed to serialize the acknowledged warnings: {}", e);
+                None
+            }
+        }
+    }
+
+    pub fn acknowledge(&mut self, warning: SecurityWarning, input: String) {
+        self.acknowledged.insert(warning, input);
+    }
+
+    pub fn forget(&mut self, warning: SecurityWarning) {
+        self.acknowledged.remove(&warning);
+    }
+
+    pub fn is_acknowledged(&self, warning: SecurityWarning, input: &str) -> bool {
+        self.acknowledged
+            .get(&warning)
+            .map(|i| i == input)
+            .unwrap_or(false)
+    }
+
+    /// Drop the acknowledgement of the warning if it was given for another input,
+    /// or if the warning does not apply anymore.
+    pub fn invalidate(&mut self, warning: SecurityWarning, input: Option<&str>) {
+        if let Some(acknowledged) = self.acknowledged.get(&warning) {
+            if input != Some(acknowledged.as_str()) {
+                self.acknowledged.remove(&warning);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn acknowledged_warnings_storage() {
+        let data_dir = std::env::temp_dir().join(format!(
+            "liana-gui-test-acknowledged-warnings-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&data_dir);
+
+        // Nothing is written before the install, not even the data directory.
+        let mut warnings = AcknowledgedWarnings::from_data_dir(&data_dir);
+        assert!(!warnings.is_acknowledged(SecurityWarning::DescriptorCopy, ""));
+        warnings.acknowledge(SecurityWarning::DescriptorCopy, String::new());
+        warnings.acknowledge(SecurityWarning::HotRecoveryKey, "52560:alone".to_string());
+        assert!(!data_dir.exists());
+
+        // Once stored, they are read back.
+        let store = |warnings: &AcknowledgedWarnings| {
+            let (dir, content) = warnings.to_store().unwrap();
+            assert_eq!(dir, data_dir);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join(ACKNOWLEDGED_WARNINGS_FILE_NAME), content).unwrap();
+        };
+        store(&warnings);
+        let stored = AcknowledgedWarnings::from_data_dir(&data_dir);
+        assert!(stored.is_acknowledged(SecurityWarning::DescriptorCopy, ""));
+        assert!(stored.is_acknowledged(SecurityWarning::HotRecoveryKey, "52560:alone"));
+
+        // Acknowledgements dropped since are not read back.
+        warnings.invalidate(SecurityWarning::HotRecoveryKey, Some("52560"));
+        warnings.forget(SecurityWarning::DescriptorCopy);
+        store(&warnings);
+        let stored = AcknowledgedWarnings::from_data_dir(&data_dir);
+        assert!(!stored.is_acknowledged(SecurityWarning::DescriptorCopy, ""));
+        assert!(!stored.is_acknowledged(SecurityWarning::HotRecoveryKey, "52560:alone"));
+
+        // Without data directory they are only kept in memory.
+        let mut warnings = AcknowledgedWarnings::default();
+        warnings.acknowledge(SecurityWarning::DescriptorCopy, String::new());
+        assert!(warnings.is_acknowledged(SecurityWarning::DescriptorCopy, ""));
+        assert!(warnings.to_store().is_none());
+
+        std::fs::remove_dir_all(&data_dir).unwrap();
+    }
+
+    #[test]
+    fn next_wallet_names() {
+        let root = std::env::temp_dir().join("liana-gui-test-next-wallet");
+        let mut ctx = Context::new(bitcoin::Network::Testnet, root.clone());
+        assert_eq!(
+            wallet_data_dir(&root, " Savings & Spending 2 "),
+            root.join(WALLETS_FOLDER_NAME).join("savings---spending-2")
+        );
+
+        ctx.record_installed_wallet(root.join("testnet").join("gui.toml"));
+        assert!(ctx.check_wallet_name("  ").is_err());
+        assert!(ctx.check_wallet_name(DEFAULT_WALLET_NAME).is_err());
+        assert!(ctx.check_wallet_name("liana").is_err());
+
+        let mut next = ctx.next_wallet("Spending").unwrap();
+        assert_eq!(next.wallet_name, "Spending");
+        assert_eq!(next.data_dir, wallet_data_dir(&root, "Spending"));
+        assert_eq!(next.bitcoin_config.network, bitcoin::Network::Testnet);
+        assert_eq!(next.installed_wallets, ctx.installed_wallets);
+
+        // The names of the wallets set up after the first one may not give the same folder,
+        // which is always in the data directory of the first wallet.
+        next.record_installed_wallet(next.data_dir.join("testnet").join("gui.toml"));
+        assert!(next.check_wallet_name("spending").is_err());
+        assert!(next.check_wallet_name("Spending!").is_ok());
+        assert_eq!(
+            next.next_wallet("Savings").unwrap().data_dir,
+            wallet_data_dir(&root, "Savings")
+        );
+    }
+}
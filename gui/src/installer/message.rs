@@ -4,8 +4,15 @@ use liana::miniscript::{
 };
 use std::path::PathBuf;
 
-use super::Error;
-use crate::hw::HardwareWallet;
+use super::{
+    context::SecurityWarning,
+    step::{
+        AddressBranch, BitcoindPing, ChecklistItem, NodeDescriptorInfo, RegistrationFormat,
+        RescanStart, SummarySection,
+    },
+    Error,
+};
+use crate::{app::menu::Menu, hw::HardwareWallet};
 use async_hwi::DeviceKind;
 
 #[derive(Debug, Clone)]
@@ -13,25 +20,57 @@ pub enum Message {
     CreateWallet,
     ParticipateWallet,
     ImportWallet,
+    /// Open the offline verification of a descriptor.
+    VerifyDescriptor,
     UserActionDone(bool),
-    Exit(PathBuf),
+    /// Leave the installer, optionally opening the given panel of the application.
+    Exit(PathBuf, Option<Menu>),
     Clibpboard(String),
     Next,
     Skip,
     Previous,
+    /// Go back from the pre-install summary to the step editing the given section.
+    EditStep(SummarySection),
     Install,
     Close,
     Reload,
+    /// Reset the current step to its defaults.
+    Reset,
+    /// The window got the focus, or lost it.
+    WindowFocused(bool),
     Select(usize),
     UseHotSigner,
+    /// Replace the hot signer by a newly generated one.
+    RegenerateHotSigner,
+    /// The warning was acknowledged (or not anymore) for the given input.
+    AcknowledgeWarning(SecurityWarning, String, bool),
     Installed(Result<PathBuf, Error>),
+    /// Set up another wallet of the given name, on the same network and bitcoind.
+    SetUpAnotherWallet(String),
+    /// Roll back what the failed install created.
+    CleanUpInstall,
+    InstallCleanedUp(Result<(), Error>),
     Network(Network),
     DefineBitcoind(DefineBitcoind),
+    DefineCoordinator(DefineCoordinator),
+    DefineTor(DefineTor),
     DefineDescriptor(DefineDescriptor),
+    BackupDescriptor(BackupDescriptor),
+    RegisterDescriptor(RegisterDescriptor),
+    Final(Final),
     ImportXpub(usize, Result<DescriptorPublicKey, Error>),
     ConnectedHardwareWallets(Vec<HardwareWallet>),
-    WalletRegistered(Result<(Fingerprint, Option<[u8; 32]>), Error>),
+    WalletRegistered(Fingerprint, Result<Option<[u8; 32]>, Error>),
+    /// A word of the mnemonic to recover was edited, or a whole phrase pasted from it.
     MnemonicWord(usize, String),
+    /// The number of words of the mnemonic to recover was selected.
+    MnemonicLength(usize),
+    /// The BIP39 passphrase the seed of the mnemonic is derived with was edited, or its
+    /// confirmation.
+    MnemonicPassphrase(String),
+    MnemonicPassphraseConfirm(String),
+    /// Derive the seed of the hot signer with the confirmed passphrase.
+    ApplyPassphrase,
     ImportMnemonic(bool),
 }
 
@@ -39,18 +78,65 @@ pub enum Message {
 pub enum DefineBitcoind {
     CookiePathEdited(String),
     AddressEdited(String),
-    PingBitcoindResult(Result<(), Error>),
+    /// Authenticate with the `rpcuser` and `rpcpassword` of bitcoind instead of its cookie file.
+    RpcAuthToggled(bool),
+    RpcUserEdited(String),
+    RpcPasswordEdited(String),
+    PingBitcoindResult(Result<BitcoindPing, Error>),
     PingBitcoind,
 }
 
+#[derive(Debug, Clone)]
+pub enum DefineCoordinator {
+    UrlEdited(String),
+    UserEdited(String),
+    PasswordEdited(String),
+    CheckConnection,
+    Checked(Result<(), Error>),
+}
+
+#[derive(Debug, Clone)]
+pub enum DefineTor {
+    Enabled(bool),
+    AddressEdited(String),
+    CookiePathEdited(String),
+    PasswordEdited(String),
+    OnionPortEdited(String),
+    CheckControlPort,
+    /// The version of Tor, if the control port accepted the authentication.
+    Checked(Result<String, Error>),
+}
+
 #[derive(Debug, Clone)]
 pub enum DefineDescriptor {
     ImportDescriptor(String),
+    PsbtEdited(String),
+    PsbtXpubsEdited(String),
+    ImportFromPsbt,
     PrimaryPath(DefinePath),
     RecoveryPath(usize, DefinePath),
     AddRecoveryPath,
+    /// Add a recovery path with a key of the hot signer.
+    AddHotRecoveryPath,
     KeyModal(ImportKeyModal),
     SequenceModal(SequenceModal),
+    /// The alias map of the keys, or the path of its file, was edited.
+    KeyAliasesEdited(String),
+    /// Compare the descriptor with the one using a key of the hot signer as primary key.
+    CompareHotPrimaryKey,
+    /// Use the key of the hot signer as primary key, or the one of the signing device back.
+    UseHotPrimaryKey(bool),
+    /// Import the descriptor from a wallet of bitcoind.
+    CoreWallet(CoreWallet),
+}
+
+#[derive(Debug, Clone)]
+pub enum CoreWallet {
+    AddressEdited(String),
+    CookiePathEdited(String),
+    WalletEdited(String),
+    ListDescriptors,
+    Descriptors(Result<Vec<String>, Error>),
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -85,3 +171,73 @@ pub enum SequenceModal {
     SequenceEdited(String),
     ConfirmSequence,
 }
+
+#[derive(Debug, Clone)]
+pub enum BackupDescriptor {
+    SplitToggled(bool),
+    ChecksumToggled(bool),
+    /// Save the exported descriptor to a file, and read it back to verify it.
+    BackupPathEdited(String),
+    SaveBackup,
+    BackupSaved(Result<PathBuf, Error>),
+    VerifyBackup,
+    BackupVerified(Result<(), String>),
+}
+
+#[derive(Debug, Clone)]
+pub enum RegisterDescriptor {
+    FormatSelected(Fingerprint, RegistrationFormat),
+    /// Copy the registration file of the cosigner device to the clipboard.
+    ExportArtifact(Fingerprint),
+    /// The connected devices, with the errors met while enumerating them.
+    HardwareWalletsEnumerated(Vec<HardwareWallet>, Vec<String>),
+    /// Enter by hand the key reported by a device that could not be detected.
+    ManualEntry(bool),
+    ManualKeyEdited(String),
+    /// The descriptor was registered by file on the device entered by hand.
+    ManuallyRegistered(Fingerprint),
+    /// The signatures of the cosigners confirming they registered the descriptor.
+    AttestationsEdited(String),
+    ImportAttestations,
+}
+
+#[derive(Debug, Clone)]
+pub enum Final {
+    /// Display the summary of the settings to be confirmed before the install, or leave it.
+    Review(bool),
+    CheckDescriptor,
+    DescriptorChecked(Result<Vec<NodeDescriptorInfo>, Error>),
+    ImportChangeToggled(bool),
+    ReceiveRangeEdited(String),
+    ChangeRangeEdited(String),
+    ChecklistToggled(ChecklistItem, bool),
+    ExternalDaemonToggled(bool),
+    /// Export the first addresses of the descriptor to a file.
+    AddressExportCountEdited(String),
+    AddressExportBranchSelected(AddressBranch),
+    AddressExportPathEdited(String),
+    ExportAddresses,
+    AddressesExported(Result<PathBuf, Error>),
+    /// Rescan the chain for the coins of the descriptor, and wait for it to complete the
+    /// install or let it run in the background.
+    RescanToggled(bool),
+    RescanWaitToggled(bool),
+    /// The year, month or day of the date the rescan starts at was edited.
+    RescanDateEdited(&'static str, String),
+    /// The first block rescanned, found for the date of the given timestamp.
+    RescanStartFound(u32, Result<RescanStart, Error>),
+    PollRescan,
+    RescanProgress(Result<Option<f64>, Error>),
+    DaemonRpcPathEdited(String),
+    /// Show the export of the generated configuration to a remote server, or hide it.
+    RemoteExportToggled(bool),
+    RemoteHostEdited(String),
+    RemoteDirectoryEdited(String),
+    RemoteDescriptorImportToggled(bool),
+    CheckRemote,
+    ExportToRemote,
+    /// The result of the connection check or of the export, with the message of its success.
+    RemoteDone(Result<&'static str, Error>),
+    /// The name of another wallet to set up once this one is installed was edited.
+    AnotherWalletNameEdited(String),
+}
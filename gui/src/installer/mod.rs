@@ -1,38 +1,46 @@
 mod context;
 mod message;
 mod prompt;
+mod rollback;
 mod step;
 mod view;
 
 use iced::{clipboard, Command, Subscription};
-use liana::miniscript::bitcoin;
+use liana::{miniscript::bitcoin, signer::MNEMONICS_FOLDER_NAME};
 use liana_ui::widget::Element;
-use tracing::{error, info, warn};
+use tracing::{info, warn};
 
-use context::Context;
+use context::{AcknowledgedWarnings, Context, ACKNOWLEDGED_WARNINGS_FILE_NAME};
+use rollback::{rollback, InstallRecord};
 
 use std::io::Write;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
 use crate::{
-    app::{config as gui_config, settings as gui_settings},
+    app::{config as gui_config, settings as gui_settings, signer_map},
     signer::Signer,
 };
 
 pub use message::Message;
+pub use step::republish_onion_service;
 use step::{
-    BackupDescriptor, BackupMnemonic, DefineBitcoind, DefineDescriptor, Final, ImportDescriptor,
-    ParticipateXpub, RecoverMnemonic, RegisterDescriptor, Step, Welcome,
+    publish_onion_service, BackupDescriptor, BackupMnemonic, DefineBitcoind, DefineCoordinator,
+    DefineDescriptor, DefineTor, Final, ImportDescriptor, ParticipateXpub, RecoverMnemonic,
+    RegisterDescriptor, Step, SummarySection, VerifyDescriptor, Welcome,
 };
 
 pub struct Installer {
     current: usize,
     steps: Vec<Box<dyn Step>>,
     signer: Arc<Mutex<Signer>>,
+    /// What the last install created, for it to be rolled back if it failed.
+    install_record: Arc<Mutex<InstallRecord>>,
 
     /// Context is data passed through each step.
     context: Context,
+    /// Whether the window has the focus, the subscriptions of the steps are paused otherwise.
+    focused: bool,
 }
 
 impl Installer {
@@ -56,19 +64,44 @@ impl Installer {
         destination_path: PathBuf,
         network: bitcoin::Network,
     ) -> (Installer, Command<Message>) {
+        let mut context = Context::new(network, destination_path);
+        // The warnings acknowledged for a wallet installed before are not asked again.
+        context.acknowledged_warnings = Arc::new(Mutex::new(AcknowledgedWarnings::from_data_dir(
+            &context.data_dir,
+        )));
         (
             Installer {
                 current: 0,
                 steps: vec![Welcome::default().into()],
-                context: Context::new(network, destination_path),
+                context,
                 signer: Arc::new(Mutex::new(Signer::generate(network).unwrap())),
+                install_record: Arc::new(Mutex::new(InstallRecord::default())),
+                focused: true,
             },
             Command::none(),
         )
     }
 
     pub fn subscription(&self) -> Subscription<Message> {
-        Subscription::none()
+        Subscription::batch(vec![
+            if self.focused {
+                self.steps
+                    .get(self.current)
+                    .expect("There is always a step")
+                    .subscription()
+            } else {
+                Subscription::none()
+            },
+            iced_native::subscription::events_with(|event, _status| match event {
+                iced::Event::Window(iced_native::window::Event::Focused) => {
+                    Some(Message::WindowFocused(true))
+                }
+                iced::Event::Window(iced_native::window::Event::Unfocused) => {
+                    Some(Message::WindowFocused(false))
+                }
+                _ => None,
+            }),
+        ])
     }
 
     pub fn stop(&mut self) {}
@@ -104,42 +137,64 @@ impl Installer {
         Command::none()
     }
 
+    /// The steps of the flow, without the definition of bitcoind once a wallet of the session
+    /// was installed: the other wallets use the same one.
+    fn set_steps(&mut self, steps: Vec<Box<dyn Step>>) {
+        self.steps = steps;
+        if !self.context.installed_wallets.is_empty() {
+            self.steps
+                .retain(|step| step.summary_section() != Some(SummarySection::Bitcoind));
+        }
+    }
+
     pub fn update(&mut self, message: Message) -> Command<Message> {
-        let hot_signer_fingerprint = self.signer.lock().unwrap().fingerprint();
         match message {
             Message::CreateWallet => {
-                self.steps = vec![
+                self.set_steps(vec![
                     Welcome::default().into(),
                     DefineDescriptor::new(self.signer.clone()).into(),
-                    BackupMnemonic::new(self.signer.clone()).into(),
-                    BackupDescriptor::default().into(),
-                    RegisterDescriptor::default().into(),
+                    BackupMnemonic::new(self.signer.clone(), true).into(),
+                    BackupDescriptor::new().into(),
+                    RegisterDescriptor::new(self.signer.clone()).into(),
                     DefineBitcoind::new().into(),
-                    Final::new(hot_signer_fingerprint).into(),
-                ];
+                    DefineCoordinator::new().into(),
+                    DefineTor::new().into(),
+                    Final::new(self.signer.clone()).into(),
+                ]);
                 self.next()
             }
             Message::ParticipateWallet => {
-                self.steps = vec![
+                self.set_steps(vec![
                     Welcome::default().into(),
                     ParticipateXpub::new(self.signer.clone()).into(),
                     ImportDescriptor::new(false).into(),
-                    BackupMnemonic::new(self.signer.clone()).into(),
-                    BackupDescriptor::default().into(),
-                    RegisterDescriptor::default().into(),
+                    BackupMnemonic::new(self.signer.clone(), false).into(),
+                    BackupDescriptor::new().into(),
+                    RegisterDescriptor::new(self.signer.clone()).into(),
                     DefineBitcoind::new().into(),
-                    Final::new(hot_signer_fingerprint).into(),
-                ];
+                    DefineCoordinator::new().into(),
+                    DefineTor::new().into(),
+                    Final::new(self.signer.clone()).into(),
+                ]);
                 self.next()
             }
             Message::ImportWallet => {
-                self.steps = vec![
+                self.set_steps(vec![
                     Welcome::default().into(),
                     ImportDescriptor::new(true).into(),
                     RecoverMnemonic::default().into(),
-                    RegisterDescriptor::default().into(),
+                    RegisterDescriptor::new(self.signer.clone()).into(),
                     DefineBitcoind::new().into(),
-                    Final::new(hot_signer_fingerprint).into(),
+                    DefineCoordinator::new().into(),
+                    DefineTor::new().into(),
+                    Final::new(self.signer.clone()).into(),
+                ]);
+                self.next()
+            }
+            Message::VerifyDescriptor => {
+                self.steps = vec![
+                    Welcome::default().into(),
+                    VerifyDescriptor::default().into(),
                 ];
                 self.next()
             }
@@ -149,40 +204,107 @@ impl Installer {
                 self.previous();
                 Command::none()
             }
+            Message::WindowFocused(focused) => {
+                let resumed = focused && !self.focused;
+                self.focused = focused;
+                if resumed {
+                    self.steps
+                        .get_mut(self.current)
+                        .expect("There is always a step")
+                        .resume()
+                } else {
+                    Command::none()
+                }
+            }
+            Message::Reset => {
+                let current_step = self
+                    .steps
+                    .get_mut(self.current)
+                    .expect("There is always a step");
+                current_step.reset();
+                current_step.load_context(&self.context);
+                current_step.load()
+            }
+            Message::EditStep(section) => {
+                if let Some(i) = self.steps.iter().position(|step| {
+                    step.summary_section() == Some(section) && !step.skip(&self.context)
+                }) {
+                    self.current = i;
+                    return self
+                        .steps
+                        .get(self.current)
+                        .expect("There is always a step")
+                        .load();
+                }
+                Command::none()
+            }
             Message::Install => {
-                self.steps
+                let current_step = self
+                    .steps
                     .get_mut(self.current)
-                    .expect("There is always a step")
-                    .update(message);
+                    .expect("There is always a step");
+                // The final step may carry some installation options.
+                if !current_step.apply(&mut self.context) {
+                    return Command::none();
+                }
+                current_step.update(message);
+                *self.install_record.lock().unwrap() = InstallRecord::default();
                 Command::perform(
-                    install(self.context.clone(), self.signer.clone()),
+                    install(
+                        self.context.clone(),
+                        self.signer.clone(),
+                        self.install_record.clone(),
+                    ),
                     Message::Installed,
                 )
             }
+            Message::Installed(Ok(path)) => {
+                self.context.record_installed_wallet(path.clone());
+                self.steps
+                    .get_mut(self.current)
+                    .expect("There is always a step")
+                    .update(Message::Installed(Ok(path)))
+            }
+            Message::SetUpAnotherWallet(name) => match self.context.next_wallet(&name) {
+                Ok(ctx) => {
+                    info!("Setting up another wallet: {}", ctx.wallet_name);
+                    // The new wallet has its own hot signer, and starts from the choice of its
+                    // flow.
+                    self.signer = Arc::new(Mutex::new(
+                        Signer::generate(ctx.bitcoin_config.network).unwrap(),
+                    ));
+                    self.context = ctx;
+                    self.steps = vec![Welcome::default().into()];
+                    self.current = 0;
+                    Command::none()
+                }
+                Err(e) => {
+                    warn!("Cannot set up another wallet named '{}': {}", name, e);
+                    Command::none()
+                }
+            },
             Message::Installed(Err(e)) => {
-                let mut data_dir = self.context.data_dir.clone();
-                data_dir.push(self.context.bitcoin_config.network.to_string());
-                // In case of failure during install, block the thread to
-                // deleted the data_dir/network directory in order to start clean again.
-                warn!("Installation failed. Cleaning up the leftover data directory.");
-                if let Err(e) = std::fs::remove_dir_all(&data_dir) {
-                    error!(
-                        "Failed to completely delete the data directory (path: '{}'): {}",
-                        data_dir.to_string_lossy(),
-                        e
-                    );
-                } else {
-                    warn!(
-                        "Successfully deleted data directory at '{}'.",
-                        data_dir.to_string_lossy()
-                    );
-                };
+                warn!("Installation failed: {}", e);
                 self.steps
                     .get_mut(self.current)
                     .expect("There is always a step")
                     .update(Message::Installed(Err(e)));
                 Command::none()
             }
+            Message::CleanUpInstall => {
+                warn!("Rolling back the failed installation.");
+                self.steps
+                    .get_mut(self.current)
+                    .expect("There is always a step")
+                    .update(message);
+                Command::perform(
+                    rollback(
+                        self.install_record.clone(),
+                        self.context.bitcoind_config.clone(),
+                    ),
+                    Message::InstallCleanedUp,
+                )
+            }
             _ => self
                 .steps
                 .get_mut(self.current)
@@ -216,12 +338,41 @@ impl Installer {
     }
 }
 
-pub fn daemon_check(cfg: liana::config::Config) -> Result<(), Error> {
-    // Start Daemon to check correctness of installation
+/// The name of the watchonly wallet the daemon creates on bitcoind, in its network data
+/// directory.
+pub const WATCHONLY_WALLET_NAME: &str = "lianad_watchonly_wallet";
+
+/// The path of the watchonly wallet of the daemon, as given to bitcoind. On Windows the daemon
+/// stores it in the data directory of bitcoind, by name.
+pub fn watchonly_wallet_path(network_datadir: &std::path::Path) -> String {
+    if cfg!(windows) {
+        WATCHONLY_WALLET_NAME.to_string()
+    } else {
+        network_datadir
+            .join(WATCHONLY_WALLET_NAME)
+            .to_string_lossy()
+            .to_string()
+    }
+}
+
+/// Start the daemon to check the correctness of the installation, triggering the rescan of the
+/// chain from the given timestamp if any. The rescan goes on in bitcoind once the daemon is
+/// stopped.
+pub fn daemon_check(
+    cfg: liana::config::Config,
+    rescan_timestamp: Option<u32>,
+) -> Result<(), Error> {
     match liana::DaemonHandle::start_default(cfg) {
         Ok(daemon) => {
+            let res = rescan_timestamp
+                .map(|timestamp| {
+                    daemon.control.start_rescan(timestamp).map_err(|e| {
+                        Error::Unexpected(format!("Failed to start the rescan: {}", e))
+                    })
+                })
+                .unwrap_or(Ok(()));
             daemon.shutdown();
-            Ok(())
+            res
         }
         Err(e) => Err(Error::Unexpected(format!(
             "Failed to start Liana daemon: {}",
@@ -230,28 +381,41 @@ pub fn daemon_check(cfg: liana::config::Config) -> Result<(), Error> {
     }
 }
 
-pub async fn install(ctx: Context, signer: Arc<Mutex<Signer>>) -> Result<PathBuf, Error> {
+pub async fn install(
+    ctx: Context,
+    signer: Arc<Mutex<Signer>>,
+    record: Arc<Mutex<InstallRecord>>,
+) -> Result<PathBuf, Error> {
+    let mut record = record.lock().unwrap();
     let mut cfg: liana::config::Config = ctx.extract_daemon_config();
     let data_dir = cfg.data_dir.unwrap();
 
+    // The data directory of a wallet set up after the first one of the session does not exist
+    // yet.
+    record.track_directory(&data_dir);
+    create_datadir(&data_dir).map_err(|e| Error::CannotCreateDatadir(e.to_string()))?;
+
     let data_dir = data_dir
         .canonicalize()
         .map_err(|e| Error::Unexpected(format!("Failed to canonicalize datadir path: {}", e)))?;
     cfg.data_dir = Some(data_dir.clone());
 
-    daemon_check(cfg.clone())?;
-
-    info!("daemon checked");
-
     let mut network_datadir_path = data_dir;
     network_datadir_path.push(cfg.bitcoin_config.network.to_string());
 
+    // The daemon creates its network data directory and its watchonly wallet.
+    record.track_directory(&network_datadir_path);
+    record.track_watchonly_wallet(&network_datadir_path);
+    daemon_check(cfg.clone(), ctx.rescan.map(|rescan| rescan.timestamp))?;
+
+    info!("daemon checked");
+
     // Step needed because of ValueAfterTable error in the toml serialize implementation.
     let daemon_config = toml::Value::try_from(&cfg)
         .map_err(|e| Error::Unexpected(format!("Failed to serialize daemon config: {}", e)))?;
 
     // create lianad configuration file
-    let daemon_config_path = create_and_write_file(
+    let daemon_config_path = record.create_file(
         network_datadir_path.clone(),
         "daemon.toml",
         daemon_config.to_string().as_bytes(),
@@ -259,43 +423,71 @@ pub async fn install(ctx: Context, signer: Arc<Mutex<Signer>>) -> Result<PathBuf
 
     info!("Daemon configuration file created");
 
+    let mnemonics_folder = network_datadir_path.join(MNEMONICS_FOLDER_NAME);
+    let mut hot_signers = Vec::new();
     if cfg
         .main_descriptor
         .to_string()
         .contains(&signer.lock().unwrap().fingerprint().to_string())
     {
-        signer
-            .lock()
-            .unwrap()
-            .store(
-                &cfg.data_dir().expect("Already checked"),
-                cfg.bitcoin_config.network,
-            )
+        record
+            .track_new_files(&mnemonics_folder, || {
+                signer.lock().unwrap().store(
+                    &cfg.data_dir().expect("Already checked"),
+                    cfg.bitcoin_config.network,
+                )
+            })
             .map_err(|e| Error::Unexpected(format!("Failed to store mnemonic: {}", e)))?;
 
+        hot_signers.push(signer.lock().unwrap().fingerprint());
         info!("Hot signer mnemonic stored");
     }
 
     if let Some(signer) = &ctx.recovered_signer {
-        signer
-            .store(
-                &cfg.data_dir().expect("Already checked"),
-                cfg.bitcoin_config.network,
-            )
+        record
+            .track_new_files(&mnemonics_folder, || {
+                signer.store(
+                    &cfg.data_dir().expect("Already checked"),
+                    cfg.bitcoin_config.network,
+                )
+            })
             .map_err(|e| Error::Unexpected(format!("Failed to store mnemonic: {}", e)))?;
 
+        hot_signers.push(signer.fingerprint());
         info!("Recovered signer mnemonic stored");
     }
 
+    // The onion service forwards to the RPC socket of the daemon, published for the key of the
+    // service to be stored in the GUI configuration.
+    let tor = match ctx.tor.clone() {
+        Some(tor) => {
+            let rpc_socket = ctx
+                .daemon_rpc_path
+                .clone()
+                .unwrap_or_else(|| network_datadir_path.join("lianad_rpc"));
+            let tor = publish_onion_service(tor, &rpc_socket)?;
+            record.track_onion_service(tor.clone());
+            info!(
+                "Onion service {} published",
+                tor.onion_address.as_deref().unwrap_or_default()
+            );
+            Some(tor)
+        }
+        None => None,
+    };
+
     // create liana GUI configuration file
-    let gui_config_path = create_and_write_file(
+    let gui_config_path = record.create_file(
         network_datadir_path.clone(),
         gui_config::DEFAULT_FILE_NAME,
-        toml::to_string(&gui_config::Config::new(
-            daemon_config_path.canonicalize().map_err(|e| {
+        toml::to_string(&gui_config::Config {
+            daemon_rpc_path: ctx.daemon_rpc_path.clone(),
+            coordinator: ctx.coordinator.clone(),
+            tor,
+            ..gui_config::Config::new(daemon_config_path.canonicalize().map_err(|e| {
                 Error::Unexpected(format!("Failed to canonicalize daemon config path: {}", e))
-            })?,
-        ))
+            })?)
+        })
         .map_err(|e| Error::Unexpected(format!("Failed to serialize gui config: {}", e)))?
         .as_bytes(),
     )?;
@@ -304,8 +496,8 @@ pub async fn install(ctx: Context, signer: Arc<Mutex<Signer>>) -> Result<PathBuf
 
     // create liana GUI settings file
     let settings: gui_settings::Settings = ctx.extract_gui_settings();
-    create_and_write_file(
-        network_datadir_path,
+    record.create_file(
+        network_datadir_path.clone(),
         gui_settings::DEFAULT_FILE_NAME,
         serde_json::to_string_pretty(&settings)
             .map_err(|e| Error::Unexpected(format!("Failed to serialize settings: {}", e)))?
@@ -314,9 +506,46 @@ pub async fn install(ctx: Context, signer: Arc<Mutex<Signer>>) -> Result<PathBuf
 
     info!("Settings file created");
 
+    // create the map of the signers of the descriptor, read by the GUI to display them
+    record.create_file(
+        network_datadir_path,
+        signer_map::DEFAULT_FILE_NAME,
+        serde_json::to_string_pretty(&ctx.extract_signer_map(&hot_signers))
+            .map_err(|e| Error::Unexpected(format!("Failed to serialize signer map: {}", e)))?
+            .as_bytes(),
+    )?;
+
+    info!("Signer map file created");
+
+    // The acknowledged warnings are only stored once everything else is, for a failed install
+    // not to leave them behind.
+    let acknowledged_warnings = ctx.acknowledged_warnings.lock().unwrap().to_store();
+    if let Some((data_dir, content)) = acknowledged_warnings {
+        if let Err(e) = record.create_file(data_dir, ACKNOWLEDGED_WARNINGS_FILE_NAME, &content) {
+            warn!("Failed to store the acknowledged warnings: {}", e);
+        }
+    }
+
+    // The install succeeded, there is nothing to roll back.
+    *record = InstallRecord::default();
     Ok(gui_config_path)
 }
 
+/// Create the data directory, readable by the user only.
+fn create_datadir(path: &std::path::Path) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::fs::DirBuilder;
+        use std::os::unix::fs::DirBuilderExt;
+        DirBuilder::new().mode(0o700).recursive(true).create(path)
+    }
+
+    #[cfg(not(unix))]
+    std::fs::create_dir_all(path)
+}
+
+/// Create the file, readable by the user only: the configuration files hold the credentials of
+/// bitcoind, of the coordinator and of the control port of Tor, and the key of the onion service.
 pub fn create_and_write_file(
     mut network_datadir: PathBuf,
     file_name: &str,
@@ -324,8 +553,23 @@ pub fn create_and_write_file(
 ) -> Result<PathBuf, Error> {
     network_datadir.push(file_name);
     let path = network_datadir;
-    let mut file =
-        std::fs::File::create(&path).map_err(|e| Error::CannotCreateFile(e.to_string()))?;
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(&path)
+        .map_err(|e| Error::CannotCreateFile(e.to_string()))?;
+    // The mode is only set on creation, an overwritten file may be readable by others.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))
+            .map_err(|e| Error::CannotCreateFile(e.to_string()))?;
+    }
     file.write_all(data)
         .map_err(|e| Error::CannotWriteToFile(e.to_string()))?;
     Ok(path)
@@ -334,11 +578,30 @@ pub fn create_and_write_file(
 #[derive(Debug, Clone)]
 pub enum Error {
     Bitcoind(String),
+    /// The cookie file of bitcoind could not be read.
+    BitcoindCookieUnreadable(String),
+    /// bitcoind refused the cookie or the user and password (HTTP 401).
+    BitcoindUnauthorized,
+    /// bitcoind runs on another network than the wallet.
+    BitcoindNetworkMismatch(String),
+    /// bitcoind runs with `disablewallet=1`.
+    BitcoindWalletDisabled,
+    /// bitcoind at the given non-loopback address refused the connection or forbade the RPC
+    /// access, as it does without `rpcbind` and `rpcallowip`.
+    BitcoindRemoteRpcRefused(String),
+    /// bitcoind has more RPC requests queued than its `rpcworkqueue`.
+    BitcoindWorkQueueExceeded,
     CannotCreateDatadir(String),
     CannotCreateFile(String),
     CannotWriteToFile(String),
     Unexpected(String),
     HardwareWallet(async_hwi::Error),
+    /// The export of the configuration to a remote server failed.
+    RemoteExport(String),
+    /// The coordination server could not be reached or refused the credentials.
+    Coordinator(String),
+    /// The control port of Tor could not be reached or refused a command.
+    Tor(String),
 }
 
 impl From<jsonrpc::simple_http::Error> for Error {
@@ -347,9 +610,32 @@ impl From<jsonrpc::simple_http::Error> for Error {
     }
 }
 
+/// The HTTP status of an error response of bitcoind without a JSON-RPC body.
+fn http_error_code(error: &jsonrpc::Error) -> Option<u16> {
+    match error {
+        jsonrpc::Error::Transport(e) => match e.downcast_ref::<jsonrpc::simple_http::Error>() {
+            Some(jsonrpc::simple_http::Error::HttpErrorCode(code)) => Some(*code),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// bitcoind answers with a plain text HTTP 503 (Service Unavailable) error instead of a JSON-RPC
+/// response when its queue of RPC requests is full. A HTTP 500 is an ordinary JSON-RPC error.
+fn is_work_queue_exceeded(error: &jsonrpc::Error) -> bool {
+    http_error_code(error) == Some(503)
+}
+
 impl From<jsonrpc::Error> for Error {
     fn from(error: jsonrpc::Error) -> Self {
-        Error::Bitcoind(error.to_string())
+        if is_work_queue_exceeded(&error) {
+            Error::BitcoindWorkQueueExceeded
+        } else if http_error_code(&error) == Some(401) {
+            Error::BitcoindUnauthorized
+        } else {
+            Error::Bitcoind(error.to_string())
+        }
     }
 }
 
@@ -363,11 +649,33 @@ impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             Self::Bitcoind(e) => write!(f, "Failed to ping bitcoind: {}", e),
+            Self::BitcoindCookieUnreadable(e) => write!(f, "Failed to read cookie file: {}", e),
+            Self::BitcoindUnauthorized => write!(
+                f,
+                "bitcoind refused the authentication: check the cookie file, or the user and password."
+            ),
+            Self::BitcoindNetworkMismatch(e) => write!(f, "Wrong network: {}", e),
+            Self::BitcoindWalletDisabled => write!(
+                f,
+                "The wallet of bitcoind is disabled. Liana needs it: restart bitcoind without the 'disablewallet=1' option."
+            ),
+            Self::BitcoindRemoteRpcRefused(addr) => write!(
+                f,
+                "bitcoind at {} refused the RPC connection of this computer.",
+                addr
+            ),
+            Self::BitcoindWorkQueueExceeded => write!(
+                f,
+                "bitcoind is overloaded: its queue of RPC requests is full."
+            ),
             Self::CannotCreateDatadir(e) => write!(f, "Failed to create datadir: {}", e),
             Self::CannotWriteToFile(e) => write!(f, "Failed to write to file: {}", e),
             Self::CannotCreateFile(e) => write!(f, "Failed to create file: {}", e),
             Self::Unexpected(e) => write!(f, "Unexpected: {}", e),
             Self::HardwareWallet(e) => write!(f, "Hardware Wallet: {}", e),
+            Self::RemoteExport(e) => write!(f, "Remote server: {}", e),
+            Self::Coordinator(e) => write!(f, "Coordinator: {}", e),
+            Self::Tor(e) => write!(f, "Tor: {}", e),
         }
     }
 }
@@ -7,5 +7,39 @@ pub const DEFINE_DESCRIPTOR_RECOVERY_PATH_TOOLTIP: &str =
 pub const DEFINE_DESCRIPTOR_FINGERPRINT_TOOLTIP: &str =
     "The alias is applied on all the keys derived from the same seed";
 pub const REGISTER_DESCRIPTOR_HELP: &str = "To be used with the wallet, a device needs the descriptor. If the descriptor contains one or more keys imported from an external signing device, the descriptor must be registered on it. Registration confirms that the device is able to handle the policy. Registration on a device is not a substitute for backing up the descriptor.";
+pub const DEVICE_ENUMERATION_FAILED_HELP: &str = "The signing devices could not be listed. On Linux, make sure the udev rules of your device are installed and that your user is a member of the plugdev group. On Windows, check that no other application, such as the device companion app, is using the device. Unplugging and plugging the device back may also help. If the device still cannot be detected, enter the key it reports by hand and register the descriptor with a file.";
+pub const MANUAL_REGISTRATION_HELP: &str = "Enter the fingerprint or the extended public key displayed by the device. Once it matches a key of the descriptor, export the registration file of this device from the list of cosigners above, import it on the device and confirm the registration here.";
+pub const INSTALL_SUMMARY_HELP: &str = "Review the settings of your wallet before installing it. Each section can be edited by going back to the step it was set in.";
+pub const MAINNET_INSTALL_WARNING: &str = "This wallet is installed on the Bitcoin mainnet: the coins it receives are real. Double check the spending policy and the signers before confirming.";
+pub const REMOTE_EXPORT_HELP: &str = "For the daemon to run on a headless server, the generated configuration can be copied to it with sftp. The connection must not need any interaction: it uses your ssh keys or agent, no password is asked. The paths in the configuration are the ones of this computer, adapt them on the server. The mnemonics of the hot keys are never exported.";
+pub const FIRST_ADDRESSES_HELP: &str = "The first addresses of the wallet at index 0. Compare them with the ones displayed by your signing devices or your node before funding the wallet.";
+pub const INSTALL_CLEANUP_HELP: &str = "The installation failed. Clean up what it created before trying again: the configuration files, the stored mnemonics and the watchonly wallet on bitcoind. Nothing which existed before the installation is removed, and the files it overwrote are restored.";
+pub const RESCAN_HELP: &str = "When importing a wallet which already received coins, the chain must be rescanned for them. The rescan takes from minutes to hours depending on your node. The wallet only displays all its coins once it is over. Starting at the date the wallet was created, or slightly before, avoids rescanning the blocks it cannot have coins in.";
+pub const BACKUP_FILE_HELP: &str = "Save the descriptor to a file, for instance on a USB stick, then verify the backup: the file is read back and must give the descriptor of the wallet, with the same checksum.";
+pub const ADDRESS_EXPORT_HELP: &str = "Export the first addresses of the wallet to a CSV file, to compare them in bulk with the ones of another wallet or a block explorer before funding it.";
+pub const COORDINATOR_HELP: &str = "The participants of a multisig wallet can share the descriptor and the transactions to sign through a coordination server. Enter its address and, if it asks for one, the authentication. Skip this step if you coordinate without a server. The connection is not encrypted: only use a server of your local network or reached through a tunnel.";
+pub const TOR_HELP: &str = "Advanced: the RPC interface of the Liana daemon can be published as an onion service, for the wallet to be reached remotely through Tor. Enter the control port of a Tor daemon running on this computer and its authentication: the cookie file or the password, if any. Only an authorized client can reach the service: its key is displayed once the installation is done. Tor forgets the service when it restarts: Liana publishes it again each time it starts.";
+pub const TOR_CLIENT_AUTH_HELP: &str = "The onion service only accepts the client with this key. On the computer reaching the daemon, save this line in a file ending with .auth_private in the ClientOnionAuthDir of Tor. It is not stored by Liana: keep it now, and never share it.";
+pub const ATTESTATION_HELP: &str = "Before funding the wallet, make sure every cosigner can use it. Each cosigner confirms the descriptor is registered on their device by signing this message with the \"sign message\" feature of their device or wallet, with the key at the given path, and sends you the signature.";
 pub const MNEMONIC_HELP: &str = "A hot key generated on this computer was used for creating this wallet. It needs to be backed up. \n Keep it in a safe place. Never share it with anyone.";
 pub const RECOVER_MNEMONIC_HELP: &str = "If you were using a hot key (a key stored on the computer) in your wallet, you will need to recover it from mnemonics to be able to sign transactions again. Otherwise you can directly go the next step.";
+pub const MNEMONIC_PASSPHRASE_HELP: &str = "Optionally, protect the mnemonic with a BIP39 passphrase: the key is derived from both, so the words alone are not enough to spend. Back up the passphrase separately from the words, it cannot be recovered. It is not stored on this computer: you will enter it each time this key signs a transaction.";
+pub const RECOVER_PASSPHRASE_HELP: &str =
+    "If the key was derived with a BIP39 passphrase, enter it. Leave it empty otherwise. It is not stored: you will enter it each time this key signs a transaction.";
+pub const RECOVER_MNEMONIC_PASTE_HELP: &str = "The whole mnemonic can be pasted in the first word, even with its words abbreviated to their first four letters.";
+pub const INCONSISTENT_NODE_WARNING: &str = "Successive requests to this address returned different chain tips. It may be a load balancer in front of several nodes. Liana needs to talk to a single, consistent bitcoind: the wallet may behave erratically otherwise.";
+pub const DESCRIPTOR_COPY_WARNING: &str = "The clipboard can be read by the other applications of this computer. The descriptor does not allow to spend your coins but reveals all the addresses of the wallet.";
+pub const HOT_RECOVERY_KEY_WARNING: &str = "A recovery key is stored on this computer. Once the timelock of its path expires, anyone gaining access to this computer can use it: if the path requires no other signature, they can spend alone the coins not refreshed in time. Keep the timelock long, refresh the coins before it expires and back up the mnemonic of this computer for the heirs.";
+pub const NON_STANDARD_WITNESS_WARNING: &str = "Spending through the paths in red would exceed the limits of standard transactions: such spends would not be relayed by the Bitcoin network and would have to be submitted to a miner directly.";
+pub const SUPPORT_SUMMARY_HELP: &str = "The host, the cookie path and its content are redacted.";
+pub const DESCRIPTOR_PAYLOAD_HELP: &str = "The descriptor can also be pasted as exported by a mobile companion or an air-gapped signer: BSMS record, wallet policy, Specter or Sparrow export.";
+pub const KEY_PAYLOAD_HELP: &str = "The key can also be pasted as exported by a mobile companion or an air-gapped signer, for instance the JSON file of a Coldcard or a Keystone.";
+pub const VERIFY_DESCRIPTOR_HELP: &str = "Paste a descriptor, for instance from a backup, to analyze it. Nothing is sent over the network: this does not require a Bitcoin node.";
+pub const REMOTE_RPC_TIP: &str = "By default bitcoind only accepts RPC connections from the machine it runs on. To accept the ones of this computer, start it with `rpcbind=<the address bitcoind listens on>` and `rpcallowip=<the address of this computer>`, for instance in its bitcoin.conf.";
+pub const WORK_QUEUE_TIP: &str = "bitcoind receives more RPC requests than it can handle, which is common during its initial sync or when other applications use it too. Wait for the load to decrease and check again, or restart bitcoind with higher `rpcthreads` and `rpcworkqueue` values, for instance in its bitcoin.conf.";
+pub const ADDRESS_CHANGE_WARNING: &str = "Editing a key or a spending path changes all the addresses of the wallet, not only some of them. Once the wallet received coins its descriptor must not be changed anymore: the coins received on the previous addresses would not be found.";
+pub const KEY_ALIASES_HELP: &str = "Optionally, paste the alias map of the keys or the path of its file to name the keys after their device. The aliases are not part of the descriptor.";
+pub const KEY_ALIASES_BACKUP_HELP: &str = "The names of the keys are not part of the descriptor. Save this alias map as a file alongside the descriptor backup to load them on another setup.";
+pub const HARDWARE_PRIMARY_KEY_HELP: &str = "Spending requires the signing device: the coins are safe even if this computer is compromised.";
+pub const HOT_PRIMARY_KEY_WARNING: &str = "Less secure: the key is stored on this computer, anyone gaining access to it holds the primary key without needing the signing device. More convenient to spend small amounts.";
+pub const ANOTHER_WALLET_HELP: &str = "Set up another wallet, for instance to separate your savings from your spending, on the same network and Bitcoin node. It is installed in its own folder of the data directory, named after the wallet: start Liana with this folder as --datadir to open it later.";
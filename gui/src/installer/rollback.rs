@@ -0,0 +1,398 @@
+//! Record of what an install created, for a failed install to be rolled back without touching
+//! anything which existed before it: a retry then starts from a clean state.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use liana::config::BitcoindConfig;
+use tracing::{info, warn};
+
+use super::{
+    create_and_write_file,
+    step::{bitcoind_client, remove_onion_service},
+    watchonly_wallet_path, Error, WATCHONLY_WALLET_NAME,
+};
+use crate::app::config::TorConfig;
+
+/// The error code of bitcoind for a wallet which is not loaded.
+const RPC_WALLET_NOT_FOUND: i32 = -18;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct InstallRecord {
+    /// The directories created, removed with all their content.
+    directories: Vec<PathBuf>,
+    files: Vec<PathBuf>,
+    /// The files overwritten, with their previous content and permissions to be restored.
+    overwritten: Vec<(PathBuf, Vec<u8>, std::fs::Permissions)>,
+    /// The watchonly wallet the daemon may have created on bitcoind, as given to `loadwallet`.
+    watchonly_wallet: Option<String>,
+    /// The onion service published on the control port of Tor.
+    onion_service: Option<TorConfig>,
+}
+
+impl InstallRecord {
+    pub fn is_empty(&self) -> bool {
+        self.directories.is_empty()
+            && self.files.is_empty()
+            && self.overwritten.is_empty()
+            && self.watchonly_wallet.is_none()
+            && self.onion_service.is_none()
+    }
+
+    /// Record the directory if it does not exist yet, before it is created.
+    pub fn track_directory(&mut self, dir: &Path) {
+        if !dir.exists() {
+            self.directories.push(dir.to_path_buf());
+        }
+    }
+
+    /// Record the watchonly wallet of the daemon for the given network data directory, if it
+    /// was not created by a previous install.
+    pub fn track_watchonly_wallet(&mut self, network_datadir: &Path) {
+        let path = network_datadir.join(WATCHONLY_WALLET_NAME);
+        if path.exists() {
+            return;
+        }
+        self.watchonly_wallet = Some(watchonly_wallet_path(network_datadir));
+        // On Windows the wallet is in the data directory of bitcoind. Its files are then left to
+        // the daemon, which removes them when starting fresh.
+        if !cfg!(windows) {
+            self.directories.push(path);
+        }
+    }
+
+    pub fn track_onion_service(&mut self, tor: TorConfig) {
+        self.onion_service = Some(tor);
+    }
+
+    /// Create the file, recording it if it did not exist. The content of an overwritten file is
+    /// backed up, for the rollback to restore it.
+    pub fn create_file(
+        &mut self,
+        network_datadir: PathBuf,
+        file_name: &str,
+        data: &[u8],
+    ) -> Result<PathBuf, Error> {
+        let path = network_datadir.join(file_name);
+        if path.exists() {
+            let (content, permissions) = std::fs::read(&path)
+                .and_then(|content| {
+                    std::fs::metadata(&path).map(|metadata| (content, metadata.permissions()))
+                })
+                .map_err(|e| {
+                    Error::Unexpected(format!(
+                        "Failed to back up {} before overwriting it: {}",
+                        path.to_string_lossy(),
+                        e
+                    ))
+                })?;
+            self.overwritten.push((path, content, permissions));
+        } else {
+            self.files.push(path);
+        }
+        create_and_write_file(network_datadir, file_name, data)
+    }
+
+    /// Record the entries the given function adds to the directory, and the directory itself if
+    /// it creates it.
+    pub fn track_new_files<T>(&mut self, dir: &Path, f: impl FnOnce() -> T) -> T {
+        self.track_directory(dir);
+        let before = dir_entries(dir);
+        let res = f();
+        self.files
+            .extend(dir_entries(dir).into_iter().filter(|p| !before.contains(p)));
+        res
+    }
+}
+
+fn dir_entries(dir: &Path) -> HashSet<PathBuf> {
+    std::fs::read_dir(dir)
+        .map(|entries| entries.filter_map(|e| e.ok().map(|e| e.path())).collect())
+        .unwrap_or_default()
+}
+
+/// Unload the watchonly wallet from bitcoind, remove the onion service from Tor, restore the
+/// files overwritten by the install, then remove the files and directories it created. What was
+/// undone is forgotten from the record, for a failed rollback to be retried where it stopped.
+pub async fn rollback(
+    record: Arc<Mutex<InstallRecord>>,
+    bitcoind_config: Option<BitcoindConfig>,
+) -> Result<(), Error> {
+    let mut record = record.lock().unwrap();
+    if let Some(wallet) = record.watchonly_wallet.clone() {
+        if let Some(config) = &bitcoind_config {
+            unload_wallet(config, &wallet)?;
+            info!("Watchonly wallet {} unloaded", wallet);
+        }
+        record.watchonly_wallet = None;
+    }
+
+    if let Some(tor) = record.onion_service.clone() {
+        remove_onion_service(&tor)?;
+        info!(
+            "Onion service {} removed",
+            tor.onion_address.as_deref().unwrap_or_default()
+        );
+        record.onion_service = None;
+    }
+
+    while let Some((file, content, permissions)) = record.overwritten.pop() {
+        if let Err(e) = restore(&file, &content, permissions.clone()) {
+            record.overwritten.push((file, content, permissions));
+            return Err(e);
+        }
+    }
+    while let Some(file) = record.files.pop() {
+        if let Err(e) = remove(&file, false) {
+            record.files.push(file);
+            return Err(e);
+        }
+    }
+    while let Some(dir) = record.directories.pop() {
+        if let Err(e) = remove(&dir, true) {
+            record.directories.push(dir);
+            return Err(e);
+        }
+    }
+
+    Ok(())
+}
+
+fn remove(path: &Path, is_dir: bool) -> Result<(), Error> {
+    let res = if is_dir {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    };
+    match res {
+        Ok(()) => {
+            info!("Removed {}", path.to_string_lossy());
+            Ok(())
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => {
+            warn!("Failed to remove {}: {}", path.to_string_lossy(), e);
+            Err(Error::Unexpected(format!(
+                "Failed to remove {}: {}",
+                path.to_string_lossy(),
+                e
+            )))
+        }
+    }
+}
+
+fn restore(path: &Path, content: &[u8], permissions: std::fs::Permissions) -> Result<(), Error> {
+    std::fs::write(path, content)
+        .and_then(|_| std::fs::set_permissions(path, permissions))
+        .map_err(|e| {
+            warn!("Failed to restore {}: {}", path.to_string_lossy(), e);
+            Error::Unexpected(format!(
+                "Failed to restore {}: {}",
+                path.to_string_lossy(),
+                e
+            ))
+        })?;
+    info!("Restored {}", path.to_string_lossy());
+    Ok(())
+}
+
+fn unload_wallet(config: &BitcoindConfig, wallet: &str) -> Result<(), Error> {
+    let client = bitcoind_client(config)?;
+    let res = client.send_request(client.build_request("unloadwallet", &[jsonrpc::arg(wallet)]))?;
+    match res.result::<serde_json::Value>() {
+        Ok(_) => Ok(()),
+        // The install failed before the daemon loaded it.
+        Err(jsonrpc::Error::Rpc(e)) if e.code == RPC_WALLET_NOT_FOUND => Ok(()),
+        Err(e) => Err(Error::Bitcoind(format!(
+            "Failed to unload the watchonly wallet: {}",
+            e
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "liana-gui-test-rollback-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// The number of steps of an install, for the tests to fail after each of them.
+    const INSTALL_STEPS: usize = 8;
+
+    /// A control port of Tor without authentication answering the commands of one connection,
+    /// with the onion service to be removed from it.
+    fn tor_control() -> (TorConfig, std::thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let tor = TorConfig {
+            control_address: listener.local_addr().unwrap().to_string(),
+            cookie_path: None,
+            password: None,
+            onion_port: 8080,
+            onion_address: Some("abc.onion".to_string()),
+            service_key: Some("ED25519-V3:key".to_string()),
+            client_auth_key: Some("client".to_string()),
+        };
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut stream = stream;
+            let mut commands = Vec::new();
+            for _ in 0..2 {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                commands.push(line.trim_end().to_string());
+                stream.write_all(b"250 OK\r\n").unwrap();
+            }
+            commands
+        });
+        (tor, server)
+    }
+
+    /// Run the steps of an install in the data directory, failing after the given number of
+    /// them. The onion service is published on the given control port.
+    fn partial_install(
+        record: &mut InstallRecord,
+        datadir: &Path,
+        steps: usize,
+        tor: Option<TorConfig>,
+    ) {
+        let network_datadir = datadir.join("testnet");
+        let mut actions: Vec<Box<dyn FnOnce(&mut InstallRecord) + '_>> = vec![
+            Box::new(|r| {
+                r.track_directory(&network_datadir);
+                r.track_watchonly_wallet(&network_datadir);
+                // The daemon check creates the data directory and the watchonly wallet.
+                std::fs::create_dir_all(network_datadir.join(WATCHONLY_WALLET_NAME)).unwrap();
+            }),
+            Box::new(|r| {
+                r.create_file(network_datadir.clone(), "daemon.toml", b"daemon")
+                    .unwrap();
+            }),
+            Box::new(|r| {
+                let mnemonics = network_datadir.join("mnemonics");
+                r.track_new_files(&mnemonics, || {
+                    std::fs::create_dir_all(&mnemonics).unwrap();
+                    std::fs::write(mnemonics.join("mnemonic-abcdef01.txt"), "words").unwrap();
+                });
+            }),
+            Box::new(move |r| r.track_onion_service(tor.expect("Published at this step"))),
+            Box::new(|r| {
+                r.create_file(network_datadir.clone(), "gui.toml", b"gui")
+                    .unwrap();
+            }),
+            Box::new(|r| {
+                r.create_file(network_datadir.clone(), "settings.json", b"settings")
+                    .unwrap();
+            }),
+            Box::new(|r| {
+                r.create_file(network_datadir.clone(), "signers.json", b"signers")
+                    .unwrap();
+            }),
+            Box::new(|r| {
+                r.create_file(
+                    datadir.to_path_buf(),
+                    "acknowledged_warnings.json",
+                    b"warnings",
+                )
+                .unwrap();
+            }),
+        ];
+        assert_eq!(actions.len(), INSTALL_STEPS);
+        actions.truncate(steps);
+        for action in actions {
+            action(record);
+        }
+    }
+
+    #[tokio::test]
+    async fn rollback_fresh_datadir() {
+        for steps in 1..=INSTALL_STEPS {
+            let datadir = test_dir(&format!("fresh-{}", steps));
+            let tor = (steps >= 4).then(tor_control);
+            let mut record = InstallRecord::default();
+            partial_install(
+                &mut record,
+                &datadir,
+                steps,
+                tor.as_ref().map(|(tor, _)| tor.clone()),
+            );
+            assert!(record.watchonly_wallet.is_some());
+            assert_eq!(record.onion_service.is_some(), tor.is_some());
+
+            let record = Arc::new(Mutex::new(record));
+            rollback(record.clone(), None).await.unwrap();
+            assert!(record.lock().unwrap().is_empty());
+            if let Some((_, server)) = tor {
+                assert_eq!(
+                    server.join().unwrap(),
+                    vec!["AUTHENTICATE".to_string(), "DEL_ONION abc".to_string()]
+                );
+            }
+            assert!(!datadir.join("testnet").exists());
+            assert!(!datadir.join("acknowledged_warnings.json").exists());
+            // The root data directory existed before the install.
+            assert!(datadir.exists());
+            std::fs::remove_dir_all(&datadir).unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn rollback_existing_datadir() {
+        for steps in 1..=INSTALL_STEPS {
+            let datadir = test_dir(&format!("existing-{}", steps));
+            let network_datadir = datadir.join("testnet");
+            let mnemonics = network_datadir.join("mnemonics");
+            std::fs::create_dir_all(network_datadir.join(WATCHONLY_WALLET_NAME)).unwrap();
+            std::fs::create_dir_all(&mnemonics).unwrap();
+            std::fs::write(mnemonics.join("mnemonic-01020304.txt"), "other words").unwrap();
+            std::fs::write(network_datadir.join("daemon.toml"), "previous").unwrap();
+            std::fs::write(network_datadir.join("signers.json"), "previous signers").unwrap();
+
+            let tor = (steps >= 4).then(tor_control);
+            let mut record = InstallRecord::default();
+            partial_install(
+                &mut record,
+                &datadir,
+                steps,
+                tor.as_ref().map(|(tor, _)| tor.clone()),
+            );
+            // Neither the directory nor the wallet were created by this install.
+            assert!(record.directories.is_empty());
+            assert!(record.watchonly_wallet.is_none());
+
+            let record = Arc::new(Mutex::new(record));
+            rollback(record.clone(), None).await.unwrap();
+            assert!(record.lock().unwrap().is_empty());
+            if let Some((_, server)) = tor {
+                server.join().unwrap();
+            }
+            assert!(network_datadir.join(WATCHONLY_WALLET_NAME).exists());
+            assert!(mnemonics.join("mnemonic-01020304.txt").exists());
+            assert!(!mnemonics.join("mnemonic-abcdef01.txt").exists());
+            // The overwritten files are restored, the new ones are removed.
+            assert_eq!(
+                std::fs::read_to_string(network_datadir.join("daemon.toml")).unwrap(),
+                "previous"
+            );
+            assert_eq!(
+                std::fs::read_to_string(network_datadir.join("signers.json")).unwrap(),
+                "previous signers"
+            );
+            assert!(!network_datadir.join("gui.toml").exists());
+            assert!(!network_datadir.join("settings.json").exists());
+            std::fs::remove_dir_all(&datadir).unwrap();
+        }
+    }
+}
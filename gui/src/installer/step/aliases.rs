@@ -0,0 +1,95 @@
+//! The aliases of the keys of a descriptor, kept in a sidecar file: a JSON object mapping the
+//! fingerprints of the master keys to their names. Aliases only make the policy easier to follow
+//! across setups, they are never part of the descriptor itself.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
+
+use liana::{descriptors::PathInfo, miniscript::bitcoin::util::bip32::Fingerprint};
+use serde_json::{Map, Value};
+
+pub type KeyAliases = BTreeMap<Fingerprint, String>;
+
+/// Parse an alias map: `{"<fingerprint>": "<name>", ..}`.
+pub fn parse_aliases(content: &str) -> Result<KeyAliases, String> {
+    let map: Map<String, Value> =
+        serde_json::from_str(content).map_err(|e| format!("Invalid alias map: {}", e))?;
+    let mut aliases = KeyAliases::new();
+    for (fingerprint, name) in map {
+        let fingerprint = Fingerprint::from_str(&fingerprint)
+            .map_err(|_| format!("'{}' is not a key fingerprint", fingerprint))?;
+        let name = name
+            .as_str()
+            .ok_or_else(|| format!("The alias of {} is not a string", fingerprint))?;
+        if !name.is_empty() {
+            aliases.insert(fingerprint, name.to_string());
+        }
+    }
+    Ok(aliases)
+}
+
+/// Load the alias map pasted by the user or from the file at the given path.
+pub fn load_aliases(input: &str) -> Result<KeyAliases, String> {
+    let input = input.trim();
+    if input.starts_with('{') {
+        return parse_aliases(input);
+    }
+    let content = std::fs::read_to_string(Path::new(input))
+        .map_err(|e| format!("Failed to read the alias map file: {}", e))?;
+    parse_aliases(&content)
+}
+
+/// The alias map, to be stored alongside the descriptor backup.
+pub fn export_aliases(aliases: &KeyAliases) -> String {
+    let map: Map<String, Value> = aliases
+        .iter()
+        .map(|(fingerprint, name)| (fingerprint.to_string(), Value::String(name.clone())))
+        .collect();
+    serde_json::to_string_pretty(&map).expect("Serializing a JSON object")
+}
+
+/// The names of the keys of the spending path, their fingerprint if they have no alias.
+pub fn keys_names(path: &PathInfo, aliases: &KeyAliases) -> String {
+    let keys = match path {
+        PathInfo::Single(key) => vec![key.clone()],
+        PathInfo::Multi(_, keys) => keys.clone(),
+    };
+    keys.iter()
+        .map(|k| {
+            aliases
+                .get(&k.master_fingerprint())
+                .cloned()
+                .unwrap_or_else(|| k.master_fingerprint().to_string())
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn alias_map() {
+        let content = r#"{"f5acc2fd": "Coldcard", "9e1c1983": "Ledger", "abcdef01": ""}"#;
+        let aliases = parse_aliases(content).unwrap();
+        assert_eq!(aliases.len(), 2);
+        assert_eq!(
+            aliases.get(&Fingerprint::from_str("f5acc2fd").unwrap()),
+            Some(&"Coldcard".to_string())
+        );
+        assert_eq!(parse_aliases(&export_aliases(&aliases)).unwrap(), aliases);
+        assert_eq!(load_aliases(&export_aliases(&aliases)).unwrap(), aliases);
+
+        let path = std::env::temp_dir().join("liana-gui-test-key-aliases.json");
+        std::fs::write(&path, export_aliases(&aliases)).unwrap();
+        assert_eq!(load_aliases(path.to_str().unwrap()).unwrap(), aliases);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(parse_aliases(r#"{"f5acc2f": "Coldcard"}"#).is_err());
+        assert!(parse_aliases(r#"{"f5acc2fd": 1}"#).is_err());
+        assert!(parse_aliases(r#"["f5acc2fd"]"#).is_err());
+        assert!(load_aliases("/nonexistent/aliases.json").is_err());
+    }
+}
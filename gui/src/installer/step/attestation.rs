@@ -0,0 +1,191 @@
+//! The confirmations of the cosigners that they registered the descriptor on their device. A
+//! cosigner confirms by signing, with the key of their device at the first receive address, a
+//! message naming this address: the signature proves both the device holds a key of the
+//! descriptor and its owner saw the address derived by the registered wallet. The signature is
+//! the one of the "sign message" feature of the devices and wallets (BIP137), in base64.
+
+use std::collections::BTreeMap;
+
+use liana::{
+    descriptors::LianaDescriptor,
+    miniscript::bitcoin::{
+        secp256k1,
+        util::{
+            bip32::{ChildNumber, DerivationPath, Fingerprint},
+            misc::signed_msg_hash,
+        },
+    },
+};
+
+/// The message signed by the cosigners, for the given first receive address.
+pub fn attestation_message(first_address: &str) -> String {
+    format!(
+        "I registered the Liana wallet with first receive address {} on my signing device",
+        first_address
+    )
+}
+
+/// The keys of the first receive address, with the path of each from the master key of its
+/// device. A device with several keys in the descriptor may sign with any of them.
+fn first_address_keys(
+    descriptor: &LianaDescriptor,
+) -> Vec<(Fingerprint, DerivationPath, secp256k1::PublicKey)> {
+    let secp = secp256k1::Secp256k1::verification_only();
+    let mut keys: Vec<_> = descriptor
+        .receive_descriptor()
+        .derive(
+            ChildNumber::from_normal_idx(0).expect("Not hardened"),
+            &secp,
+        )
+        .bip32_derivations()
+        .into_iter()
+        .map(|(key, (fingerprint, path))| (fingerprint, path, key))
+        .collect();
+    keys.sort();
+    keys
+}
+
+/// The derivation path each cosigner signs the message at.
+pub fn attestation_paths(descriptor: &LianaDescriptor) -> BTreeMap<Fingerprint, DerivationPath> {
+    let mut paths = BTreeMap::new();
+    for (fingerprint, path, _) in first_address_keys(descriptor) {
+        paths.entry(fingerprint).or_insert(path);
+    }
+    paths
+}
+
+/// Check the signature of the message for the descriptor, returning the device whose key made
+/// it.
+pub fn verify_attestation(
+    descriptor: &LianaDescriptor,
+    message: &str,
+    signature: &str,
+) -> Result<Fingerprint, String> {
+    let bytes = base64::decode(signature.trim())
+        .map_err(|_| format!("'{}' is not a base64 signature", signature.trim()))?;
+    // The first byte is the recovery header, the public key being known it is not needed.
+    if bytes.len() != 65 || !(27..=42).contains(&bytes[0]) {
+        return Err(format!(
+            "'{}' is not a signed message signature",
+            signature.trim()
+        ));
+    }
+    let mut sig = secp256k1::ecdsa::Signature::from_compact(&bytes[1..])
+        .map_err(|e| format!("Invalid signature: {}", e))?;
+    sig.normalize_s();
+    let msg =
+        secp256k1::Message::from_slice(&signed_msg_hash(message)[..]).expect("A hash is 32 bytes");
+    let secp = secp256k1::Secp256k1::verification_only();
+    first_address_keys(descriptor)
+        .into_iter()
+        .find(|(_, _, key)| secp.verify_ecdsa(&msg, &sig, key).is_ok())
+        .map(|(fingerprint, _, _)| fingerprint)
+        .ok_or_else(|| {
+            format!(
+                "The signature '{}' was not made by a key of the first receive address",
+                signature.trim()
+            )
+        })
+}
+
+/// Verify the signatures, one per line, returning the confirmed devices and an error for each
+/// invalid signature.
+pub fn import_attestations(
+    descriptor: &LianaDescriptor,
+    message: &str,
+    input: &str,
+) -> (Vec<Fingerprint>, Vec<String>) {
+    let mut confirmed = Vec::new();
+    let mut errors = Vec::new();
+    for line in input.lines().filter(|line| !line.trim().is_empty()) {
+        match verify_attestation(descriptor, message, line) {
+            Ok(fingerprint) => confirmed.push(fingerprint),
+            Err(e) => errors.push(e),
+        }
+    }
+    (confirmed, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use liana::miniscript::bitcoin::{
+        util::bip32::{ExtendedPrivKey, ExtendedPubKey},
+        Network,
+    };
+    use std::str::FromStr;
+
+    /// Sign the message as a device would with the key at the given path.
+    fn sign_message(
+        master: &ExtendedPrivKey,
+        path: &DerivationPath,
+        message: &str,
+        header: u8,
+    ) -> String {
+        let secp = secp256k1::Secp256k1::new();
+        let key = master.derive_priv(&secp, path).unwrap().private_key;
+        let msg = secp256k1::Message::from_slice(&signed_msg_hash(message)[..]).unwrap();
+        let mut bytes = vec![header];
+        bytes.extend_from_slice(&secp.sign_ecdsa(&msg, &key).serialize_compact());
+        base64::encode(bytes)
+    }
+
+    #[test]
+    fn cosigner_attestations() {
+        let secp = secp256k1::Secp256k1::new();
+        let masters: Vec<ExtendedPrivKey> = (1..=3)
+            .map(|seed| ExtendedPrivKey::new_master(Network::Testnet, &[seed; 32]).unwrap())
+            .collect();
+        let account = DerivationPath::from_str("m/48'/1'/0'/2'").unwrap();
+        let keys: Vec<String> = masters
+            .iter()
+            .map(|master| {
+                format!(
+                    "[{}/48'/1'/0'/2']{}/<0;1>/*",
+                    master.fingerprint(&secp),
+                    ExtendedPubKey::from_priv(&secp, &master.derive_priv(&secp, &account).unwrap())
+                )
+            })
+            .collect();
+        let descriptor = LianaDescriptor::from_str(&format!(
+            "wsh(or_d(multi(2,{},{}),and_v(v:pkh({}),older(52560))))",
+            keys[0], keys[1], keys[2]
+        ))
+        .unwrap();
+
+        let paths = attestation_paths(&descriptor);
+        assert_eq!(paths.len(), 3);
+        let path = DerivationPath::from_str("m/48'/1'/0'/2'/0/0").unwrap();
+        assert!(paths.values().all(|p| *p == path));
+
+        let message = attestation_message("tb1qexample");
+        let first = sign_message(&masters[0], &path, &message, 31);
+        let third = sign_message(&masters[2], &path, &message, 39);
+        assert_eq!(
+            verify_attestation(&descriptor, &message, &first),
+            Ok(masters[0].fingerprint(&secp))
+        );
+
+        // A signature of another message, or with a key of another path, is refused.
+        let other_message = sign_message(&masters[1], &path, "Another message", 31);
+        let other_path = sign_message(
+            &masters[1],
+            &DerivationPath::from_str("m/48'/1'/0'/2'/0/1").unwrap(),
+            &message,
+            31,
+        );
+        let (confirmed, errors) = import_attestations(
+            &descriptor,
+            &message,
+            &format!(
+                "{}\n\n  {}  \n{}\n{}\nnotbase64",
+                first, third, other_message, other_path
+            ),
+        );
+        assert_eq!(
+            confirmed,
+            vec![masters[0].fingerprint(&secp), masters[2].fingerprint(&secp)]
+        );
+        assert_eq!(errors.len(), 3);
+    }
+}
@@ -0,0 +1,243 @@
+use std::collections::HashSet;
+
+use iced::Command;
+use jsonrpc::{client::Client, simple_http::SimpleHttpTransport};
+use liana_ui::{component::form, widget::Element};
+
+use crate::{
+    app::config::CoordinatorConfig,
+    installer::{
+        context::Context,
+        message::{self, Message},
+        step::{Step, SummarySection},
+        view, Error,
+    },
+};
+
+/// Timeout of the requests to the coordination server made by the installer.
+const COORDINATOR_TIMEOUT_SECS: u64 = 5;
+
+/// The coordination server the participants of a multisig share the PSBTs and the descriptor
+/// through. It is optional: the step is skipped for a single key wallet and can be skipped by
+/// the user when the participants coordinate without a server.
+pub struct DefineCoordinator {
+    url: form::Value<String>,
+    user: form::Value<String>,
+    password: form::Value<String>,
+    is_connected: Option<Result<(), Error>>,
+    skip: bool,
+}
+
+/// Parse the coordinator settings entered by the user. The server is reached through the plain
+/// HTTP JSON-RPC transport also used for bitcoind, which does not support TLS.
+pub fn parse_coordinator(
+    url: &str,
+    user: &str,
+    password: &str,
+) -> Result<CoordinatorConfig, String> {
+    let url = url.trim();
+    let host = url
+        .strip_prefix("http://")
+        .ok_or_else(|| "The URL of the coordinator must start with http://".to_string())?;
+    if host.is_empty() || host.starts_with('/') || url.contains(char::is_whitespace) {
+        return Err(format!("'{}' is not a valid URL", url));
+    }
+    let (user, password) = (user.trim(), password.trim());
+    match (user.is_empty(), password.is_empty()) {
+        (true, true) => Ok(CoordinatorConfig {
+            url: url.to_string(),
+            user: None,
+            password: None,
+        }),
+        (false, false) => Ok(CoordinatorConfig {
+            url: url.to_string(),
+            user: Some(user.to_string()),
+            password: Some(password.to_string()),
+        }),
+        _ => Err("Please enter both the user and the password, or none of them".to_string()),
+    }
+}
+
+/// Check the coordinator answers with the given credentials. Any JSON-RPC response, even an
+/// error, means the server was reached and accepted the authentication.
+async fn check_coordinator(config: CoordinatorConfig) -> Result<(), Error> {
+    let mut builder = SimpleHttpTransport::builder()
+        .url(&config.url)
+        .map_err(|e| Error::Coordinator(e.to_string()))?
+        .timeout(std::time::Duration::from_secs(COORDINATOR_TIMEOUT_SECS));
+    if let Some(user) = &config.user {
+        builder = builder.auth(user, config.password.as_ref());
+    }
+    let client = Client::with_transport(builder.build());
+    match client.send_request(client.build_request("ping", &[])) {
+        Ok(_) => Ok(()),
+        Err(jsonrpc::Error::Transport(e)) => {
+            let e = e.to_string();
+            if e.contains("401") || e.contains("403") {
+                Err(Error::Coordinator(
+                    "the server refused the credentials".to_string(),
+                ))
+            } else {
+                Err(Error::Coordinator(e))
+            }
+        }
+        Err(e) => Err(Error::Coordinator(e.to_string())),
+    }
+}
+
+impl DefineCoordinator {
+    pub fn new() -> Self {
+        Self {
+            url: form::Value::default(),
+            user: form::Value::default(),
+            password: form::Value::default(),
+            is_connected: None,
+            skip: false,
+        }
+    }
+
+    fn coordinator(&mut self) -> Option<CoordinatorConfig> {
+        match parse_coordinator(&self.url.value, &self.user.value, &self.password.value) {
+            Ok(config) => Some(config),
+            Err(e) => {
+                self.is_connected = Some(Err(Error::Coordinator(e)));
+                None
+            }
+        }
+    }
+}
+
+impl Default for DefineCoordinator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Step for DefineCoordinator {
+    fn update(&mut self, message: Message) -> Command<Message> {
+        match message {
+            Message::DefineCoordinator(msg) => match msg {
+                message::DefineCoordinator::UrlEdited(url) => {
+                    self.is_connected = None;
+                    self.url.valid = true;
+                    self.url.value = url;
+                }
+                message::DefineCoordinator::UserEdited(user) => {
+                    self.is_connected = None;
+                    self.user.value = user;
+                }
+                message::DefineCoordinator::PasswordEdited(password) => {
+                    self.is_connected = None;
+                    self.password.value = password;
+                }
+                message::DefineCoordinator::CheckConnection => {
+                    if let Some(config) = self.coordinator() {
+                        self.is_connected = None;
+                        return Command::perform(check_coordinator(config), |res| {
+                            Message::DefineCoordinator(message::DefineCoordinator::Checked(res))
+                        });
+                    }
+                }
+                message::DefineCoordinator::Checked(res) => {
+                    self.is_connected = Some(res);
+                }
+            },
+            Message::Skip => {
+                self.skip = true;
+                return Command::perform(async {}, |_| Message::Next);
+            }
+            _ => {}
+        }
+        Command::none()
+    }
+
+    /// A wallet with a single key has no one to coordinate with.
+    fn skip(&self, ctx: &Context) -> bool {
+        ctx.descriptor
+            .as_ref()
+            .map(|desc| {
+                let policy = desc.policy();
+                std::iter::once(policy.primary_path())
+                    .chain(policy.recovery_paths().values())
+                    .flat_map(|path| path.thresh_origins().1.into_iter().map(|(fp, _)| fp))
+                    .collect::<HashSet<_>>()
+                    .len()
+                    <= 1
+            })
+            .unwrap_or(false)
+    }
+
+    fn summary_section(&self) -> Option<SummarySection> {
+        Some(SummarySection::Coordinator)
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    fn apply(&mut self, ctx: &mut Context) -> bool {
+        if self.skip {
+            // If the user comes back to this step, the skip must be asked again.
+            self.skip = false;
+            ctx.coordinator = None;
+            return true;
+        }
+        match self.coordinator() {
+            Some(config) => {
+                ctx.coordinator = Some(config);
+                true
+            }
+            None => {
+                self.url.valid = false;
+                false
+            }
+        }
+    }
+
+    fn view(&self, progress: (usize, usize)) -> Element<Message> {
+        view::define_coordinator(
+            progress,
+            &self.url,
+            &self.user,
+            &self.password,
+            self.is_connected.as_ref(),
+        )
+    }
+}
+
+impl From<DefineCoordinator> for Box<dyn Step> {
+    fn from(s: DefineCoordinator) -> Box<dyn Step> {
+        Box::new(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coordinator_settings() {
+        assert_eq!(
+            parse_coordinator(" http://10.0.0.2:8080/rpc ", "", ""),
+            Ok(CoordinatorConfig {
+                url: "http://10.0.0.2:8080/rpc".to_string(),
+                user: None,
+                password: None,
+            })
+        );
+        assert_eq!(
+            parse_coordinator("http://coordinator.local", "alice", "secret"),
+            Ok(CoordinatorConfig {
+                url: "http://coordinator.local".to_string(),
+                user: Some("alice".to_string()),
+                password: Some("secret".to_string()),
+            })
+        );
+        assert!(parse_coordinator("https://coordinator.local", "", "").is_err());
+        assert!(parse_coordinator("coordinator.local", "", "").is_err());
+        assert!(parse_coordinator("http://", "", "").is_err());
+        assert!(parse_coordinator("http://coordinator local", "", "").is_err());
+        assert!(parse_coordinator("http://coordinator.local", "alice", "").is_err());
+        assert!(parse_coordinator("http://coordinator.local", "", "secret").is_err());
+    }
+}
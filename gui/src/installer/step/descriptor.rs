@@ -1,22 +1,30 @@
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::{Arc, Mutex};
 
-use iced::Command;
+use iced::{widget::qr_code, Command};
 use liana::{
-    descriptors::{LianaDescriptor, LianaPolicy, PathInfo},
+    descriptors::{LianaDescriptor, LianaPolicy, PathInfo, PathWitnessSize},
     miniscript::{
         bitcoin::{
-            util::bip32::{ChildNumber, DerivationPath, Fingerprint},
+            self, consensus, secp256k1,
+            util::{
+                address::{Payload, WitnessVersion},
+                bip32::{
+                    ChildNumber, DerivationPath, ExtendedPrivKey, ExtendedPubKey, Fingerprint,
+                    KeySource,
+                },
+                psbt::Psbt,
+            },
             Network,
         },
-        descriptor::{
-            DerivPaths, DescriptorMultiXKey, DescriptorPublicKey, DescriptorXKey, Wildcard,
-        },
+        descriptor::{Descriptor, DescriptorPublicKey, DescriptorXKey, Wildcard, WshInner},
+        translate_hash_clone, Miniscript, Segwitv0, Terminal, TranslatePk, Translator,
     },
 };
 
+use jsonrpc::{client::Client, simple_http::SimpleHttpTransport};
 use liana_ui::{
     component::{form, modal::Modal},
     widget::Element,
@@ -26,10 +34,25 @@ use async_hwi::DeviceKind;
 
 use crate::{
     app::settings::KeySetting,
-    hw::{list_unregistered_hardware_wallets, HardwareWallet},
+    hw::{
+        enumerate_unregistered_hardware_wallets, list_unregistered_hardware_wallets, HardwareWallet,
+    },
     installer::{
+        context::{AcknowledgedWarnings, SecurityWarning},
         message::{self, Message},
-        step::{Context, Step},
+        step::{
+            aliases::{export_aliases, keys_names, load_aliases, KeyAliases},
+            attestation::{attestation_message, attestation_paths, import_attestations},
+            bitcoind_default_address, bitcoind_default_cookie_path, connection_error,
+            parse_file_path,
+            payload::{parse_descriptor_payload, parse_key_payload},
+            policy_builder::{
+                multipath_key, path_info, BuilderKey, BuilderPath, PolicyBuilder, ScriptType,
+            },
+            second_opinion::{second_opinion, DefinedPath},
+            template::{policy_template_label, PolicyShape},
+            Context, Step, SummarySection, BITCOIND_TIMEOUT_SECS,
+        },
         view, Error,
     },
     signer::Signer,
@@ -52,12 +75,30 @@ pub struct RecoveryPath {
     duplicate_sequence: bool,
 }
 
+/// The timelock suggested for the recovery paths, in blocks. It is short on regtest so the
+/// recovery can be tested quickly.
+fn default_recovery_sequence(network: Network) -> u16 {
+    match network {
+        // ~6 months
+        Network::Bitcoin => 26352,
+        // ~1 week
+        Network::Testnet | Network::Signet => 1008,
+        Network::Regtest => 10,
+    }
+}
+
+/// The timelock suggested for the recovery path at the given position: a multiple of the
+/// default of the network, so the successive paths are available one after the other.
+fn suggested_recovery_sequence(network: Network, index: usize) -> u16 {
+    default_recovery_sequence(network).saturating_mul(index as u16 + 1)
+}
+
 impl RecoveryPath {
-    pub fn new() -> Self {
+    pub fn new(sequence: u16) -> Self {
         Self {
             keys: vec![DescriptorKey::default()],
             threshold: 1,
-            sequence: u16::MAX,
+            sequence,
             duplicate_sequence: false,
         }
     }
@@ -88,6 +129,250 @@ impl RecoveryPath {
     }
 }
 
+/// The spending path as entered by the user, for the second opinion on the descriptor.
+fn defined_path(keys: &[DescriptorKey], threshold: usize) -> DefinedPath {
+    DefinedPath {
+        threshold,
+        keys: keys
+            .iter()
+            .filter_map(|key| match &key.key {
+                Some(DescriptorPublicKey::XPub(xpub)) => Some(xpub.clone()),
+                _ => None,
+            })
+            .collect(),
+    }
+}
+
+/// A dummy key standing for a key not set yet in the previews.
+fn placeholder_key(
+    network: Network,
+    index: usize,
+    secp: &secp256k1::Secp256k1<secp256k1::SignOnly>,
+) -> DescriptorPublicKey {
+    let xpriv =
+        ExtendedPrivKey::new_master(network, &[index as u8; 32]).expect("Seed of a valid length");
+    let xkey = ExtendedPubKey::from_priv(secp, &xpriv);
+    multipath_key(&DescriptorXKey {
+        origin: Some((xkey.fingerprint(), DerivationPath::master())),
+        xkey,
+        derivation_path: DerivationPath::master(),
+        wildcard: Wildcard::Unhardened,
+    })
+}
+
+const INCOMPLETE_PLACEHOLDER: &str = "<incomplete>";
+
+/// The descriptor with the placeholder keys replaced, in which case it has no checksum.
+fn preview_string(descriptor: &LianaDescriptor, placeholders: &[String]) -> String {
+    let mut desc = descriptor.to_string();
+    if !placeholders.is_empty() {
+        if let Some((d, _)) = desc.split_once('#') {
+            desc = d.to_string();
+        }
+        for placeholder in placeholders {
+            desc = desc.replace(placeholder, INCOMPLETE_PLACEHOLDER);
+        }
+    }
+    desc
+}
+
+/// The estimated witness size for spending through each path of the descriptor, and whether
+/// such a spend would be relayed by standard nodes.
+pub fn witness_sizes_summary(descriptor: &LianaDescriptor) -> Vec<(String, bool)> {
+    let (primary, recovery_paths) = descriptor.witness_sizes();
+    let line = |path: String, size: &PathWitnessSize| {
+        (
+            format!(
+                "{}: witness of ~{} WU, {} stack elements",
+                path, size.weight, size.stack_items
+            ),
+            size.is_standard(),
+        )
+    };
+    std::iter::once(line("Primary path".to_string(), &primary))
+        .chain(
+            recovery_paths.iter().map(|(timelock, size)| {
+                line(format!("Recovery path after {} blocks", timelock), size)
+            }),
+        )
+        .collect()
+}
+
+/// The encoding of an address, which depends on the witness version of its script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressEncoding {
+    Base58,
+    /// Segwit v0.
+    Bech32,
+    /// Segwit v1 (Taproot) and later.
+    Bech32m,
+}
+
+impl AddressEncoding {
+    pub fn of(address: &bitcoin::Address) -> Self {
+        match address.payload {
+            Payload::WitnessProgram {
+                version: WitnessVersion::V0,
+                ..
+            } => Self::Bech32,
+            Payload::WitnessProgram { .. } => Self::Bech32m,
+            _ => Self::Base58,
+        }
+    }
+
+    /// Bech32m is recent and some wallets or services are not able to parse it yet.
+    pub fn is_widely_supported(&self) -> bool {
+        !matches!(self, Self::Bech32m)
+    }
+}
+
+impl std::fmt::Display for AddressEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Base58 => write!(f, "base58"),
+            Self::Bech32 => write!(f, "bech32"),
+            Self::Bech32m => write!(f, "bech32m"),
+        }
+    }
+}
+
+/// The address followed by its encoding, flagged if other tools may not be able to parse it.
+pub fn address_label(address: &bitcoin::Address) -> String {
+    let encoding = AddressEncoding::of(address);
+    if encoding.is_widely_supported() {
+        format!("{} ({})", address, encoding)
+    } else {
+        format!("{} ({}, not supported by every wallet)", address, encoding)
+    }
+}
+
+/// The first receive and change addresses of the descriptor, for the user to compare them with
+/// the ones displayed by the node or the signing devices.
+pub fn first_addresses(descriptor: &LianaDescriptor, network: Network) -> (String, String) {
+    let secp = secp256k1::Secp256k1::verification_only();
+    (
+        address_label(
+            &descriptor
+                .receive_descriptor()
+                .derive(0.into(), &secp)
+                .address(network),
+        ),
+        address_label(
+            &descriptor
+                .change_descriptor()
+                .derive(0.into(), &secp)
+                .address(network),
+        ),
+    )
+}
+
+/// The most addresses exported per branch of the descriptor, to keep the file manageable.
+pub const MAX_EXPORTED_ADDRESSES: u32 = 10_000;
+
+/// The branches of the descriptor to export the addresses of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressBranch {
+    Receive,
+    Change,
+    Both,
+}
+
+impl AddressBranch {
+    pub const ALL: [AddressBranch; 3] = [
+        AddressBranch::Receive,
+        AddressBranch::Change,
+        AddressBranch::Both,
+    ];
+}
+
+impl std::fmt::Display for AddressBranch {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Receive => write!(f, "Receive addresses"),
+            Self::Change => write!(f, "Change addresses"),
+            Self::Both => write!(f, "Receive and change addresses"),
+        }
+    }
+}
+
+/// The first addresses of the given branches of the descriptor as CSV, one line per address
+/// with its branch and derivation index, to be compared in bulk with another tool.
+pub fn export_addresses(
+    descriptor: &LianaDescriptor,
+    network: Network,
+    branch: AddressBranch,
+    count: u32,
+) -> String {
+    let secp = secp256k1::Secp256k1::verification_only();
+    let mut branches = Vec::new();
+    if branch != AddressBranch::Change {
+        branches.push(("receive", descriptor.receive_descriptor()));
+    }
+    if branch != AddressBranch::Receive {
+        branches.push(("change", descriptor.change_descriptor()));
+    }
+    let mut csv = "branch,index,address\n".to_string();
+    for (name, desc) in branches {
+        for index in 0..count.min(MAX_EXPORTED_ADDRESSES) {
+            csv.push_str(&format!(
+                "{},{},{}\n",
+                name,
+                index,
+                desc.derive(index.into(), &secp).address(network)
+            ));
+        }
+    }
+    csv
+}
+
+/// A human readable summary of the spending paths of the descriptor, the keys being named after
+/// their alias if they have one, followed by the template the policy matches if any.
+pub fn descriptor_policy_summary(
+    descriptor: &LianaDescriptor,
+    aliases: &KeyAliases,
+) -> Vec<String> {
+    let policy = descriptor.policy();
+    std::iter::once((None, policy.primary_path()))
+        .chain(policy.recovery_paths().iter().map(|(s, p)| (Some(*s), p)))
+        .map(|(sequence, path)| {
+            let (threshold, origins) = path.thresh_origins();
+            path_summary(
+                sequence,
+                threshold,
+                origins.len(),
+                keys_names(path, aliases),
+            )
+        })
+        .chain(std::iter::once(policy_template_label(
+            &PolicyShape::from_descriptor(descriptor),
+        )))
+        .collect()
+}
+
+/// A human readable description of a spending path, available after the given timelock if any.
+fn path_summary(
+    sequence: Option<u16>,
+    threshold: usize,
+    keys_count: usize,
+    names: String,
+) -> String {
+    if let Some(sequence) = sequence {
+        format!(
+            "Spendable after {} blocks (~{} days) of inactivity by {} of {} key(s): {}",
+            sequence,
+            sequence as u32 * 10 / 1440,
+            threshold,
+            keys_count,
+            names
+        )
+    } else {
+        format!(
+            "Spendable at any time by {} of {} key(s): {}",
+            threshold, keys_count, names
+        )
+    }
+}
+
 pub struct DefineDescriptor {
     network: Network,
     network_valid: bool,
@@ -98,10 +383,30 @@ pub struct DefineDescriptor {
 
     modal: Option<Box<dyn DescriptorEditModal>>,
     signer: Arc<Mutex<Signer>>,
+    acknowledged_warnings: Arc<Mutex<AcknowledgedWarnings>>,
+
+    /// The first receive address before and after the last edit of the policy which changed it.
+    address_change: Option<(String, String)>,
+
+    aliases_input: form::Value<String>,
+    /// The aliases loaded by the user, given to the keys they are set for.
+    imported_aliases: KeyAliases,
+
+    primary_key_variants: Option<PrimaryKeyVariants>,
+
+    /// The policy this step last wrote to the context.
+    applied_policy: Option<PolicyBuilder>,
 
     error: Option<String>,
 }
 
+/// The first key of the primary path, either the one of a signing device or a key of the hot
+/// signer, for the user to compare both descriptors and choose between security and convenience.
+struct PrimaryKeyVariants {
+    hardware: DescriptorKey,
+    hot: DescriptorKey,
+}
+
 impl DefineDescriptor {
     pub fn new(signer: Arc<Mutex<Signer>>) -> Self {
         Self {
@@ -110,20 +415,373 @@ impl DefineDescriptor {
             network_valid: true,
             spending_keys: vec![DescriptorKey::default()],
             spending_threshold: 1,
-            recovery_paths: vec![RecoveryPath::new()],
+            recovery_paths: vec![RecoveryPath::new(suggested_recovery_sequence(
+                Network::Bitcoin,
+                0,
+            ))],
             modal: None,
             signer,
+            acknowledged_warnings: Arc::new(Mutex::new(AcknowledgedWarnings::default())),
+            address_change: None,
+            aliases_input: form::Value::default(),
+            imported_aliases: KeyAliases::new(),
+            primary_key_variants: None,
+            applied_policy: None,
             error: None,
         }
     }
 
+    /// The policy as entered, without the keys not set yet.
+    fn policy_builder(&self) -> PolicyBuilder {
+        fn builder_path(keys: &[DescriptorKey], threshold: usize) -> BuilderPath {
+            BuilderPath {
+                threshold,
+                keys: keys
+                    .iter()
+                    .filter_map(|key| match &key.key {
+                        Some(DescriptorPublicKey::XPub(xpub)) => Some(BuilderKey {
+                            name: key.name.clone(),
+                            xpub: xpub.clone(),
+                            device_kind: key.device_kind,
+                        }),
+                        _ => None,
+                    })
+                    .collect(),
+            }
+        }
+        PolicyBuilder {
+            script_type: ScriptType::Wsh,
+            primary: builder_path(&self.spending_keys, self.spending_threshold),
+            recovery: self
+                .recovery_paths
+                .iter()
+                .map(|path| (path.sequence, builder_path(&path.keys, path.threshold)))
+                .collect(),
+        }
+    }
+
+    /// Replace the policy being edited by the given one.
+    fn load_policy(&mut self, builder: &PolicyBuilder) {
+        fn descriptor_keys(path: &BuilderPath) -> Vec<DescriptorKey> {
+            path.keys
+                .iter()
+                .map(|key| DescriptorKey {
+                    name: key.name.clone(),
+                    device_kind: key.device_kind,
+                    key: Some(DescriptorPublicKey::XPub(key.xpub.clone())),
+                    ..Default::default()
+                })
+                .collect()
+        }
+        self.spending_keys = descriptor_keys(&builder.primary);
+        self.spending_threshold = builder.primary.threshold;
+        self.recovery_paths = builder
+            .recovery
+            .iter()
+            .map(|(sequence, path)| RecoveryPath {
+                keys: descriptor_keys(path),
+                threshold: path.threshold,
+                sequence: *sequence,
+                duplicate_sequence: false,
+            })
+            .collect();
+        self.primary_key_variants = None;
+        self.address_change = None;
+        self.error = None;
+        for key in self.spending_keys.iter_mut() {
+            key.check_network(self.network);
+        }
+        for path in self.recovery_paths.iter_mut() {
+            path.check_network(self.network);
+        }
+        self.check_for_duplicate();
+    }
+
+    /// A key of the hot signer, to use as primary key instead of the one of a signing device or
+    /// as recovery key, at the next account not used by the other keys of the hot signer.
+    fn hot_signer_key(&self) -> Option<DescriptorKey> {
+        let signer = self.signer.lock().unwrap();
+        let fingerprint = signer.fingerprint();
+        let account_index = self
+            .fingerprint_account_index_mappping()
+            .get(&fingerprint)
+            .map(|account_index| account_index.increment().unwrap())
+            .unwrap_or_else(|| ChildNumber::from_hardened_idx(0).unwrap());
+        let derivation_path = generate_derivation_path(self.network, account_index);
+        let key = DescriptorPublicKey::from_str(&format!(
+            "[{}{}]{}",
+            fingerprint,
+            derivation_path.to_string().trim_start_matches('m'),
+            signer.get_extended_pubkey(&derivation_path)
+        ))
+        .ok()?;
+        Some(DescriptorKey {
+            name: self
+                .keys_aliases()
+                .get(&fingerprint)
+                .filter(|name| !name.is_empty())
+                .cloned()
+                .unwrap_or_else(|| "This computer".to_string()),
+            key: Some(key),
+            ..Default::default()
+        })
+    }
+
+    /// Whether a key of the hot signer is in a recovery path.
+    fn hot_signer_in_recovery_path(&self) -> bool {
+        let fingerprint = self.signer.lock().unwrap().fingerprint();
+        self.recovery_paths
+            .iter()
+            .flat_map(|path| path.keys.iter())
+            .any(|key| {
+                key.key
+                    .as_ref()
+                    .map(|k| k.master_fingerprint() == fingerprint)
+                    .unwrap_or(false)
+            })
+    }
+
+    /// The timelock of the recovery path added at the end, the suggested one for its position
+    /// if not already taken.
+    fn next_recovery_sequence(&self) -> u16 {
+        (self.recovery_paths.len()..)
+            .map(|i| suggested_recovery_sequence(self.network, i))
+            .take_while(|seq| *seq != u16::MAX)
+            .find(|seq| !self.recovery_paths.iter().any(|p| p.sequence == *seq))
+            .unwrap_or(u16::MAX)
+    }
+
+    /// Whether the first key of the primary path is the one of a signing device, which could be
+    /// compared with a key of the hot signer.
+    fn can_compare_hot_primary_key(&self) -> bool {
+        self.primary_key_variants.is_none()
+            && self
+                .spending_keys
+                .first()
+                .map(|key| key.key.is_some() && key.device_kind.is_some())
+                .unwrap_or(false)
+    }
+
+    /// The descriptor as defined, with the given first key for the primary path.
+    fn variant_preview(&self, primary_key: &DescriptorKey) -> Result<String, String> {
+        let mut spending_keys = self.spending_keys.clone();
+        spending_keys[0] = primary_key.clone();
+        let keys = spending_keys
+            .iter()
+            .chain(self.recovery_paths.iter().flat_map(|p| p.keys.iter()))
+            .filter_map(|k| k.key.as_ref());
+        let mut set = HashSet::new();
+        for key in keys {
+            if !check_key_network(key, self.network) {
+                return Err("A key is not for the selected network".to_string());
+            }
+            if !set.insert(key) {
+                return Err("A key is used more than once".to_string());
+            }
+        }
+        self.preview_descriptor_with(&spending_keys)
+            .map(|(desc, placeholders)| preview_string(&desc, &placeholders))
+    }
+
+    /// Name the keys after the aliases loaded by the user.
+    fn apply_aliases(&mut self) {
+        let aliases = &self.imported_aliases;
+        for key in self.spending_keys.iter_mut().chain(
+            self.recovery_paths
+                .iter_mut()
+                .flat_map(|p| p.keys.iter_mut()),
+        ) {
+            if let Some(alias) = key
+                .key
+                .as_ref()
+                .and_then(|k| aliases.get(&k.master_fingerprint()))
+            {
+                key.name = alias.clone();
+            }
+        }
+        self.check_for_duplicate();
+    }
+
     fn valid(&self) -> bool {
         !self.spending_keys.is_empty()
             && !self.spending_keys.iter().any(|k| k.key.is_none())
             && !self.recovery_paths.iter().any(|path| !path.valid())
+            && self
+                .warnings()
+                .iter()
+                .all(|(_, _, acknowledged)| *acknowledged)
+    }
+
+    /// The descriptor as currently defined, with a placeholder key for each of the keys not set
+    /// yet. Returns the placeholder keys along with the descriptor.
+    fn preview_descriptor(&self) -> Result<(LianaDescriptor, Vec<String>), String> {
+        self.preview_descriptor_with(&self.spending_keys)
+    }
+
+    fn preview_descriptor_with(
+        &self,
+        spending_keys: &[DescriptorKey],
+    ) -> Result<(LianaDescriptor, Vec<String>), String> {
+        let secp = secp256k1::Secp256k1::signing_only();
+        let mut placeholders = Vec::new();
+        let mut to_key = |key: &DescriptorKey| match &key.key {
+            Some(DescriptorPublicKey::XPub(xpub)) => multipath_key(xpub),
+            _ => {
+                let placeholder = placeholder_key(self.network, placeholders.len(), &secp);
+                placeholders.push(placeholder.to_string());
+                placeholder
+            }
+        };
+
+        let spending_keys: Vec<_> = spending_keys.iter().map(&mut to_key).collect();
+        let mut recovery_paths = BTreeMap::new();
+        for path in &self.recovery_paths {
+            let keys: Vec<_> = path.keys.iter().map(&mut to_key).collect();
+            recovery_paths.insert(path.sequence, path_info(keys, path.threshold));
+        }
+        if spending_keys.is_empty() {
+            return Err("The primary path has no key".to_string());
+        }
+        let policy = LianaPolicy::new(
+            path_info(spending_keys, self.spending_threshold),
+            recovery_paths,
+        )
+        .map_err(|e| e.to_string())?;
+
+        Ok((LianaDescriptor::new(policy), placeholders))
+    }
+
+    /// The first receive address of the descriptor, once all its keys are set.
+    fn first_address(&self) -> Option<String> {
+        let (descriptor, placeholders) = self.preview_descriptor().ok()?;
+        if !placeholders.is_empty() {
+            return None;
+        }
+        let secp = secp256k1::Secp256k1::verification_only();
+        Some(address_label(
+            &descriptor
+                .receive_descriptor()
+                .derive(0.into(), &secp)
+                .address(self.network),
+        ))
+    }
+
+    /// A human readable summary of the spending paths as currently defined, followed by the
+    /// template they match if any.
+    fn policy_summary(&self) -> Vec<String> {
+        let names = |keys: &[DescriptorKey]| {
+            keys.iter()
+                .map(|k| {
+                    if k.key.is_none() {
+                        INCOMPLETE_PLACEHOLDER.to_string()
+                    } else if k.name.is_empty() {
+                        k.key
+                            .as_ref()
+                            .map(|k| k.master_fingerprint().to_string())
+                            .unwrap_or_default()
+                    } else {
+                        k.name.clone()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let mut summary = vec![path_summary(
+            None,
+            self.spending_threshold,
+            self.spending_keys.len(),
+            names(&self.spending_keys),
+        )];
+        let mut recovery_paths: Vec<&RecoveryPath> = self.recovery_paths.iter().collect();
+        recovery_paths.sort_by_key(|path| path.sequence);
+        for path in recovery_paths {
+            summary.push(path_summary(
+                Some(path.sequence),
+                path.threshold,
+                path.keys.len(),
+                names(&path.keys),
+            ));
+        }
+        summary.push(policy_template_label(&PolicyShape::new(
+            (self.spending_threshold, self.spending_keys.len()),
+            self.recovery_paths
+                .iter()
+                .map(|path| (path.sequence, path.threshold, path.keys.len()))
+                .collect(),
+        )));
+        summary
+    }
+
+    /// The input each security warning applies to, if it applies at all.
+    fn warnings_inputs(&self) -> Vec<(SecurityWarning, Option<String>)> {
+        // The recovery paths with a key of the hot signer, marked if it is enough to spend.
+        let hot_fingerprint = self.signer.lock().unwrap().fingerprint();
+        let mut hot_recovery_paths: Vec<String> = self
+            .recovery_paths
+            .iter()
+            .filter_map(|path| {
+                let hot_keys = path
+                    .keys
+                    .iter()
+                    .filter(|k| {
+                        k.key
+                            .as_ref()
+                            .map(|k| k.master_fingerprint() == hot_fingerprint)
+                            .unwrap_or(false)
+                    })
+                    .count();
+                if hot_keys == 0 {
+                    None
+                } else if hot_keys >= path.threshold {
+                    Some(format!("{}:alone", path.sequence))
+                } else {
+                    Some(path.sequence.to_string())
+                }
+            })
+            .collect();
+        hot_recovery_paths.sort();
+
+        vec![(
+            SecurityWarning::HotRecoveryKey,
+            if hot_recovery_paths.is_empty() {
+                None
+            } else {
+                Some(hot_recovery_paths.join(","))
+            },
+        )]
+    }
+
+    /// The security warnings that apply to the current policy, with their input and
+    /// whether they were acknowledged.
+    fn warnings(&self) -> Vec<(SecurityWarning, String, bool)> {
+        let acknowledged_warnings = self.acknowledged_warnings.lock().unwrap();
+        self.warnings_inputs()
+            .into_iter()
+            .filter_map(|(warning, input)| {
+                input.map(|input| {
+                    let acknowledged = acknowledged_warnings.is_acknowledged(warning, &input);
+                    (warning, input, acknowledged)
+                })
+            })
+            .collect()
+    }
+
+    /// Drop the acknowledgements given for a previous version of the policy.
+    fn invalidate_acknowledged_warnings(&mut self) {
+        let inputs = self.warnings_inputs();
+        let mut acknowledged_warnings = self.acknowledged_warnings.lock().unwrap();
+        for (warning, input) in inputs {
+            acknowledged_warnings.invalidate(warning, input.as_deref());
+        }
     }
 
     fn set_network(&mut self, network: Network) {
+        // Recovery paths still using the suggested timelock get the one of the new network.
+        for (i, path) in self.recovery_paths.iter_mut().enumerate() {
+            if path.sequence == suggested_recovery_sequence(self.network, i) {
+                path.sequence = suggested_recovery_sequence(network, i);
+            }
+        }
         self.network = network;
         self.signer.lock().unwrap().set_network(network);
         if let Some(mut network_datadir) = self.data_dir.clone() {
@@ -136,6 +794,8 @@ impl DefineDescriptor {
         for path in self.recovery_paths.iter_mut() {
             path.check_network(self.network);
         }
+        self.check_for_duplicate();
+        self.invalidate_acknowledged_warnings();
     }
 
     // TODO: Improve algo
@@ -259,7 +919,11 @@ impl DefineDescriptor {
     }
 
     fn keys_aliases(&self) -> HashMap<Fingerprint, String> {
-        let mut map = HashMap::new();
+        let mut map: HashMap<_, _> = self
+            .imported_aliases
+            .iter()
+            .map(|(fingerprint, alias)| (*fingerprint, alias.clone()))
+            .collect();
         for spending_key in &self.spending_keys {
             if let Some(key) = spending_key.key.as_ref() {
                 map.insert(key.master_fingerprint(), spending_key.name.clone());
@@ -281,13 +945,86 @@ impl Step for DefineDescriptor {
     // Verification of the values is happening when the user click on Next button.
     fn update(&mut self, message: Message) -> Command<Message> {
         self.error = None;
+        // Any edit of the keys or of the paths results in a completely different set of addresses.
+        let first_address = if matches!(
+            message,
+            Message::DefineDescriptor(message::DefineDescriptor::PrimaryPath(_))
+                | Message::DefineDescriptor(message::DefineDescriptor::RecoveryPath(..))
+                | Message::DefineDescriptor(message::DefineDescriptor::UseHotPrimaryKey(_))
+        ) {
+            self.first_address()
+        } else {
+            None
+        };
+        // The compared primary keys are outdated once the primary path is edited.
+        if matches!(
+            message,
+            Message::Network(_)
+                | Message::DefineDescriptor(message::DefineDescriptor::PrimaryPath(_))
+        ) {
+            self.primary_key_variants = None;
+        }
         match message {
             Message::Close => {
                 self.modal = None;
             }
             Message::Network(network) => self.set_network(network),
+            Message::DefineDescriptor(message::DefineDescriptor::CompareHotPrimaryKey) => {
+                if self.can_compare_hot_primary_key() {
+                    if let Some(hot) = self.hot_signer_key() {
+                        self.primary_key_variants = Some(PrimaryKeyVariants {
+                            hardware: self.spending_keys[0].clone(),
+                            hot,
+                        });
+                    }
+                }
+            }
+            Message::DefineDescriptor(message::DefineDescriptor::UseHotPrimaryKey(use_hot)) => {
+                if let Some(variants) = &self.primary_key_variants {
+                    self.spending_keys[0] = if use_hot {
+                        variants.hot.clone()
+                    } else {
+                        variants.hardware.clone()
+                    };
+                    self.check_for_duplicate();
+                    self.invalidate_acknowledged_warnings();
+                }
+            }
+            Message::AcknowledgeWarning(warning, input, acknowledged) => {
+                let mut acknowledged_warnings = self.acknowledged_warnings.lock().unwrap();
+                if acknowledged {
+                    acknowledged_warnings.acknowledge(warning, input);
+                } else {
+                    acknowledged_warnings.forget(warning);
+                }
+            }
+            Message::DefineDescriptor(message::DefineDescriptor::KeyAliasesEdited(input)) => {
+                match load_aliases(&input) {
+                    Ok(aliases) => {
+                        self.aliases_input.valid = true;
+                        self.imported_aliases = aliases;
+                        self.apply_aliases();
+                    }
+                    Err(_) => self.aliases_input.valid = input.trim().is_empty(),
+                }
+                self.aliases_input.value = input;
+            }
             Message::DefineDescriptor(message::DefineDescriptor::AddRecoveryPath) => {
-                self.recovery_paths.push(RecoveryPath::new());
+                self.recovery_paths
+                    .push(RecoveryPath::new(self.next_recovery_sequence()));
+                self.check_for_duplicate();
+                self.invalidate_acknowledged_warnings();
+            }
+            Message::DefineDescriptor(message::DefineDescriptor::AddHotRecoveryPath) => {
+                if !self.hot_signer_in_recovery_path() {
+                    if let Some(key) = self.hot_signer_key() {
+                        let mut path = RecoveryPath::new(self.next_recovery_sequence());
+                        path.keys = vec![key];
+                        self.recovery_paths.push(path);
+                        self.check_for_duplicate();
+                        self.invalidate_acknowledged_warnings();
+                    }
+                }
             }
             Message::DefineDescriptor(message::DefineDescriptor::PrimaryPath(msg)) => match msg {
                 message::DefinePath::ThresholdEdited(value) => {
@@ -315,6 +1052,7 @@ impl Step for DefineDescriptor {
                         }
                         self.modal = None;
                         self.check_for_duplicate();
+                        self.invalidate_acknowledged_warnings();
                     }
                     message::DefineKey::Edit => {
                         if let Some(spending_key) = self.spending_keys.get(i) {
@@ -339,6 +1077,7 @@ impl Step for DefineDescriptor {
                             self.spending_threshold -= 1;
                         }
                         self.check_for_duplicate();
+                        self.invalidate_acknowledged_warnings();
                     }
                 },
                 _ => {}
@@ -356,6 +1095,7 @@ impl Step for DefineDescriptor {
                         path.sequence = seq;
                     }
                     self.check_for_duplicate();
+                    self.invalidate_acknowledged_warnings();
                 }
                 message::DefinePath::EditSequence => {
                     if let Some(path) = self.recovery_paths.get(i) {
@@ -390,6 +1130,7 @@ impl Step for DefineDescriptor {
                         }
                         self.modal = None;
                         self.check_for_duplicate();
+                        self.invalidate_acknowledged_warnings();
                     }
                     message::DefineKey::Edit => {
                         if let Some(key) =
@@ -426,6 +1167,7 @@ impl Step for DefineDescriptor {
                             self.recovery_paths.remove(i);
                         }
                         self.check_for_duplicate();
+                        self.invalidate_acknowledged_warnings();
                     }
                 },
             },
@@ -435,106 +1177,115 @@ impl Step for DefineDescriptor {
                 }
             }
         };
+        if let Some(before) = first_address {
+            self.address_change = match self.first_address() {
+                Some(after) if after != before => Some((before, after)),
+                Some(_) => self.address_change.take(),
+                None => None,
+            };
+        }
         Command::none()
     }
 
     fn load_context(&mut self, ctx: &Context) {
         self.data_dir = Some(ctx.data_dir.clone());
-        self.set_network(ctx.bitcoin_config.network)
+        self.acknowledged_warnings = ctx.acknowledged_warnings.clone();
+        self.set_network(ctx.bitcoin_config.network);
+        // The policy is edited from the one given by another step, not from the one this step
+        // defined.
+        if let Some(builder) = &ctx.policy_builder {
+            if self.applied_policy.as_ref() != Some(builder) {
+                self.load_policy(builder);
+            }
+        }
+    }
+
+    /// The hot signer is kept: its keys may already be used by the other steps. So is the
+    /// policy last applied, for it not to be loaded back in place of the defaults.
+    fn reset(&mut self) {
+        let applied_policy = self.applied_policy.take();
+        *self = Self::new(self.signer.clone());
+        self.applied_policy = applied_policy;
+    }
+
+    fn summary_section(&self) -> Option<SummarySection> {
+        Some(SummarySection::Descriptor)
     }
 
     fn apply(&mut self, ctx: &mut Context) -> bool {
+        // Underlying conditions are checked again, the acknowledgement must match them.
+        if self
+            .warnings()
+            .iter()
+            .any(|(_, _, acknowledged)| !acknowledged)
+        {
+            return false;
+        }
         ctx.bitcoin_config.network = self.network;
         ctx.keys = Vec::new();
         let mut hw_is_used = false;
-        let mut spending_keys: Vec<DescriptorPublicKey> = Vec::new();
-        for spending_key in self.spending_keys.iter().clone() {
-            if let Some(DescriptorPublicKey::XPub(xpub)) = spending_key.key.as_ref() {
+        for key in self
+            .spending_keys
+            .iter()
+            .chain(self.recovery_paths.iter().flat_map(|path| path.keys.iter()))
+        {
+            if let Some(DescriptorPublicKey::XPub(xpub)) = key.key.as_ref() {
                 if let Some((master_fingerprint, _)) = xpub.origin {
                     ctx.keys.push(KeySetting {
                         master_fingerprint,
-                        name: spending_key.name.clone(),
+                        name: key.name.clone(),
                     });
-                    if spending_key.device_kind.is_some() {
+                    if key.device_kind.is_some() {
                         hw_is_used = true;
                     }
                 }
-                let xpub = DescriptorMultiXKey {
-                    origin: xpub.origin.clone(),
-                    xkey: xpub.xkey,
-                    derivation_paths: DerivPaths::new(vec![
-                        DerivationPath::from_str("m/0").unwrap(),
-                        DerivationPath::from_str("m/1").unwrap(),
-                    ])
-                    .unwrap(),
-                    wildcard: Wildcard::Unhardened,
-                };
-                spending_keys.push(DescriptorPublicKey::MultiXPub(xpub));
-            }
-        }
-
-        let mut recovery_paths = BTreeMap::new();
-
-        for path in self.recovery_paths.iter_mut() {
-            let mut recovery_keys: Vec<DescriptorPublicKey> = Vec::new();
-            for recovery_key in path.keys.iter().clone() {
-                if let Some(DescriptorPublicKey::XPub(xpub)) = recovery_key.key.as_ref() {
-                    if let Some((master_fingerprint, _)) = xpub.origin {
-                        ctx.keys.push(KeySetting {
-                            master_fingerprint,
-                            name: recovery_key.name.clone(),
-                        });
-                        if recovery_key.device_kind.is_some() {
-                            hw_is_used = true;
-                        }
-                    }
-                    let xpub = DescriptorMultiXKey {
-                        origin: xpub.origin.clone(),
-                        xkey: xpub.xkey,
-                        derivation_paths: DerivPaths::new(vec![
-                            DerivationPath::from_str("m/0").unwrap(),
-                            DerivationPath::from_str("m/1").unwrap(),
-                        ])
-                        .unwrap(),
-                        wildcard: Wildcard::Unhardened,
-                    };
-                    recovery_keys.push(DescriptorPublicKey::MultiXPub(xpub));
-                }
             }
-
-            let recovery_keys = if recovery_keys.len() == 1 {
-                PathInfo::Single(recovery_keys[0].clone())
-            } else {
-                PathInfo::Multi(path.threshold, recovery_keys)
-            };
-
-            recovery_paths.insert(path.sequence, recovery_keys);
         }
 
-        if !self.network_valid || spending_keys.is_empty() {
+        let builder = self.policy_builder();
+        if !self.network_valid || builder.primary.keys.is_empty() {
             return false;
         }
 
-        let spending_keys = if spending_keys.len() == 1 {
-            PathInfo::Single(spending_keys[0].clone())
-        } else {
-            PathInfo::Multi(self.spending_threshold, spending_keys)
-        };
-
-        let policy = match LianaPolicy::new(spending_keys, recovery_paths) {
-            Ok(policy) => policy,
+        let descriptor = match builder.to_descriptor() {
+            Ok(descriptor) => descriptor,
             Err(e) => {
-                self.error = Some(e.to_string());
+                self.error = Some(e);
                 return false;
             }
         };
+        if let Err(e) = check_descriptor_timelocks(&descriptor) {
+            self.error = Some(e);
+            return false;
+        }
+
+        // The builder is checked against the keys and paths as entered, the descriptor is
+        // the most critical piece of the wallet.
+        if let Err(e) = second_opinion(
+            &descriptor,
+            &defined_path(&self.spending_keys, self.spending_threshold),
+            &self
+                .recovery_paths
+                .iter()
+                .map(|path| (path.sequence, defined_path(&path.keys, path.threshold)))
+                .collect(),
+        ) {
+            self.error = Some(format!(
+                "The descriptor does not match the policy defined, this is a bug: {}",
+                e
+            ));
+            return false;
+        }
 
-        ctx.descriptor = Some(LianaDescriptor::new(policy));
+        ctx.descriptor = Some(descriptor);
+        ctx.policy_builder = Some(builder.clone());
+        self.applied_policy = Some(builder);
         ctx.hw_is_used = hw_is_used;
         true
     }
 
     fn view(&self, progress: (usize, usize)) -> Element<Message> {
+        let preview = self.preview_descriptor();
         let content = view::define_descriptor(
             progress,
             self.network,
@@ -560,6 +1311,27 @@ impl Step for DefineDescriptor {
                     })
                 })
                 .collect(),
+            self.warnings(),
+            preview
+                .as_ref()
+                .map(|(desc, placeholders)| preview_string(desc, placeholders))
+                .map_err(|e| e.clone()),
+            self.policy_summary(),
+            preview
+                .as_ref()
+                .map(|(desc, _)| witness_sizes_summary(desc))
+                .unwrap_or_default(),
+            self.address_change.as_ref(),
+            &self.aliases_input,
+            self.can_compare_hot_primary_key(),
+            !self.hot_signer_in_recovery_path(),
+            self.primary_key_variants.as_ref().map(|variants| {
+                (
+                    self.variant_preview(&variants.hardware),
+                    self.variant_preview(&variants.hot),
+                    self.spending_keys[0].key == variants.hot.key,
+                )
+            }),
             self.valid(),
             self.error.as_ref(),
         );
@@ -577,6 +1349,7 @@ impl Step for DefineDescriptor {
     }
 }
 
+#[derive(Clone)]
 pub struct DescriptorKey {
     pub name: String,
     pub device_kind: Option<DeviceKind>,
@@ -619,6 +1392,65 @@ impl DescriptorKey {
     }
 }
 
+/// Check the origin of a pasted key expression `[fingerprint/derivation/path]xpub`. Its syntax is
+/// checked first, to point out the malformed part of it instead of failing to parse the whole
+/// key. Then as the fingerprint is the one of the master key, it can only be compared to what the
+/// extended key reveals of its ancestry: its depth, and its parent fingerprint if it was derived
+/// right from the master key.
+///
+/// Returns an error if the origin is malformed and a warning if it is inconsistent with the key.
+pub fn check_key_origin(key: &str) -> Result<Option<String>, String> {
+    let (origin, xpub) = match key.trim().strip_prefix('[') {
+        Some(key) => key
+            .split_once(']')
+            .ok_or_else(|| "The origin of the key is missing its closing bracket.".to_string())?,
+        None => return Ok(None),
+    };
+    let mut steps = origin.split('/');
+    let fingerprint = steps.next().unwrap_or_default();
+    if fingerprint.len() != 8 || !fingerprint.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!(
+            "The master key fingerprint '{}' must be 8 hexadecimal characters.",
+            fingerprint
+        ));
+    }
+    let mut depth = 0;
+    for step in steps {
+        if ChildNumber::from_str(step).is_err() {
+            return Err(format!(
+                "The derivation step '{}' of the key origin is not a valid index.",
+                step
+            ));
+        }
+        depth += 1;
+    }
+
+    let xkey = match DescriptorPublicKey::from_str(&format!("[{}]{}", origin, xpub)) {
+        Ok(DescriptorPublicKey::XPub(key)) => key.xkey,
+        _ => return Ok(None),
+    };
+    let fingerprint = Fingerprint::from_str(fingerprint).expect("Checked above");
+    if usize::from(xkey.depth) != depth {
+        Ok(Some(format!(
+            "The key origin has {} derivation steps but the key is at depth {}.",
+            depth, xkey.depth
+        )))
+    } else if xkey.depth == 0 && xkey.fingerprint() != fingerprint {
+        Ok(Some(format!(
+            "The key is a master key of fingerprint {}, not {}.",
+            xkey.fingerprint(),
+            fingerprint
+        )))
+    } else if xkey.depth == 1 && xkey.parent_fingerprint != fingerprint {
+        Ok(Some(format!(
+            "The key was derived from a master key of fingerprint {}, not {}.",
+            xkey.parent_fingerprint, fingerprint
+        )))
+    } else {
+        Ok(None)
+    }
+}
+
 fn check_key_network(key: &DescriptorPublicKey, network: Network) -> bool {
     match key {
         DescriptorPublicKey::XPub(key) => {
@@ -718,6 +1550,8 @@ pub struct EditXpubModal {
 
     form_name: form::Value<String>,
     form_xpub: form::Value<String>,
+    /// The result of the check of the origin of the entered key, see [`check_key_origin`].
+    key_origin: Result<Option<String>, String>,
     edit_name: bool,
 
     hws: Vec<HardwareWallet>,
@@ -748,6 +1582,7 @@ impl EditXpubModal {
                 valid: true,
                 value: key.map(|k| k.to_string()).unwrap_or_else(String::new),
             },
+            key_origin: Ok(None),
             keys_aliases,
             account_indexes,
             path_index,
@@ -827,6 +1662,7 @@ impl DescriptorEditModal for EditXpubModal {
                 let fingerprint = self.hot_signer.lock().unwrap().fingerprint();
                 self.chosen_signer = Some((fingerprint, None));
                 self.form_xpub.valid = true;
+                self.key_origin = Ok(None);
                 if let Some(alias) = self.keys_aliases.get(&fingerprint) {
                     self.form_name.valid = true;
                     self.form_name.value = alias.clone();
@@ -866,6 +1702,7 @@ impl DescriptorEditModal for EditXpubModal {
                             }
                             self.form_xpub.valid = true;
                             self.form_xpub.value = key.to_string();
+                            self.key_origin = Ok(None);
                         }
                         Err(e) => {
                             self.chosen_signer = None;
@@ -881,7 +1718,14 @@ impl DescriptorEditModal for EditXpubModal {
                     self.form_name.value = name;
                 }
                 message::ImportKeyModal::XPubEdited(s) => {
-                    if let Ok(DescriptorPublicKey::XPub(key)) = DescriptorPublicKey::from_str(&s) {
+                    // The key may have been shared by a mobile companion in its own format.
+                    let s = parse_key_payload(&s).unwrap_or(s);
+                    self.key_origin = check_key_origin(&s);
+                    if self.key_origin.is_err() {
+                        self.form_xpub.valid = false;
+                    } else if let Ok(DescriptorPublicKey::XPub(key)) =
+                        DescriptorPublicKey::from_str(&s)
+                    {
                         self.chosen_signer = None;
                         if let Some((fingerprint, _)) = key.origin {
                             self.form_xpub.valid = true;
@@ -950,6 +1794,7 @@ impl DescriptorEditModal for EditXpubModal {
             &self.hot_signer_fingerprint,
             self.keys_aliases.get(&self.hot_signer_fingerprint),
             &self.form_xpub,
+            &self.key_origin,
             &self.form_name,
             self.edit_name,
         )
@@ -1223,12 +2068,283 @@ impl From<ParticipateXpub> for Box<dyn Step> {
     }
 }
 
-pub struct ImportDescriptor {
-    network: Network,
-    network_valid: bool,
-    change_network: bool,
-    data_dir: Option<PathBuf>,
-    imported_descriptor: form::Value<String>,
+/// Reconstruct the descriptor of the wallet from the witness script and the BIP32 derivations
+/// of a PSBT input. The extended keys are looked up in the global xpubs of the PSBT, then in the
+/// ones provided by the user. The origins of the missing extended keys are listed in the error.
+pub fn descriptor_from_psbt(
+    psbt: &Psbt,
+    user_xpubs: &BTreeMap<ExtendedPubKey, KeySource>,
+) -> Result<LianaDescriptor, String> {
+    let input = psbt
+        .inputs
+        .iter()
+        .find(|input| input.witness_script.is_some())
+        .ok_or_else(|| "The PSBT does not have any input with a witness script".to_string())?;
+    let ms = Miniscript::<bitcoin::PublicKey, Segwitv0>::parse(
+        input.witness_script.as_ref().expect("Checked above"),
+    )
+    .map_err(|e| format!("Failed to parse the witness script: {}", e))?;
+
+    let secp = secp256k1::Secp256k1::verification_only();
+    let mut keys = HashMap::new();
+    let mut missing = Vec::new();
+    for pk in ms.iter_pk() {
+        let (fingerprint, path) = match input.bip32_derivation.get(&pk.inner) {
+            Some(source) if source.1.len() >= 2 => source,
+            _ => {
+                missing.push(format!("origin of the key {}", pk));
+                continue;
+            }
+        };
+        // The last two derivation steps are the branch and the index of the address.
+        let (origin_path, steps) = path.as_ref().split_at(path.len() - 2);
+        let origin_path = DerivationPath::from(origin_path);
+        let xkey = psbt
+            .xpub
+            .iter()
+            .chain(user_xpubs.iter())
+            .find(|(xpub, (fg, p))| {
+                fg == fingerprint
+                    && *p == origin_path
+                    && xpub
+                        .derive_pub(&secp, &steps)
+                        .map(|derived| derived.public_key == pk.inner)
+                        .unwrap_or(false)
+            })
+            .map(|(xpub, _)| *xpub);
+        if let Some(xkey) = xkey {
+            keys.insert(
+                pk,
+                multipath_key(&DescriptorXKey {
+                    origin: Some((*fingerprint, origin_path)),
+                    xkey,
+                    derivation_path: DerivationPath::master(),
+                    wildcard: Wildcard::Unhardened,
+                }),
+            );
+        } else {
+            missing.push(format!(
+                "extended key [{}{}]",
+                fingerprint,
+                origin_path.to_string().trim_start_matches('m')
+            ));
+        }
+    }
+    if !missing.is_empty() {
+        missing.sort();
+        missing.dedup();
+        return Err(format!("Missing {}", missing.join(", ")));
+    }
+
+    struct KeyMap(HashMap<bitcoin::PublicKey, DescriptorPublicKey>);
+    impl Translator<bitcoin::PublicKey, DescriptorPublicKey, ()> for KeyMap {
+        fn pk(&mut self, pk: &bitcoin::PublicKey) -> Result<DescriptorPublicKey, ()> {
+            self.0.get(pk).cloned().ok_or(())
+        }
+        translate_hash_clone!(bitcoin::PublicKey, DescriptorPublicKey, ());
+    }
+    let ms = ms
+        .translate_pk(&mut KeyMap(keys))
+        .expect("All keys were found above");
+    let desc = Descriptor::new_wsh(ms).map_err(|e| e.to_string())?;
+    LianaDescriptor::from_str(&desc.to_string())
+        .map_err(|e| format!("The PSBT is not from a Liana wallet: {}", e))
+}
+
+/// Parse the extended keys with origins separated by whitespaces or commas.
+fn parse_xpubs_with_origin(s: &str) -> Result<BTreeMap<ExtendedPubKey, KeySource>, String> {
+    let mut xpubs = BTreeMap::new();
+    for key in s.split(|c: char| c.is_whitespace() || c == ',') {
+        if key.is_empty() {
+            continue;
+        }
+        match DescriptorPublicKey::from_str(key) {
+            Ok(DescriptorPublicKey::XPub(DescriptorXKey {
+                origin: Some(origin),
+                xkey,
+                ..
+            })) => {
+                xpubs.insert(xkey, origin);
+            }
+            _ => return Err(format!("Invalid extended key with origin: {}", key)),
+        }
+    }
+    Ok(xpubs)
+}
+
+/// The network the extended keys of the descriptor are encoded for, if they agree. Testnet
+/// stands for all the test networks, which share the same encoding.
+fn descriptor_network(descriptor: &LianaDescriptor) -> Option<Network> {
+    if descriptor.all_xpubs_net_is(Network::Bitcoin) {
+        Some(Network::Bitcoin)
+    } else if descriptor.all_xpubs_net_is(Network::Testnet) {
+        Some(Network::Testnet)
+    } else {
+        None
+    }
+}
+
+/// The network to switch to for the descriptor keys to be valid, if any. Errors if the keys are
+/// not all for the same network.
+fn descriptor_network_mismatch(
+    descriptor: &LianaDescriptor,
+    network: Network,
+) -> Result<Option<Network>, String> {
+    match descriptor_network(descriptor) {
+        None => Err("The keys of the descriptor are not all for the same network.".to_string()),
+        Some(Network::Bitcoin) if network != Network::Bitcoin => Ok(Some(Network::Bitcoin)),
+        Some(Network::Testnet) if network == Network::Bitcoin => Ok(Some(Network::Testnet)),
+        Some(_) => Ok(None),
+    }
+}
+
+/// A timelock required by a spending path of the Miniscript.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PathTimelock {
+    Relative(bitcoin::Sequence),
+    Absolute(u32),
+}
+
+/// The timelocks required by each of the ways the Miniscript can be satisfied, when only
+/// considering its timelocks. A miniscript that cannot be satisfied has none.
+fn spending_paths_timelocks(
+    node: &Terminal<DescriptorPublicKey, Segwitv0>,
+) -> Vec<Vec<PathTimelock>> {
+    let and = |a: Vec<Vec<PathTimelock>>, b: Vec<Vec<PathTimelock>>| {
+        let mut paths = Vec::new();
+        for x in &a {
+            for y in &b {
+                paths.push(x.iter().chain(y.iter()).copied().collect());
+            }
+        }
+        paths
+    };
+    match node {
+        Terminal::False => Vec::new(),
+        Terminal::Older(sequence) => vec![vec![PathTimelock::Relative(*sequence)]],
+        Terminal::After(locktime) => vec![vec![PathTimelock::Absolute(locktime.0)]],
+        Terminal::Alt(sub)
+        | Terminal::Swap(sub)
+        | Terminal::Check(sub)
+        | Terminal::DupIf(sub)
+        | Terminal::Verify(sub)
+        | Terminal::NonZero(sub)
+        | Terminal::ZeroNotEqual(sub) => spending_paths_timelocks(&sub.node),
+        Terminal::AndV(a, b) | Terminal::AndB(a, b) => and(
+            spending_paths_timelocks(&a.node),
+            spending_paths_timelocks(&b.node),
+        ),
+        Terminal::AndOr(a, b, c) => {
+            let mut paths = and(
+                spending_paths_timelocks(&a.node),
+                spending_paths_timelocks(&b.node),
+            );
+            paths.extend(spending_paths_timelocks(&c.node));
+            paths
+        }
+        Terminal::OrB(a, b) | Terminal::OrD(a, b) | Terminal::OrC(a, b) | Terminal::OrI(a, b) => {
+            let mut paths = spending_paths_timelocks(&a.node);
+            paths.extend(spending_paths_timelocks(&b.node));
+            paths
+        }
+        Terminal::Thresh(k, subs) => {
+            // Every combination of k of the sub-policies is a way to satisfy the threshold.
+            let subs: Vec<_> = subs
+                .iter()
+                .map(|sub| spending_paths_timelocks(&sub.node))
+                .collect();
+            let mut combinations: Vec<(usize, Vec<Vec<PathTimelock>>)> = vec![(0, vec![vec![]])];
+            for sub in subs {
+                let mut next = Vec::new();
+                for (count, paths) in combinations {
+                    if count < *k {
+                        next.push((count + 1, and(paths.clone(), sub.clone())));
+                    }
+                    next.push((count, paths));
+                }
+                combinations = next;
+            }
+            combinations
+                .into_iter()
+                .filter(|(count, _)| count == k)
+                .flat_map(|(_, paths)| paths)
+                .collect()
+        }
+        _ => vec![vec![]],
+    }
+}
+
+/// Check the timelocks of every spending path of the descriptor can be satisfied by a spending
+/// transaction: a path can never be used if it combines timelocks of different units, and
+/// Liana only sets the sequence of the inputs for relative timelocks in blocks.
+fn check_timelocks(ms: &Miniscript<DescriptorPublicKey, Segwitv0>) -> Result<(), String> {
+    for path in spending_paths_timelocks(&ms.node) {
+        let relative: Vec<_> = path
+            .iter()
+            .filter_map(|tl| match tl {
+                PathTimelock::Relative(sequence) => Some(*sequence),
+                _ => None,
+            })
+            .collect();
+        if let Some(sequence) = relative.iter().find(|s| s.is_time_locked()) {
+            if relative.iter().any(|s| s.is_height_locked()) {
+                return Err(format!(
+                    "A spending path requires both a relative timelock in blocks and one in time (sequence {}): no transaction can satisfy both, the path could never be used.",
+                    sequence.to_consensus_u32()
+                ));
+            }
+            return Err(format!(
+                "A spending path requires a relative timelock in time (sequence {}): Liana only supports relative timelocks in blocks, the path could never be used.",
+                sequence.to_consensus_u32()
+            ));
+        }
+        let absolute: Vec<_> = path
+            .iter()
+            .filter_map(|tl| match tl {
+                PathTimelock::Absolute(locktime) => Some(*locktime),
+                _ => None,
+            })
+            .collect();
+        // Locktimes below this threshold are block heights, timestamps above.
+        if absolute.iter().any(|l| *l < 500_000_000) && absolute.iter().any(|l| *l >= 500_000_000) {
+            return Err("A spending path requires both an absolute timelock in blocks and one in time: no transaction can satisfy both, the path could never be used.".to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Check the timelocks of the spending paths of a Liana descriptor, see `check_timelocks`.
+fn check_descriptor_timelocks(descriptor: &LianaDescriptor) -> Result<(), String> {
+    let desc = Descriptor::<DescriptorPublicKey>::from_str(&descriptor.to_string())
+        .map_err(|e| e.to_string())?;
+    match &desc {
+        Descriptor::Wsh(wsh) => match wsh.as_inner() {
+            WshInner::Ms(ms) => check_timelocks(ms),
+            _ => Err("The descriptor is not a Miniscript descriptor.".to_string()),
+        },
+        _ => Err("The descriptor is not a P2WSH descriptor.".to_string()),
+    }
+}
+
+pub struct ImportDescriptor {
+    network: Network,
+    network_valid: bool,
+    change_network: bool,
+    data_dir: Option<PathBuf>,
+    imported_descriptor: form::Value<String>,
+    imported_psbt: form::Value<String>,
+    psbt_xpubs: form::Value<String>,
+    psbt_error: Option<String>,
+    aliases_input: form::Value<String>,
+    /// The aliases of the keys, only used to name them in the summary and in the settings.
+    aliases: KeyAliases,
+    /// The wallet of a bitcoind the descriptor is imported from, and the Liana descriptors found
+    /// in it.
+    core_address: form::Value<String>,
+    core_cookie_path: form::Value<String>,
+    core_wallet: form::Value<String>,
+    core_fetching: bool,
+    core_descriptors: Option<Result<Vec<String>, Error>>,
     error: Option<String>,
 }
 
@@ -1240,9 +2356,66 @@ impl ImportDescriptor {
             network_valid: true,
             data_dir: None,
             imported_descriptor: form::Value::default(),
+            imported_psbt: form::Value::default(),
+            psbt_xpubs: form::Value::default(),
+            psbt_error: None,
+            aliases_input: form::Value::default(),
+            aliases: KeyAliases::new(),
+            core_address: form::Value::default(),
+            core_cookie_path: form::Value::default(),
+            core_wallet: form::Value::default(),
+            core_fetching: false,
+            core_descriptors: None,
             error: None,
         }
     }
+
+    /// Use the default connection of bitcoind for the network, unless the user edited it.
+    fn set_core_defaults(&mut self, previous: Option<Network>) {
+        let previous_address = previous.map(|network| bitcoind_default_address(&network));
+        if self.core_address.value.is_empty()
+            || Some(&self.core_address.value) == previous_address.as_ref()
+        {
+            self.core_address.value = bitcoind_default_address(&self.network);
+        }
+        let previous_cookie_path =
+            previous.and_then(|network| bitcoind_default_cookie_path(&network));
+        if self.core_cookie_path.value.is_empty()
+            || Some(&self.core_cookie_path.value) == previous_cookie_path.as_ref()
+        {
+            self.core_cookie_path.value =
+                bitcoind_default_cookie_path(&self.network).unwrap_or_default();
+        }
+    }
+
+    fn import_from_psbt(&mut self) {
+        self.psbt_error = None;
+        let psbt = match base64::decode(self.imported_psbt.value.trim())
+            .ok()
+            .and_then(|bytes| consensus::encode::deserialize::<Psbt>(&bytes).ok())
+        {
+            Some(psbt) => psbt,
+            None => {
+                self.imported_psbt.valid = false;
+                return;
+            }
+        };
+        let xpubs = match parse_xpubs_with_origin(&self.psbt_xpubs.value) {
+            Ok(xpubs) => xpubs,
+            Err(e) => {
+                self.psbt_xpubs.valid = false;
+                self.psbt_error = Some(e);
+                return;
+            }
+        };
+        match descriptor_from_psbt(&psbt, &xpubs) {
+            Ok(desc) => {
+                self.imported_descriptor.value = desc.to_string();
+                self.imported_descriptor.valid = true;
+            }
+            Err(e) => self.psbt_error = Some(e),
+        }
+    }
 }
 
 impl Step for ImportDescriptor {
@@ -1251,28 +2424,115 @@ impl Step for ImportDescriptor {
     fn update(&mut self, message: Message) -> Command<Message> {
         match message {
             Message::Network(network) => {
+                let previous = self.network;
                 self.network = network;
+                self.set_core_defaults(Some(previous));
                 let mut network_datadir = self.data_dir.clone().unwrap();
                 network_datadir.push(self.network.to_string());
                 self.network_valid = !network_datadir.exists();
             }
+            Message::DefineDescriptor(message::DefineDescriptor::CoreWallet(msg)) => match msg {
+                message::CoreWallet::AddressEdited(address) => {
+                    self.core_address.valid = true;
+                    self.core_address.value = address;
+                }
+                message::CoreWallet::CookiePathEdited(path) => {
+                    self.core_cookie_path.valid = true;
+                    self.core_cookie_path.value = path;
+                }
+                message::CoreWallet::WalletEdited(wallet) => {
+                    self.core_wallet.value = wallet;
+                }
+                message::CoreWallet::ListDescriptors => {
+                    self.core_address.valid =
+                        std::net::SocketAddr::from_str(self.core_address.value.trim()).is_ok();
+                    self.core_cookie_path.valid = !self.core_cookie_path.value.trim().is_empty();
+                    if self.core_address.valid && self.core_cookie_path.valid {
+                        self.core_fetching = true;
+                        self.core_descriptors = None;
+                        return Command::perform(
+                            list_wallet_descriptors(
+                                self.core_address.value.trim().to_string(),
+                                PathBuf::from(self.core_cookie_path.value.trim()),
+                                self.core_wallet.value.trim().to_string(),
+                            ),
+                            |res| {
+                                Message::DefineDescriptor(message::DefineDescriptor::CoreWallet(
+                                    message::CoreWallet::Descriptors(res),
+                                ))
+                            },
+                        );
+                    }
+                }
+                message::CoreWallet::Descriptors(res) => {
+                    self.core_fetching = false;
+                    let res = res.map(|descs| {
+                        liana_descriptors_from_core(&descs)
+                            .iter()
+                            .map(|desc| desc.to_string())
+                            .collect::<Vec<_>>()
+                    });
+                    // A single Liana descriptor in the wallet is the one to import.
+                    if let Ok([desc]) = res.as_deref() {
+                        self.imported_descriptor.value = desc.clone();
+                        self.imported_descriptor.valid = true;
+                    }
+                    self.core_descriptors = Some(res);
+                }
+            },
             Message::DefineDescriptor(message::DefineDescriptor::ImportDescriptor(desc)) => {
-                self.imported_descriptor.value = desc;
+                // The descriptor may have been shared by a mobile companion in its own format.
+                self.imported_descriptor.value = parse_descriptor_payload(&desc).unwrap_or(desc);
                 self.imported_descriptor.valid = true;
             }
+            Message::DefineDescriptor(message::DefineDescriptor::KeyAliasesEdited(input)) => {
+                match load_aliases(&input) {
+                    Ok(aliases) => {
+                        self.aliases_input.valid = true;
+                        self.aliases = aliases;
+                    }
+                    Err(_) => {
+                        self.aliases_input.valid = input.trim().is_empty();
+                        self.aliases = KeyAliases::new();
+                    }
+                }
+                self.aliases_input.value = input;
+            }
+            Message::DefineDescriptor(message::DefineDescriptor::PsbtEdited(psbt)) => {
+                self.imported_psbt.value = psbt;
+                self.imported_psbt.valid = true;
+                self.psbt_error = None;
+            }
+            Message::DefineDescriptor(message::DefineDescriptor::PsbtXpubsEdited(xpubs)) => {
+                self.psbt_xpubs.value = xpubs;
+                self.psbt_xpubs.valid = true;
+                self.psbt_error = None;
+            }
+            Message::DefineDescriptor(message::DefineDescriptor::ImportFromPsbt) => {
+                self.import_from_psbt();
+            }
             _ => {}
         };
         Command::none()
     }
 
+    fn reset(&mut self) {
+        *self = Self::new(self.change_network);
+    }
+
     fn load_context(&mut self, ctx: &Context) {
         self.network = ctx.bitcoin_config.network;
+        self.set_core_defaults(None);
         self.data_dir = Some(ctx.data_dir.clone());
         let mut network_datadir = ctx.data_dir.clone();
         network_datadir.push(self.network.to_string());
         self.network_valid = !network_datadir.exists();
     }
 
+    fn summary_section(&self) -> Option<SummarySection> {
+        Some(SummarySection::Descriptor)
+    }
+
     fn apply(&mut self, ctx: &mut Context) -> bool {
         ctx.bitcoin_config.network = self.network;
         // Set to true in order to force the registration process to be shown to user.
@@ -1280,7 +2540,28 @@ impl Step for ImportDescriptor {
         // descriptor forms for import or creation cannot be both empty or filled.
         if !self.imported_descriptor.value.is_empty() {
             if let Ok(desc) = LianaDescriptor::from_str(&self.imported_descriptor.value) {
+                if let Err(e) = descriptor_network_mismatch(&desc, self.network) {
+                    self.error = Some(e);
+                    return false;
+                }
                 self.imported_descriptor.valid = true;
+                self.error = None;
+                let policy = desc.policy();
+                ctx.keys = std::iter::once(policy.primary_path())
+                    .chain(policy.recovery_paths().values())
+                    .flat_map(|path| path.thresh_origins().1.into_iter().map(|(fp, _)| fp))
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
+                    .filter_map(|master_fingerprint| {
+                        self.aliases
+                            .get(&master_fingerprint)
+                            .map(|name| KeySetting {
+                                master_fingerprint,
+                                name: name.clone(),
+                            })
+                    })
+                    .collect();
+                ctx.policy_builder = PolicyBuilder::from_descriptor(&desc, &self.aliases).ok();
                 ctx.descriptor = Some(desc);
                 true
             } else {
@@ -1293,47 +2574,473 @@ impl Step for ImportDescriptor {
     }
 
     fn view(&self, progress: (usize, usize)) -> Element<Message> {
+        let descriptor = LianaDescriptor::from_str(&self.imported_descriptor.value).ok();
         view::import_descriptor(
             progress,
             self.change_network,
             self.network,
             self.network_valid,
             &self.imported_descriptor,
+            &self.imported_psbt,
+            &self.psbt_xpubs,
+            self.psbt_error.as_ref(),
+            descriptor
+                .as_ref()
+                .map(witness_sizes_summary)
+                .unwrap_or_default(),
+            descriptor
+                .as_ref()
+                .map(|desc| descriptor_network_mismatch(desc, self.network)),
+            descriptor
+                .as_ref()
+                .map(|desc| descriptor_policy_summary(desc, &self.aliases))
+                .unwrap_or_default(),
+            &self.aliases_input,
+            (
+                &self.core_address,
+                &self.core_cookie_path,
+                &self.core_wallet,
+                self.core_fetching,
+                self.core_descriptors.as_ref(),
+            ),
             self.error.as_ref(),
         )
     }
 }
 
+/// The descriptors of a wallet of bitcoind, as returned by `listdescriptors`.
+async fn list_wallet_descriptors(
+    address: String,
+    cookie_path: PathBuf,
+    wallet: String,
+) -> Result<Vec<String>, Error> {
+    let cookie = std::fs::read_to_string(&cookie_path)
+        .map_err(|e| Error::BitcoindCookieUnreadable(e.to_string()))?;
+    // Without a wallet name, bitcoind uses its only loaded wallet.
+    let url = if wallet.is_empty() {
+        address.clone()
+    } else {
+        format!("http://{}/wallet/{}", address, wallet)
+    };
+    let client = Client::with_transport(
+        SimpleHttpTransport::builder()
+            .url(&url)?
+            .timeout(std::time::Duration::from_secs(BITCOIND_TIMEOUT_SECS))
+            .cookie_auth(cookie)
+            .build(),
+    );
+    let res = client
+        .send_request(client.build_request("listdescriptors", &[]))
+        .map_err(|e| connection_error(&address, e))?
+        .result::<serde_json::Value>()
+        .map_err(|e| Error::Bitcoind(format!("Failed to list the descriptors: {}", e)))?;
+    Ok(res
+        .get("descriptors")
+        .and_then(serde_json::Value::as_array)
+        .map(|descs| {
+            descs
+                .iter()
+                .filter_map(|desc| desc.get("desc").and_then(serde_json::Value::as_str))
+                .map(|desc| desc.to_string())
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// The Liana descriptors among the ones of a wallet of bitcoind. Bitcoind may store the receive
+/// and change descriptors apart, as `/0/*` and `/1/*` derivations: they are merged back.
+pub fn liana_descriptors_from_core(descs: &[String]) -> Vec<LianaDescriptor> {
+    let descs: Vec<&str> = descs
+        .iter()
+        .map(|desc| desc.split_once('#').map(|(d, _)| d).unwrap_or(desc))
+        .collect();
+    let mut found: Vec<LianaDescriptor> = Vec::new();
+    for desc in &descs {
+        let liana_desc = LianaDescriptor::from_str(desc).ok().or_else(|| {
+            if !desc.contains("/0/*") || !descs.contains(&desc.replace("/0/*", "/1/*").as_str()) {
+                return None;
+            }
+            LianaDescriptor::from_str(&desc.replace("/0/*", "/<0;1>/*")).ok()
+        });
+        if let Some(liana_desc) = liana_desc {
+            if !found
+                .iter()
+                .any(|d| d.to_string() == liana_desc.to_string())
+            {
+                found.push(liana_desc);
+            }
+        }
+    }
+    found
+}
+
 impl From<ImportDescriptor> for Box<dyn Step> {
     fn from(s: ImportDescriptor) -> Box<dyn Step> {
         Box::new(s)
     }
 }
 
+/// The number of receive and change addresses shown by the descriptor verification.
+const VERIFIED_ADDRESSES_COUNT: u32 = 3;
+
+/// Everything that can be learned from a descriptor without a connection to a node.
+#[derive(Debug, Clone)]
+pub struct DescriptorAnalysis {
+    pub checksum: String,
+    /// Whether the checksum was part of the verified input, in which case it was checked.
+    pub checksum_provided: bool,
+    pub network: Network,
+    pub policy_summary: Vec<String>,
+    /// The policy of each spending path, as a miniscript policy.
+    pub paths_policies: Vec<(String, String)>,
+    /// The origin of all the keys of the descriptor.
+    pub key_origins: Vec<String>,
+    pub receive_addresses: Vec<String>,
+    pub change_addresses: Vec<String>,
+    pub witness_sizes: Vec<(String, bool)>,
+}
+
+impl DescriptorAnalysis {
+    pub fn new(input: &str) -> Result<Self, String> {
+        let input = parse_descriptor_payload(input).unwrap_or_else(|| input.trim().to_string());
+        let descriptor = LianaDescriptor::from_str(&input).map_err(|e| e.to_string())?;
+        let checksum = descriptor
+            .to_string()
+            .split_once('#')
+            .map(|(_, checksum)| checksum.to_string())
+            .unwrap_or_default();
+        // Testnet addresses are also the signet ones.
+        let network = if descriptor.all_xpubs_net_is(Network::Bitcoin) {
+            Network::Bitcoin
+        } else {
+            Network::Testnet
+        };
+
+        let policy = descriptor.policy();
+        let mut paths_policies = Vec::new();
+        let mut key_origins = Vec::new();
+        for (sequence, path) in std::iter::once((None, policy.primary_path()))
+            .chain(policy.recovery_paths().iter().map(|(s, p)| (Some(*s), p)))
+        {
+            let (_, origins) = path.thresh_origins();
+            let ms_policy = path.clone().into_ms_policy().to_string();
+            paths_policies.push(match sequence {
+                None => ("Primary path".to_string(), ms_policy),
+                Some(sequence) => (
+                    format!("Recovery path after {} blocks", sequence),
+                    format!("and({},older({}))", ms_policy, sequence),
+                ),
+            });
+            for (fingerprint, derivation_path) in origins {
+                let origin = format!(
+                    "[{}{}]",
+                    fingerprint,
+                    derivation_path.to_string().trim_start_matches('m')
+                );
+                if !key_origins.contains(&origin) {
+                    key_origins.push(origin);
+                }
+            }
+        }
+
+        let secp = secp256k1::Secp256k1::verification_only();
+        let addresses = |desc: &liana::descriptors::SinglePathLianaDesc| {
+            (0..VERIFIED_ADDRESSES_COUNT)
+                .map(|i| {
+                    address_label(
+                        &desc
+                            .derive(
+                                ChildNumber::from_normal_idx(i).expect("Not hardened"),
+                                &secp,
+                            )
+                            .address(network),
+                    )
+                })
+                .collect()
+        };
+
+        Ok(Self {
+            checksum,
+            checksum_provided: input.contains('#'),
+            network,
+            policy_summary: descriptor_policy_summary(&descriptor, &KeyAliases::new()),
+            paths_policies,
+            key_origins,
+            receive_addresses: addresses(descriptor.receive_descriptor()),
+            change_addresses: addresses(descriptor.change_descriptor()),
+            witness_sizes: witness_sizes_summary(&descriptor),
+        })
+    }
+}
+
+/// A standalone tool to audit a descriptor, for instance a backup, without any node.
 #[derive(Default)]
+pub struct VerifyDescriptor {
+    descriptor: form::Value<String>,
+    analysis: Option<Result<DescriptorAnalysis, String>>,
+}
+
+impl Step for VerifyDescriptor {
+    fn update(&mut self, message: Message) -> Command<Message> {
+        if let Message::DefineDescriptor(message::DefineDescriptor::ImportDescriptor(desc)) =
+            message
+        {
+            self.analysis = if desc.trim().is_empty() {
+                None
+            } else {
+                Some(DescriptorAnalysis::new(&desc))
+            };
+            self.descriptor.valid = !matches!(self.analysis, Some(Err(_)));
+            self.descriptor.value = desc;
+        }
+        Command::none()
+    }
+
+    fn view(&self, progress: (usize, usize)) -> Element<Message> {
+        view::verify_descriptor(progress, &self.descriptor, self.analysis.as_ref())
+    }
+}
+
+impl From<VerifyDescriptor> for Box<dyn Step> {
+    fn from(s: VerifyDescriptor) -> Box<dyn Step> {
+        Box::new(s)
+    }
+}
+
+/// The format of the file a cosigner takes away to register the descriptor on their device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegistrationFormat {
+    Descriptor,
+    Specter,
+    WalletPolicy,
+}
+
+impl RegistrationFormat {
+    pub const ALL: [RegistrationFormat; 3] = [
+        RegistrationFormat::Descriptor,
+        RegistrationFormat::Specter,
+        RegistrationFormat::WalletPolicy,
+    ];
+
+    /// The format expected by the given device model, if known.
+    pub fn for_device(kind: Option<&DeviceKind>) -> Self {
+        if matches!(
+            kind,
+            Some(DeviceKind::Specter) | Some(DeviceKind::SpecterSimulator)
+        ) {
+            Self::Specter
+        } else if matches!(
+            kind,
+            Some(DeviceKind::Ledger) | Some(DeviceKind::LedgerSimulator)
+        ) {
+            Self::WalletPolicy
+        } else {
+            Self::Descriptor
+        }
+    }
+}
+
+impl std::fmt::Display for RegistrationFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Descriptor => write!(f, "Descriptor"),
+            Self::Specter => write!(f, "Specter DIY"),
+            Self::WalletPolicy => write!(f, "Ledger wallet policy"),
+        }
+    }
+}
+
+/// The content of the registration file for the given format.
+pub fn registration_artifact(descriptor: &LianaDescriptor, format: RegistrationFormat) -> String {
+    match format {
+        RegistrationFormat::Descriptor => descriptor.to_string(),
+        RegistrationFormat::Specter => format!("addwallet Liana&{}", descriptor),
+        RegistrationFormat::WalletPolicy => {
+            let policy = descriptor.policy();
+            let mut keys: Vec<DescriptorPublicKey> = Vec::new();
+            for path in
+                std::iter::once(policy.primary_path()).chain(policy.recovery_paths().values())
+            {
+                let path_keys = match path {
+                    PathInfo::Single(key) => vec![key.clone()],
+                    PathInfo::Multi(_, keys) => keys.clone(),
+                };
+                for key in path_keys {
+                    if !keys.contains(&key) {
+                        keys.push(key);
+                    }
+                }
+            }
+            let desc = descriptor.to_string();
+            let mut template = desc
+                .split_once('#')
+                .map(|(d, _)| d.to_string())
+                .unwrap_or(desc);
+            let mut keys_info = Vec::with_capacity(keys.len());
+            for (i, key) in keys.iter().enumerate() {
+                let key = key.to_string();
+                template = template.replace(&key, &format!("@{}/**", i));
+                keys_info.push(
+                    key.strip_suffix("/<0;1>/*")
+                        .map(|k| k.to_string())
+                        .unwrap_or(key),
+                );
+            }
+            serde_json::json!({
+                "name": "Liana",
+                "descriptor_template": template,
+                "keys_info": keys_info,
+            })
+            .to_string()
+        }
+    }
+}
+
 pub struct RegisterDescriptor {
     descriptor: Option<LianaDescriptor>,
+    /// The first receive address, to be compared with the one displayed by the devices.
+    first_address: Option<String>,
     keys_aliases: HashMap<Fingerprint, String>,
+    hot_signer: Arc<Mutex<Signer>>,
+    hot_signer_fingerprint: Fingerprint,
+    /// The devices of the cosigners the descriptor must be registered on.
+    cosigners: Vec<Fingerprint>,
+    formats: HashMap<Fingerprint, RegistrationFormat>,
+    exported: HashSet<Fingerprint>,
     processing: bool,
     chosen_hw: Option<usize>,
     hws: Vec<HardwareWallet>,
     hmacs: Vec<(Fingerprint, DeviceKind, Option<[u8; 32]>)>,
     registered: HashSet<Fingerprint>,
-    error: Option<Error>,
+    /// The devices the registration failed on, for instance because they were unplugged, with
+    /// the error. The user can retry on them once they are connected again.
+    failed: HashMap<Fingerprint, Error>,
+    /// The errors met while listing the devices. If no device could be listed, the user can
+    /// enter the key of the device by hand and register the descriptor with a file.
+    enumeration_errors: Vec<String>,
+    manual_entry: bool,
+    manual_key: form::Value<String>,
+    manual_device: Option<Result<Fingerprint, String>>,
+    /// The message the cosigners sign to confirm they registered the descriptor, the signatures
+    /// entered by the user and the cosigners they confirmed.
+    attestation_message: Option<String>,
+    attestations_input: form::Value<String>,
+    attested: HashSet<Fingerprint>,
+    attestation_errors: Vec<String>,
     done: bool,
 }
 
+impl RegisterDescriptor {
+    pub fn new(hot_signer: Arc<Mutex<Signer>>) -> Self {
+        let hot_signer_fingerprint = hot_signer.lock().unwrap().fingerprint();
+        Self {
+            descriptor: None,
+            first_address: None,
+            keys_aliases: HashMap::new(),
+            hot_signer,
+            hot_signer_fingerprint,
+            cosigners: Vec::new(),
+            formats: HashMap::new(),
+            exported: HashSet::new(),
+            processing: false,
+            chosen_hw: None,
+            hws: Vec::new(),
+            hmacs: Vec::new(),
+            registered: HashSet::new(),
+            failed: HashMap::new(),
+            enumeration_errors: Vec::new(),
+            manual_entry: false,
+            manual_key: form::Value::default(),
+            manual_device: None,
+            attestation_message: None,
+            attestations_input: form::Value::default(),
+            attested: HashSet::new(),
+            attestation_errors: Vec::new(),
+            done: false,
+        }
+    }
+
+    /// Whether the cosigner device has the descriptor registered: either registered from this
+    /// computer or confirmed by its owner.
+    fn confirmed(&self, fingerprint: &Fingerprint) -> bool {
+        self.registered.contains(fingerprint) || self.attested.contains(fingerprint)
+    }
+
+    /// The registration format of the cosigner device: the one chosen by the user, otherwise the
+    /// one of the model if the device is connected.
+    fn format(&self, fingerprint: &Fingerprint) -> RegistrationFormat {
+        self.formats.get(fingerprint).copied().unwrap_or_else(|| {
+            RegistrationFormat::for_device(
+                self.hws
+                    .iter()
+                    .find(|hw| hw.fingerprint().as_ref() == Some(fingerprint))
+                    .map(|hw| hw.kind()),
+            )
+        })
+    }
+}
+
 impl Step for RegisterDescriptor {
     fn load_context(&mut self, ctx: &Context) {
         self.descriptor = ctx.descriptor.clone();
+        self.hot_signer_fingerprint = self.hot_signer.lock().unwrap().fingerprint();
+        let secp = secp256k1::Secp256k1::verification_only();
+        let first_address = self.descriptor.as_ref().map(|desc| {
+            desc.receive_descriptor()
+                .derive(0.into(), &secp)
+                .address(ctx.bitcoin_config.network)
+        });
+        self.first_address = first_address.as_ref().map(address_label);
+        let attestation_message = first_address
+            .as_ref()
+            .map(|address| attestation_message(&address.to_string()));
+        // The confirmations are only valid for the descriptor they were made for.
+        if attestation_message != self.attestation_message {
+            self.attested = HashSet::new();
+            self.attestation_errors = Vec::new();
+        }
+        self.attestation_message = attestation_message;
         let mut map = HashMap::new();
         for key in ctx.keys.iter().filter(|k| !k.name.is_empty()) {
             map.insert(key.master_fingerprint, key.name.clone());
         }
         self.keys_aliases = map;
+        self.cosigners = Vec::new();
+        if let Some(desc) = &self.descriptor {
+            let policy = desc.policy();
+            for path in
+                std::iter::once(policy.primary_path()).chain(policy.recovery_paths().values())
+            {
+                for (fingerprint, _) in path.thresh_origins().1 {
+                    if fingerprint != self.hot_signer_fingerprint
+                        && !self.cosigners.contains(&fingerprint)
+                    {
+                        self.cosigners.push(fingerprint);
+                    }
+                }
+            }
+            self.cosigners.sort();
+        }
     }
     fn update(&mut self, message: Message) -> Command<Message> {
         match message {
+            Message::RegisterDescriptor(message::RegisterDescriptor::FormatSelected(
+                fingerprint,
+                format,
+            )) => {
+                self.formats.insert(fingerprint, format);
+            }
+            Message::RegisterDescriptor(message::RegisterDescriptor::ExportArtifact(
+                fingerprint,
+            )) => {
+                if let Some(desc) = &self.descriptor {
+                    self.exported.insert(fingerprint);
+                    return iced::clipboard::write(registration_artifact(
+                        desc,
+                        self.format(&fingerprint),
+                    ));
+                }
+            }
             Message::Select(i) => {
                 if let Some(HardwareWallet::Supported {
                     device,
@@ -1345,19 +3052,19 @@ impl Step for RegisterDescriptor {
                         let descriptor = self.descriptor.as_ref().unwrap().to_string();
                         self.chosen_hw = Some(i);
                         self.processing = true;
-                        self.error = None;
+                        self.failed.remove(fingerprint);
                         return Command::perform(
                             register_wallet(device.clone(), *fingerprint, descriptor),
-                            Message::WalletRegistered,
+                            |(fingerprint, res)| Message::WalletRegistered(fingerprint, res),
                         );
                     }
                 }
             }
-            Message::WalletRegistered(res) => {
+            Message::WalletRegistered(fingerprint, res) => {
                 self.processing = false;
                 self.chosen_hw = None;
                 match res {
-                    Ok((fingerprint, hmac)) => {
+                    Ok(hmac) => {
                         if let Some(hw_h) = self
                             .hws
                             .iter()
@@ -1367,14 +3074,70 @@ impl Step for RegisterDescriptor {
                             self.hmacs.push((fingerprint, *hw_h.kind(), hmac));
                         }
                     }
-                    Err(e) => self.error = Some(e),
+                    Err(e) => {
+                        self.failed.insert(fingerprint, e);
+                    }
                 }
             }
-            Message::ConnectedHardwareWallets(hws) => {
+            Message::RegisterDescriptor(
+                message::RegisterDescriptor::HardwareWalletsEnumerated(hws, errors),
+            ) => {
                 self.hws = hws;
+                self.enumeration_errors = errors;
+            }
+            Message::RegisterDescriptor(message::RegisterDescriptor::ManualEntry(manual)) => {
+                self.manual_entry = manual;
+                self.manual_key = form::Value::default();
+                self.manual_device = None;
+            }
+            Message::RegisterDescriptor(message::RegisterDescriptor::ManualKeyEdited(input)) => {
+                self.manual_device = match &self.descriptor {
+                    Some(desc) if !input.trim().is_empty() => {
+                        Some(manual_device_fingerprint(desc, &self.cosigners, &input))
+                    }
+                    _ => None,
+                };
+                self.manual_key.valid = !matches!(self.manual_device, Some(Err(_)));
+                self.manual_key.value = input;
+            }
+            Message::RegisterDescriptor(message::RegisterDescriptor::ManuallyRegistered(
+                fingerprint,
+            )) => {
+                if self.cosigners.contains(&fingerprint) {
+                    self.registered.insert(fingerprint);
+                    self.failed.remove(&fingerprint);
+                    self.manual_key = form::Value::default();
+                    self.manual_device = None;
+                }
+            }
+            Message::RegisterDescriptor(message::RegisterDescriptor::AttestationsEdited(input)) => {
+                self.attestations_input.valid = true;
+                self.attestations_input.value = input;
+            }
+            Message::RegisterDescriptor(message::RegisterDescriptor::ImportAttestations) => {
+                if let (Some(desc), Some(message)) = (&self.descriptor, &self.attestation_message) {
+                    let (confirmed, mut errors) =
+                        import_attestations(desc, message, &self.attestations_input.value);
+                    for fingerprint in confirmed {
+                        if self.cosigners.contains(&fingerprint) {
+                            self.attested.insert(fingerprint);
+                        } else {
+                            errors.push(format!(
+                                "The signature of #{} is not the one of a cosigner device",
+                                fingerprint
+                            ));
+                        }
+                    }
+                    self.attestations_input.valid = errors.is_empty();
+                    if errors.is_empty() {
+                        self.attestations_input = form::Value::default();
+                    }
+                    self.attestation_errors = errors;
+                }
             }
             Message::Reload => {
                 self.hws = Vec::new();
+                self.enumeration_errors = Vec::new();
                 return self.load();
             }
             Message::UserActionDone(done) => {
@@ -1387,8 +3150,18 @@ impl Step for RegisterDescriptor {
     fn skip(&self, ctx: &Context) -> bool {
         !ctx.hw_is_used
     }
+    fn summary_section(&self) -> Option<SummarySection> {
+        Some(SummarySection::Registration)
+    }
     fn apply(&mut self, ctx: &mut Context) -> bool {
+        ctx.cosigners = self
+            .cosigners
+            .iter()
+            .map(|fg| (*fg, self.confirmed(fg)))
+            .collect();
         for (fingerprint, kind, token) in &self.hmacs {
+            // The step is applied again if the user came back to it from the summary.
+            ctx.hws.retain(|(_, fg, _)| fg != fingerprint);
             ctx.hws.push((*kind, *fingerprint, *token));
         }
         true
@@ -1396,18 +3169,88 @@ impl Step for RegisterDescriptor {
     fn load(&self) -> Command<Message> {
         let keys_aliases = self.keys_aliases.clone();
         Command::perform(
-            async move { list_unregistered_hardware_wallets(Some(&keys_aliases)).await },
-            Message::ConnectedHardwareWallets,
+            async move { enumerate_unregistered_hardware_wallets(Some(&keys_aliases)).await },
+            |(hws, errors)| {
+                Message::RegisterDescriptor(message::RegisterDescriptor::HardwareWalletsEnumerated(
+                    hws, errors,
+                ))
+            },
         )
     }
     fn view(&self, progress: (usize, usize)) -> Element<Message> {
         let desc = self.descriptor.as_ref().unwrap();
+        let mut failed: Vec<_> = self
+            .failed
+            .iter()
+            .map(|(fg, e)| {
+                (
+                    *fg,
+                    self.keys_aliases.get(fg),
+                    e,
+                    self.hws.iter().position(|hw| hw.fingerprint() == Some(*fg)),
+                )
+            })
+            .collect();
+        failed.sort_by_key(|(fg, ..)| *fg);
         view::register_descriptor(
             progress,
             desc.to_string(),
+            self.first_address.as_ref(),
+            self.cosigners
+                .iter()
+                .map(|fg| {
+                    (
+                        *fg,
+                        self.keys_aliases.get(fg),
+                        self.format(fg),
+                        self.registered.contains(fg),
+                        self.exported.contains(fg),
+                    )
+                })
+                .collect(),
             &self.hws,
             &self.registered,
-            self.error.as_ref(),
+            failed,
+            // The enumeration failed if errors were met and no device could be listed.
+            if self.hws.is_empty() {
+                Some(&self.enumeration_errors)
+            } else {
+                None
+            },
+            if self.manual_entry && self.hws.is_empty() {
+                Some((
+                    &self.manual_key,
+                    self.manual_device.as_ref().map(|res| {
+                        res.as_ref()
+                            .map(|fg| (*fg, self.keys_aliases.get(fg)))
+                            .map_err(|e| e.as_str())
+                    }),
+                ))
+            } else {
+                None
+            },
+            self.attestation_message.as_ref().map(|message| {
+                let paths = attestation_paths(desc);
+                (
+                    message,
+                    self.cosigners
+                        .iter()
+                        .map(|fg| {
+                            (
+                                *fg,
+                                self.keys_aliases.get(fg),
+                                paths
+                                    .get(fg)
+                                    .map(|path| path.to_string())
+                                    .unwrap_or_default(),
+                                self.confirmed(fg),
+                            )
+                        })
+                        .collect(),
+                    &self.attestations_input,
+                    self.attestation_errors.as_slice(),
+                )
+            }),
             self.processing,
             self.chosen_hw,
             self.done,
@@ -1415,16 +3258,70 @@ impl Step for RegisterDescriptor {
     }
 }
 
+/// The cosigner device whose key was entered by hand: either the fingerprint of its master key
+/// or the extended public key it reports, with or without its origin.
+fn manual_device_fingerprint(
+    descriptor: &LianaDescriptor,
+    cosigners: &[Fingerprint],
+    input: &str,
+) -> Result<Fingerprint, String> {
+    let input = input.trim();
+    let fingerprint = if input.len() == 8 {
+        Fingerprint::from_str(input).map_err(|_| format!("'{}' is not a key fingerprint", input))?
+    } else {
+        let (origin, xpub) = match input.split_once(']') {
+            Some((origin, xpub)) => (Some(origin.trim_start_matches('[')), xpub),
+            None => (None, input),
+        };
+        // Ignore the derivation steps following the extended key, if any.
+        let xpub = xpub.split('/').next().unwrap_or(xpub);
+        let xpub = ExtendedPubKey::from_str(xpub)
+            .map_err(|e| format!("Invalid extended public key: {}", e))?;
+        let policy = descriptor.policy();
+        let fingerprint = std::iter::once(policy.primary_path())
+            .chain(policy.recovery_paths().values())
+            .flat_map(|path| match path {
+                PathInfo::Single(key) => vec![key.clone()],
+                PathInfo::Multi(_, keys) => keys.clone(),
+            })
+            .find_map(|key| match &key {
+                DescriptorPublicKey::MultiXPub(xkey) if xkey.xkey == xpub => {
+                    Some(key.master_fingerprint())
+                }
+                _ => None,
+            })
+            .ok_or_else(|| "This key is not part of the descriptor".to_string())?;
+        if let Some(origin) = origin {
+            let origin_fingerprint = origin.split('/').next().unwrap_or(origin);
+            if origin_fingerprint != fingerprint.to_string() {
+                return Err(format!(
+                    "The key is part of the descriptor with the fingerprint {}, not {}",
+                    fingerprint, origin_fingerprint
+                ));
+            }
+        }
+        fingerprint
+    };
+    if !cosigners.contains(&fingerprint) {
+        return Err(format!(
+            "No signing device of the descriptor has the fingerprint {}",
+            fingerprint
+        ));
+    }
+    Ok(fingerprint)
+}
+
 async fn register_wallet(
     hw: std::sync::Arc<dyn async_hwi::HWI + Send + Sync>,
     fingerprint: Fingerprint,
     descriptor: String,
-) -> Result<(Fingerprint, Option<[u8; 32]>), Error> {
-    let hmac = hw
-        .register_wallet("Liana", &descriptor)
-        .await
-        .map_err(Error::from)?;
-    Ok((fingerprint, hmac))
+) -> (Fingerprint, Result<Option<[u8; 32]>, Error>) {
+    (
+        fingerprint,
+        hw.register_wallet("Liana", &descriptor)
+            .await
+            .map_err(Error::from),
+    )
 }
 
 impl From<RegisterDescriptor> for Box<dyn Step> {
@@ -1433,25 +3330,267 @@ impl From<RegisterDescriptor> for Box<dyn Step> {
     }
 }
 
-#[derive(Default)]
+/// Export the descriptor in the requested form: either as the multipath descriptor or as the
+/// separate receive and change descriptors, with or without the checksum. Every generated
+/// descriptor is parsed back and checked against the original one.
+pub fn export_descriptor(
+    descriptor: &LianaDescriptor,
+    split: bool,
+    with_checksum: bool,
+) -> Result<Vec<(&'static str, String)>, String> {
+    let format = |desc: String| -> String {
+        if with_checksum {
+            desc
+        } else {
+            desc.split_once('#')
+                .map(|(desc, _)| desc.to_string())
+                .unwrap_or(desc)
+        }
+    };
+
+    if !split {
+        let exported = format(descriptor.to_string());
+        match LianaDescriptor::from_str(&exported) {
+            Ok(parsed) if parsed == *descriptor => Ok(vec![("Descriptor", exported)]),
+            _ => Err("The exported descriptor does not match the wallet descriptor".to_string()),
+        }
+    } else {
+        let mut exported = Vec::new();
+        for (label, desc) in [
+            ("Receive descriptor", descriptor.receive_descriptor()),
+            ("Change descriptor", descriptor.change_descriptor()),
+        ] {
+            let s = format(desc.to_string());
+            match Descriptor::<DescriptorPublicKey>::from_str(&s) {
+                Ok(parsed) if *desc == parsed => exported.push((label, s)),
+                _ => {
+                    return Err(format!(
+                        "The exported {} does not match the wallet descriptor",
+                        label.to_lowercase()
+                    ))
+                }
+            }
+        }
+        Ok(exported)
+    }
+}
+
+/// The content of the backup file of the exported descriptors, one per line.
+fn backup_file_content(exported: &[(&'static str, String)]) -> String {
+    exported
+        .iter()
+        .map(|(_, descriptor)| format!("{}\n", descriptor))
+        .collect()
+}
+
+/// Check the backup file gives back the descriptor of the wallet, with the same checksum: the
+/// descriptor itself or its receive and change descriptors.
+fn verify_backup(descriptor: &LianaDescriptor, content: &str) -> Result<(), String> {
+    let lines: Vec<&str> = content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    match lines.as_slice() {
+        [line] => match LianaDescriptor::from_str(line) {
+            Ok(parsed) if parsed.to_string() == descriptor.to_string() => Ok(()),
+            Ok(_) => Err("The backup holds another descriptor than the wallet one".to_string()),
+            Err(e) => Err(format!(
+                "The backup does not hold a valid descriptor: {}",
+                e
+            )),
+        },
+        [receive, change] => {
+            for (label, line, desc) in [
+                ("receive", receive, descriptor.receive_descriptor()),
+                ("change", change, descriptor.change_descriptor()),
+            ] {
+                match Descriptor::<DescriptorPublicKey>::from_str(line) {
+                    Ok(parsed) if parsed.to_string() == desc.to_string() => {}
+                    Ok(_) => {
+                        return Err(format!(
+                            "The backup holds another {} descriptor than the wallet one",
+                            label
+                        ))
+                    }
+                    Err(e) => {
+                        return Err(format!(
+                            "The backup does not hold a valid {} descriptor: {}",
+                            label, e
+                        ))
+                    }
+                }
+            }
+            Ok(())
+        }
+        _ => Err(format!(
+            "The backup holds {} lines instead of the descriptor",
+            lines.len()
+        )),
+    }
+}
+
 pub struct BackupDescriptor {
     done: bool,
     descriptor: Option<LianaDescriptor>,
+    split: bool,
+    with_checksum: bool,
+    exported: Result<Vec<(&'static str, String)>, String>,
+    qr_codes: Vec<Option<qr_code::State>>,
+    /// The alias map of the keys, to be backed up along with the descriptor.
+    aliases: Option<String>,
+    acknowledged_warnings: Arc<Mutex<AcknowledgedWarnings>>,
+    /// The file the exported descriptor is saved to, and the result of reading it back.
+    backup_path: form::Value<String>,
+    backup: Option<Result<PathBuf, Error>>,
+    backup_verification: Option<Result<(), String>>,
+}
+
+impl BackupDescriptor {
+    pub fn new() -> Self {
+        Self {
+            done: false,
+            descriptor: None,
+            split: false,
+            with_checksum: true,
+            exported: Ok(Vec::new()),
+            qr_codes: Vec::new(),
+            aliases: None,
+            acknowledged_warnings: Arc::new(Mutex::new(AcknowledgedWarnings::default())),
+            backup_path: form::Value::default(),
+            backup: None,
+            backup_verification: None,
+        }
+    }
+
+    fn export(&mut self) {
+        self.qr_codes = Vec::new();
+        // A file saved before is not the backup of the new export anymore.
+        self.backup = None;
+        self.backup_verification = None;
+        if let Some(descriptor) = &self.descriptor {
+            self.exported = export_descriptor(descriptor, self.split, self.with_checksum);
+            if let Ok(exported) = &self.exported {
+                self.qr_codes = exported
+                    .iter()
+                    .map(|(_, desc)| qr_code::State::new(desc).ok())
+                    .collect();
+            }
+        }
+    }
+}
+
+impl Default for BackupDescriptor {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Step for BackupDescriptor {
     fn update(&mut self, message: Message) -> Command<Message> {
-        if let Message::UserActionDone(done) = message {
-            self.done = done;
+        match message {
+            Message::UserActionDone(done) => self.done = done,
+            Message::BackupDescriptor(message::BackupDescriptor::SplitToggled(split)) => {
+                self.split = split;
+                self.export();
+            }
+            Message::BackupDescriptor(message::BackupDescriptor::ChecksumToggled(checksum)) => {
+                self.with_checksum = checksum;
+                self.export();
+            }
+            Message::AcknowledgeWarning(warning, input, acknowledged) => {
+                let mut acknowledged_warnings = self.acknowledged_warnings.lock().unwrap();
+                if acknowledged {
+                    acknowledged_warnings.acknowledge(warning, input);
+                } else {
+                    acknowledged_warnings.forget(warning);
+                }
+            }
+            Message::BackupDescriptor(message::BackupDescriptor::BackupPathEdited(path)) => {
+                self.backup_path.valid = true;
+                self.backup_path.value = path;
+            }
+            Message::BackupDescriptor(message::BackupDescriptor::SaveBackup) => {
+                let path = parse_file_path(&self.backup_path.value);
+                self.backup_path.valid = path.is_some();
+                if let (Some(path), Ok(exported)) = (path, &self.exported) {
+                    let content = backup_file_content(exported);
+                    return Command::perform(
+                        async move {
+                            std::fs::write(&path, content)
+                                .map_err(|e| Error::CannotWriteToFile(e.to_string()))?;
+                            Ok(path)
+                        },
+                        |res| {
+                            Message::BackupDescriptor(message::BackupDescriptor::BackupSaved(res))
+                        },
+                    );
+                }
+            }
+            Message::BackupDescriptor(message::BackupDescriptor::BackupSaved(res)) => {
+                self.backup = Some(res);
+                self.backup_verification = None;
+            }
+            Message::BackupDescriptor(message::BackupDescriptor::VerifyBackup) => {
+                if let (Some(Ok(path)), Some(descriptor)) = (&self.backup, &self.descriptor) {
+                    let path = path.clone();
+                    let descriptor = descriptor.clone();
+                    return Command::perform(
+                        async move {
+                            let content = std::fs::read_to_string(&path)
+                                .map_err(|e| format!("Failed to read the backup file: {}", e))?;
+                            verify_backup(&descriptor, &content)
+                        },
+                        |res| {
+                            Message::BackupDescriptor(message::BackupDescriptor::BackupVerified(
+                                res,
+                            ))
+                        },
+                    );
+                }
+            }
+            Message::BackupDescriptor(message::BackupDescriptor::BackupVerified(res)) => {
+                self.backup_verification = Some(res);
+            }
+            _ => {}
         }
         Command::none()
     }
     fn load_context(&mut self, ctx: &Context) {
         self.descriptor = ctx.descriptor.clone();
+        self.acknowledged_warnings = ctx.acknowledged_warnings.clone();
+        let aliases: KeyAliases = ctx
+            .keys
+            .iter()
+            .filter(|key| !key.name.is_empty())
+            .map(|key| (key.master_fingerprint, key.name.clone()))
+            .collect();
+        self.aliases = if aliases.is_empty() {
+            None
+        } else {
+            Some(export_aliases(&aliases))
+        };
+        self.export();
     }
     fn view(&self, progress: (usize, usize)) -> Element<Message> {
-        let desc = self.descriptor.as_ref().unwrap();
-        view::backup_descriptor(progress, desc.to_string(), self.done)
+        view::backup_descriptor(
+            progress,
+            self.split,
+            self.with_checksum,
+            self.exported.as_ref(),
+            &self.qr_codes,
+            self.aliases.as_ref(),
+            self.acknowledged_warnings
+                .lock()
+                .unwrap()
+                .is_acknowledged(SecurityWarning::DescriptorCopy, ""),
+            (
+                &self.backup_path,
+                self.backup.as_ref(),
+                self.backup_verification.as_ref(),
+            ),
+            self.done,
+        )
     }
 }
 
@@ -1501,6 +3640,14 @@ mod tests {
             Mutex::new(Signer::generate(Network::Bitcoin).unwrap()),
         )));
 
+        // No key is set yet, the preview shows placeholders.
+        sandbox.check(|step| {
+            let (desc, placeholders) = step.preview_descriptor().unwrap();
+            let preview = preview_string(&desc, &placeholders);
+            assert!(preview.contains(INCOMPLETE_PLACEHOLDER));
+            assert!(!preview.contains('#'));
+        });
+
         // Edit primary key
         sandbox
             .update(Message::DefineDescriptor(
@@ -1572,6 +3719,13 @@ mod tests {
                 .unwrap()
                 .to_string()
                 .contains(&step.signer.lock().unwrap().fingerprint().to_string()));
+            // Once complete the preview is the applied descriptor.
+            let (desc, placeholders) = step.preview_descriptor().unwrap();
+            assert!(placeholders.is_empty());
+            assert_eq!(
+                preview_string(&desc, &placeholders),
+                ctx.descriptor.as_ref().unwrap().to_string()
+            );
         });
     }
 
@@ -1629,7 +3783,12 @@ mod tests {
             assert!(step.modal.is_none());
             assert!((step).apply(&mut ctx));
             assert!(ctx.hw_is_used);
+            // There was no address before the descriptor was complete.
+            assert!(step.address_change.is_none());
         });
+        let mut first_address = None;
+        sandbox.check(|step| first_address = step.first_address());
+        let first_address = first_address.unwrap();
 
         // Now edit primary key to use hot signer instead of Specter device
         sandbox
@@ -1658,6 +3817,10 @@ mod tests {
             assert!(step.modal.is_none());
             assert!((step).apply(&mut ctx));
             assert!(!ctx.hw_is_used);
+            // Changing a single key changes the addresses.
+            let (before, after) = step.address_change.clone().unwrap();
+            assert_eq!(before, first_address);
+            assert_ne!(before, after);
         });
 
         // Now edit the recovery key to use Specter device
@@ -1670,5 +3833,744 @@ mod tests {
             assert!((step).apply(&mut ctx));
             assert!(ctx.hw_is_used);
         });
+
+        // The signing devices of the keys are kept when the policy is given by another step.
+        let mut builder = ctx.policy_builder.clone().unwrap();
+        builder.primary.keys[0].name = "This computer".to_string();
+        ctx.policy_builder = Some(builder);
+        sandbox.check(|step| {
+            step.load_context(&ctx);
+            assert_eq!(step.spending_keys[0].name, "This computer");
+            assert!((step).apply(&mut ctx));
+            assert!(ctx.hw_is_used);
+        });
+    }
+
+    #[tokio::test]
+    async fn test_define_descriptor_hot_primary_key_variant() {
+        let mut ctx = Context::new(Network::Signet, PathBuf::from_str("/").unwrap());
+        let signer = Arc::new(Mutex::new(Signer::generate(Network::Signet).unwrap()));
+        let hot_fingerprint = signer.lock().unwrap().fingerprint().to_string();
+        let sandbox: Sandbox<DefineDescriptor> = Sandbox::new(DefineDescriptor::new(signer));
+        sandbox.check(|step| {
+            step.load_context(&ctx);
+            assert!(!step.can_compare_hot_primary_key());
+        });
+
+        sandbox
+            .update(Message::DefineDescriptor(
+                message::DefineDescriptor::PrimaryPath(message::DefinePath::Key(
+                    0,
+                    message::DefineKey::Edited(
+                        "My Specter key".to_string(),
+                        DescriptorPublicKey::from_str("[4df3f0e3/84'/0'/0']tpubDDRs9DnRUiJc4hq92PSJKhfzQBgHJUrDo7T2i48smsDfLsQcm3Vh7JhuGqJv8zozVkNFin8YPgpmn2NWNmpRaE3GW2pSxbmAzYf2juy7LeW").unwrap(),
+                        Some(DeviceKind::Specter),
+                    ),
+                )),
+            ))
+            .await;
+        sandbox
+            .update(Message::DefineDescriptor(
+                message::DefineDescriptor::RecoveryPath(
+                    0,
+                    message::DefinePath::Key(
+                        0,
+                        message::DefineKey::Edited(
+                            "External recovery key".to_string(),
+                            DescriptorPublicKey::from_str("[f5acc2fd/48'/1'/0'/2']tpubDFAqEGNyad35aBCKUAXbQGDjdVhNueno5ZZVEn3sQbW5ci457gLR7HyTmHBg93oourBssgUxuWz1jX5uhc1qaqFo9VsybY1J5FuedLfm4dK").unwrap(),
+                            None,
+                        ),
+                    ),
+                ),
+            ))
+            .await;
+        sandbox.check(|step| assert!(step.can_compare_hot_primary_key()));
+
+        sandbox
+            .update(Message::DefineDescriptor(
+                message::DefineDescriptor::CompareHotPrimaryKey,
+            ))
+            .await;
+        sandbox.check(|step| {
+            assert!(!step.can_compare_hot_primary_key());
+            let variants = step.primary_key_variants.as_ref().unwrap();
+            let hardware = step.variant_preview(&variants.hardware).unwrap();
+            let hot = step.variant_preview(&variants.hot).unwrap();
+            assert!(hardware.contains("4df3f0e3") && !hardware.contains(&hot_fingerprint));
+            assert!(hot.contains(&hot_fingerprint) && !hot.contains("4df3f0e3"));
+        });
+
+        sandbox
+            .update(Message::DefineDescriptor(
+                message::DefineDescriptor::UseHotPrimaryKey(true),
+            ))
+            .await;
+        sandbox.check(|step| {
+            assert!(step.apply(&mut ctx));
+            assert!(!ctx.hw_is_used);
+            let descriptor = ctx.descriptor.as_ref().unwrap().to_string();
+            assert!(descriptor.contains(&hot_fingerprint));
+            assert!(step.address_change.is_some());
+        });
+
+        sandbox
+            .update(Message::DefineDescriptor(
+                message::DefineDescriptor::UseHotPrimaryKey(false),
+            ))
+            .await;
+        sandbox.check(|step| {
+            assert!(step.apply(&mut ctx));
+            assert!(ctx.hw_is_used);
+            assert!(!ctx
+                .descriptor
+                .as_ref()
+                .unwrap()
+                .to_string()
+                .contains(&hot_fingerprint));
+        });
+
+        // The compared descriptors follow the edits of the recovery paths, editing the primary
+        // path ends the comparison.
+        sandbox
+            .update(Message::DefineDescriptor(
+                message::DefineDescriptor::AddRecoveryPath,
+            ))
+            .await;
+        sandbox.check(|step| assert!(step.primary_key_variants.is_some()));
+        sandbox
+            .update(Message::DefineDescriptor(
+                message::DefineDescriptor::PrimaryPath(message::DefinePath::ThresholdEdited(1)),
+            ))
+            .await;
+        sandbox.check(|step| assert!(step.primary_key_variants.is_none()));
+    }
+
+    #[tokio::test]
+    async fn test_define_descriptor_policy_builder() {
+        let descriptor = LianaDescriptor::from_str("wsh(or_d(pk([9e1c1983/48'/1'/0'/2']tpubDEWCLCRcyEWgDA6kgcFvq3xbn5EjMwr3ZF3LQyJEE5pvbsLNKnLBNSsGreZm8NRkGoqA3mTjBR7RxuJ6vKQzhCTCq1GXZjfPvAXXzrmuoWj/<0;1>/*),and_v(v:pkh([f5acc2fd/48'/1'/0'/2']tpubDFAqEGNyad35aBCKUAXbQGDjdVhNueno5ZZVEn3sQbW5ci457gLR7HyTmHBg93oourBssgUxuWz1jX5uhc1qaqFo9VsybY1J5FuedLfm4dK/<0;1>/*),older(52560))))").unwrap();
+        let aliases: KeyAliases = vec![
+            (
+                Fingerprint::from_str("9e1c1983").unwrap(),
+                "Alice".to_string(),
+            ),
+            (
+                Fingerprint::from_str("f5acc2fd").unwrap(),
+                "Bob".to_string(),
+            ),
+        ]
+        .into_iter()
+        .collect();
+        let builder = PolicyBuilder::from_descriptor(&descriptor, &aliases).unwrap();
+
+        // The policy given by the context is the one edited.
+        let mut ctx = Context::new(Network::Testnet, PathBuf::from_str("/").unwrap());
+        ctx.policy_builder = Some(builder.clone());
+        let signer = Arc::new(Mutex::new(Signer::generate(Network::Testnet).unwrap()));
+        let sandbox: Sandbox<DefineDescriptor> = Sandbox::new(DefineDescriptor::new(signer));
+        sandbox.check(|step| {
+            step.load_context(&ctx);
+            assert_eq!(step.spending_keys.len(), 1);
+            assert_eq!(step.spending_keys[0].name, "Alice");
+            assert_eq!(step.recovery_paths.len(), 1);
+            assert_eq!(step.recovery_paths[0].sequence, 52560);
+            assert_eq!(step.policy_builder(), builder);
+        });
+
+        // The descriptor written to the context is the one generated from the policy.
+        let mut applied = Context::new(Network::Testnet, PathBuf::from_str("/").unwrap());
+        sandbox.check(|step| {
+            step.load_context(&applied);
+            assert!(step.apply(&mut applied));
+        });
+        assert_eq!(applied.descriptor, Some(descriptor));
+        assert_eq!(applied.policy_builder, Some(builder.clone()));
+
+        // Coming back to the step does not reload its own policy, nor does a reset.
+        sandbox
+            .update(Message::DefineDescriptor(
+                message::DefineDescriptor::PrimaryPath(message::DefinePath::Key(
+                    0,
+                    message::DefineKey::Delete,
+                )),
+            ))
+            .await;
+        sandbox.check(|step| {
+            step.load_context(&applied);
+            assert!(step.spending_keys.is_empty());
+            step.reset();
+            step.load_context(&applied);
+            assert!(step.spending_keys.iter().all(|key| key.key.is_none()));
+        });
+    }
+
+    #[tokio::test]
+    async fn test_define_descriptor_hot_recovery_key() {
+        let mut ctx = Context::new(Network::Signet, PathBuf::from_str("/").unwrap());
+        let signer = Arc::new(Mutex::new(Signer::generate(Network::Signet).unwrap()));
+        let hot_fingerprint = signer.lock().unwrap().fingerprint();
+        let sandbox: Sandbox<DefineDescriptor> = Sandbox::new(DefineDescriptor::new(signer));
+        sandbox.check(|step| step.load_context(&ctx));
+        sandbox
+            .update(Message::DefineDescriptor(
+                message::DefineDescriptor::PrimaryPath(message::DefinePath::Key(
+                    0,
+                    message::DefineKey::Edited(
+                        "My Specter key".to_string(),
+                        DescriptorPublicKey::from_str("[4df3f0e3/84'/0'/0']tpubDDRs9DnRUiJc4hq92PSJKhfzQBgHJUrDo7T2i48smsDfLsQcm3Vh7JhuGqJv8zozVkNFin8YPgpmn2NWNmpRaE3GW2pSxbmAzYf2juy7LeW").unwrap(),
+                        Some(DeviceKind::Specter),
+                    ),
+                )),
+            ))
+            .await;
+        // The empty recovery path is replaced by one with a key of the hot signer.
+        sandbox
+            .update(Message::DefineDescriptor(
+                message::DefineDescriptor::RecoveryPath(
+                    0,
+                    message::DefinePath::Key(0, message::DefineKey::Delete),
+                ),
+            ))
+            .await;
+        sandbox
+            .update(Message::DefineDescriptor(
+                message::DefineDescriptor::AddHotRecoveryPath,
+            ))
+            .await;
+        sandbox
+            .update(Message::DefineDescriptor(
+                message::DefineDescriptor::AddHotRecoveryPath,
+            ))
+            .await;
+        sandbox.check(|step| {
+            assert_eq!(step.recovery_paths.len(), 1);
+            assert!(step.hot_signer_in_recovery_path());
+            // The hot signer can spend alone through the recovery path, the user is warned.
+            assert_eq!(
+                step.warnings(),
+                vec![(
+                    SecurityWarning::HotRecoveryKey,
+                    format!("{}:alone", suggested_recovery_sequence(Network::Signet, 0)),
+                    false
+                )]
+            );
+            assert!(!step.apply(&mut ctx));
+        });
+
+        sandbox
+            .update(Message::AcknowledgeWarning(
+                SecurityWarning::HotRecoveryKey,
+                format!("{}:alone", suggested_recovery_sequence(Network::Signet, 0)),
+                true,
+            ))
+            .await;
+        sandbox.check(|step| {
+            assert!(step.apply(&mut ctx));
+            assert!(ctx.hw_is_used);
+            let placement = crate::installer::step::HotSignerPlacement::new(
+                ctx.descriptor.as_ref().unwrap(),
+                hot_fingerprint,
+            )
+            .unwrap();
+            assert!(!placement.primary);
+            assert_eq!(
+                placement.recovery,
+                vec![suggested_recovery_sequence(Network::Signet, 0)]
+            );
+        });
+    }
+
+    #[test]
+    fn test_export_descriptor() {
+        let descriptor = LianaDescriptor::from_str("wsh(or_d(pk([abcdef01]xpub6Eze7yAT3Y1wGrnzedCNVYDXUqa9NmHVWck5emBaTbXtURbe1NWZbK9bsz1TiVE7Cz341PMTfYgFw1KdLWdzcM1UMFTcdQfCYhhXZ2HJvTW/<0;1>/*),and_v(v:pkh([abcdef01]xpub688Hn4wScQAAiYJLPg9yH27hUpfZAUnmJejRQBCiwfP5PEDzjWMNW1wChcninxr5gyavFqbbDjdV1aK5USJz8NDVjUy7FRQaaqqXHh5SbXe/<0;1>/*),older(52560))))#g7vk9r5l").unwrap();
+
+        let exported = export_descriptor(&descriptor, false, true).unwrap();
+        assert_eq!(exported.len(), 1);
+        assert_eq!(exported[0].1, descriptor.to_string());
+
+        let exported = export_descriptor(&descriptor, false, false).unwrap();
+        assert_eq!(exported.len(), 1);
+        assert!(!exported[0].1.contains('#'));
+        assert!(exported[0].1.contains("<0;1>"));
+
+        let exported = export_descriptor(&descriptor, true, true).unwrap();
+        assert_eq!(exported.len(), 2);
+        assert_eq!(exported[0].1, descriptor.receive_descriptor().to_string());
+        assert_eq!(exported[1].1, descriptor.change_descriptor().to_string());
+
+        let exported = export_descriptor(&descriptor, true, false).unwrap();
+        assert_eq!(exported.len(), 2);
+        for (_, desc) in exported {
+            assert!(!desc.contains('#'));
+            assert!(!desc.contains("<0;1>"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_descriptor_backup_round_trip() {
+        let descriptor = LianaDescriptor::from_str("wsh(or_d(pk([abcdef01]xpub6Eze7yAT3Y1wGrnzedCNVYDXUqa9NmHVWck5emBaTbXtURbe1NWZbK9bsz1TiVE7Cz341PMTfYgFw1KdLWdzcM1UMFTcdQfCYhhXZ2HJvTW/<0;1>/*),and_v(v:pkh([abcdef01]xpub688Hn4wScQAAiYJLPg9yH27hUpfZAUnmJejRQBCiwfP5PEDzjWMNW1wChcninxr5gyavFqbbDjdV1aK5USJz8NDVjUy7FRQaaqqXHh5SbXe/<0;1>/*),older(52560))))#g7vk9r5l").unwrap();
+        for (split, with_checksum) in [(false, true), (false, false), (true, true), (true, false)] {
+            let exported = export_descriptor(&descriptor, split, with_checksum).unwrap();
+            assert_eq!(
+                verify_backup(&descriptor, &backup_file_content(&exported)),
+                Ok(())
+            );
+        }
+
+        // A corrupted or foreign backup is refused.
+        let content = backup_file_content(&export_descriptor(&descriptor, false, true).unwrap());
+        assert!(verify_backup(&descriptor, &content.replace("52560", "52561")).is_err());
+        assert!(verify_backup(&descriptor, &content.replace("xpub6Eze", "xpub6Ezf")).is_err());
+        assert!(verify_backup(&descriptor, "").is_err());
+        let other = LianaDescriptor::from_str("wsh(or_d(pk([9e1c1983/48'/1'/0'/2']tpubDEWCLCRcyEWgDA6kgcFvq3xbn5EjMwr3ZF3LQyJEE5pvbsLNKnLBNSsGreZm8NRkGoqA3mTjBR7RxuJ6vKQzhCTCq1GXZjfPvAXXzrmuoWj/<0;1>/*),and_v(v:pkh([f5acc2fd/48'/1'/0'/2']tpubDFAqEGNyad35aBCKUAXbQGDjdVhNueno5ZZVEn3sQbW5ci457gLR7HyTmHBg93oourBssgUxuWz1jX5uhc1qaqFo9VsybY1J5FuedLfm4dK/<0;1>/*),older(20))))").unwrap();
+        assert!(verify_backup(&descriptor, &other.to_string()).is_err());
+        let split = export_descriptor(&descriptor, true, true).unwrap();
+        assert!(verify_backup(&descriptor, &format!("{}\n{}\n", split[1].1, split[0].1)).is_err());
+
+        // The file written by the step is read back.
+        let path = std::env::temp_dir().join(format!(
+            "liana-gui-descriptor-backup-{}.txt",
+            std::process::id()
+        ));
+        let mut ctx = Context::new(Network::Bitcoin, PathBuf::from_str("/").unwrap());
+        ctx.descriptor = Some(descriptor);
+        let sandbox: Sandbox<BackupDescriptor> = Sandbox::new(BackupDescriptor::new());
+        sandbox.check(|step| step.load_context(&ctx));
+        sandbox
+            .update(Message::BackupDescriptor(
+                message::BackupDescriptor::BackupPathEdited(path.to_string_lossy().to_string()),
+            ))
+            .await;
+        sandbox
+            .update(Message::BackupDescriptor(
+                message::BackupDescriptor::SaveBackup,
+            ))
+            .await;
+        sandbox
+            .update(Message::BackupDescriptor(
+                message::BackupDescriptor::VerifyBackup,
+            ))
+            .await;
+        sandbox.check(|step| assert_eq!(step.backup_verification, Some(Ok(()))));
+
+        std::fs::write(&path, "wsh(pk(tampered))").unwrap();
+        sandbox
+            .update(Message::BackupDescriptor(
+                message::BackupDescriptor::VerifyBackup,
+            ))
+            .await;
+        sandbox.check(|step| assert!(matches!(step.backup_verification, Some(Err(_)))));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_registration_artifact() {
+        let descriptor = LianaDescriptor::from_str("wsh(or_d(pk([abcdef01]xpub6Eze7yAT3Y1wGrnzedCNVYDXUqa9NmHVWck5emBaTbXtURbe1NWZbK9bsz1TiVE7Cz341PMTfYgFw1KdLWdzcM1UMFTcdQfCYhhXZ2HJvTW/<0;1>/*),and_v(v:pkh([abcdef01]xpub688Hn4wScQAAiYJLPg9yH27hUpfZAUnmJejRQBCiwfP5PEDzjWMNW1wChcninxr5gyavFqbbDjdV1aK5USJz8NDVjUy7FRQaaqqXHh5SbXe/<0;1>/*),older(52560))))#g7vk9r5l").unwrap();
+
+        assert_eq!(
+            registration_artifact(&descriptor, RegistrationFormat::Descriptor),
+            descriptor.to_string()
+        );
+        assert_eq!(
+            registration_artifact(&descriptor, RegistrationFormat::Specter),
+            format!("addwallet Liana&{}", descriptor)
+        );
+
+        let policy: serde_json::Value = serde_json::from_str(&registration_artifact(
+            &descriptor,
+            RegistrationFormat::WalletPolicy,
+        ))
+        .unwrap();
+        assert_eq!(
+            policy["descriptor_template"],
+            "wsh(or_d(pk(@0/**),and_v(v:pkh(@1/**),older(52560))))"
+        );
+        assert_eq!(
+            policy["keys_info"],
+            serde_json::json!([
+                "[abcdef01]xpub6Eze7yAT3Y1wGrnzedCNVYDXUqa9NmHVWck5emBaTbXtURbe1NWZbK9bsz1TiVE7Cz341PMTfYgFw1KdLWdzcM1UMFTcdQfCYhhXZ2HJvTW",
+                "[abcdef01]xpub688Hn4wScQAAiYJLPg9yH27hUpfZAUnmJejRQBCiwfP5PEDzjWMNW1wChcninxr5gyavFqbbDjdV1aK5USJz8NDVjUy7FRQaaqqXHh5SbXe"
+            ])
+        );
+
+        assert_eq!(
+            RegistrationFormat::for_device(Some(&DeviceKind::Specter)),
+            RegistrationFormat::Specter
+        );
+        assert_eq!(
+            RegistrationFormat::for_device(None),
+            RegistrationFormat::Descriptor
+        );
+    }
+
+    #[test]
+    fn test_address_encoding() {
+        const KEY: &str = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+        let address = |desc: &str| {
+            Descriptor::<bitcoin::PublicKey>::from_str(&desc.replace("KEY", KEY))
+                .unwrap()
+                .address(Network::Bitcoin)
+                .unwrap()
+        };
+
+        let v0 = address("wsh(pk(KEY))");
+        assert_eq!(AddressEncoding::of(&v0), AddressEncoding::Bech32);
+        assert!(address_label(&v0).ends_with(" (bech32)"));
+        assert_eq!(
+            AddressEncoding::of(&address("wpkh(KEY)")),
+            AddressEncoding::Bech32
+        );
+
+        let taproot = address("tr(KEY)");
+        assert_eq!(AddressEncoding::of(&taproot), AddressEncoding::Bech32m);
+        assert!(taproot.to_string().starts_with("bc1p"));
+        assert!(address_label(&taproot).contains("bech32m, not supported by every wallet"));
+
+        assert_eq!(
+            AddressEncoding::of(&address("sh(wpkh(KEY))")),
+            AddressEncoding::Base58
+        );
+        assert_eq!(
+            AddressEncoding::of(&address("pkh(KEY)")),
+            AddressEncoding::Base58
+        );
+
+        // Liana descriptors are P2WSH.
+        let descriptor = LianaDescriptor::from_str("wsh(or_d(pk([abcdef01]xpub6Eze7yAT3Y1wGrnzedCNVYDXUqa9NmHVWck5emBaTbXtURbe1NWZbK9bsz1TiVE7Cz341PMTfYgFw1KdLWdzcM1UMFTcdQfCYhhXZ2HJvTW/<0;1>/*),and_v(v:pkh([abcdef01]xpub688Hn4wScQAAiYJLPg9yH27hUpfZAUnmJejRQBCiwfP5PEDzjWMNW1wChcninxr5gyavFqbbDjdV1aK5USJz8NDVjUy7FRQaaqqXHh5SbXe/<0;1>/*),older(52560))))#g7vk9r5l").unwrap();
+        let secp = secp256k1::Secp256k1::verification_only();
+        let address = descriptor
+            .receive_descriptor()
+            .derive(0.into(), &secp)
+            .address(Network::Bitcoin);
+        assert_eq!(AddressEncoding::of(&address), AddressEncoding::Bech32);
+    }
+
+    #[test]
+    fn test_liana_descriptors_from_core() {
+        let multipath = "wsh(or_d(pk([abcdef01]xpub6Eze7yAT3Y1wGrnzedCNVYDXUqa9NmHVWck5emBaTbXtURbe1NWZbK9bsz1TiVE7Cz341PMTfYgFw1KdLWdzcM1UMFTcdQfCYhhXZ2HJvTW/<0;1>/*),and_v(v:pkh([abcdef01]xpub688Hn4wScQAAiYJLPg9yH27hUpfZAUnmJejRQBCiwfP5PEDzjWMNW1wChcninxr5gyavFqbbDjdV1aK5USJz8NDVjUy7FRQaaqqXHh5SbXe/<0;1>/*),older(52560))))";
+        let expected = LianaDescriptor::from_str(multipath).unwrap();
+        let receive = multipath.replace("<0;1>", "0");
+        let change = multipath.replace("<0;1>", "1");
+        let other = "wpkh([abcdef01/84'/0'/0']xpub6Eze7yAT3Y1wGrnzedCNVYDXUqa9NmHVWck5emBaTbXtURbe1NWZbK9bsz1TiVE7Cz341PMTfYgFw1KdLWdzcM1UMFTcdQfCYhhXZ2HJvTW/0/*)#00000000".to_string();
+
+        // The receive and change descriptors stored apart, with their checksums.
+        let found = liana_descriptors_from_core(&[
+            other.clone(),
+            format!("{}#abcdefgh", receive),
+            format!("{}#abcdefgh", change),
+        ]);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].to_string(), expected.to_string());
+
+        // The multipath descriptor, found once even if bitcoind also lists its branches.
+        let found =
+            liana_descriptors_from_core(&[multipath.to_string(), receive.clone(), change.clone()]);
+        assert_eq!(found.len(), 1);
+
+        // A receive descriptor without its change one is not enough.
+        assert!(liana_descriptors_from_core(&[receive, other]).is_empty());
+    }
+
+    #[test]
+    fn test_export_addresses() {
+        let descriptor = LianaDescriptor::from_str("wsh(or_d(pk([abcdef01]xpub6Eze7yAT3Y1wGrnzedCNVYDXUqa9NmHVWck5emBaTbXtURbe1NWZbK9bsz1TiVE7Cz341PMTfYgFw1KdLWdzcM1UMFTcdQfCYhhXZ2HJvTW/<0;1>/*),and_v(v:pkh([abcdef01]xpub688Hn4wScQAAiYJLPg9yH27hUpfZAUnmJejRQBCiwfP5PEDzjWMNW1wChcninxr5gyavFqbbDjdV1aK5USJz8NDVjUy7FRQaaqqXHh5SbXe/<0;1>/*),older(52560))))#g7vk9r5l").unwrap();
+        let secp = secp256k1::Secp256k1::verification_only();
+        let csv = export_addresses(&descriptor, Network::Bitcoin, AddressBranch::Both, 3);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 7);
+        assert_eq!(lines[0], "branch,index,address");
+        assert_eq!(
+            lines[3],
+            format!(
+                "receive,2,{}",
+                descriptor
+                    .receive_descriptor()
+                    .derive(2.into(), &secp)
+                    .address(Network::Bitcoin)
+            )
+        );
+        assert_eq!(
+            lines[4],
+            format!(
+                "change,0,{}",
+                descriptor
+                    .change_descriptor()
+                    .derive(0.into(), &secp)
+                    .address(Network::Bitcoin)
+            )
+        );
+
+        let csv = export_addresses(&descriptor, Network::Bitcoin, AddressBranch::Change, 2);
+        assert_eq!(csv.lines().count(), 3);
+        assert!(csv.lines().skip(1).all(|l| l.starts_with("change,")));
+    }
+
+    #[test]
+    fn test_first_addresses() {
+        let multipath = LianaDescriptor::from_str("wsh(or_d(pk([abcdef01]xpub6Eze7yAT3Y1wGrnzedCNVYDXUqa9NmHVWck5emBaTbXtURbe1NWZbK9bsz1TiVE7Cz341PMTfYgFw1KdLWdzcM1UMFTcdQfCYhhXZ2HJvTW/<0;1>/*),and_v(v:pkh([abcdef01]xpub688Hn4wScQAAiYJLPg9yH27hUpfZAUnmJejRQBCiwfP5PEDzjWMNW1wChcninxr5gyavFqbbDjdV1aK5USJz8NDVjUy7FRQaaqqXHh5SbXe/<0;1>/*),older(52560))))#g7vk9r5l").unwrap();
+        let secp = secp256k1::Secp256k1::verification_only();
+        let (receive, change) = first_addresses(&multipath, Network::Testnet);
+        assert_ne!(receive, change);
+        let expected = |desc: &str| {
+            address_label(
+                &Descriptor::<DescriptorPublicKey>::from_str(desc)
+                    .unwrap()
+                    .at_derivation_index(0)
+                    .derived_descriptor(&secp)
+                    .unwrap()
+                    .address(Network::Testnet)
+                    .unwrap(),
+            )
+        };
+        // The same addresses as the ones of the two branches of the descriptor written apart.
+        assert_eq!(receive, expected("wsh(or_d(pk([abcdef01]xpub6Eze7yAT3Y1wGrnzedCNVYDXUqa9NmHVWck5emBaTbXtURbe1NWZbK9bsz1TiVE7Cz341PMTfYgFw1KdLWdzcM1UMFTcdQfCYhhXZ2HJvTW/0/*),and_v(v:pkh([abcdef01]xpub688Hn4wScQAAiYJLPg9yH27hUpfZAUnmJejRQBCiwfP5PEDzjWMNW1wChcninxr5gyavFqbbDjdV1aK5USJz8NDVjUy7FRQaaqqXHh5SbXe/0/*),older(52560))))"));
+        assert_eq!(change, expected("wsh(or_d(pk([abcdef01]xpub6Eze7yAT3Y1wGrnzedCNVYDXUqa9NmHVWck5emBaTbXtURbe1NWZbK9bsz1TiVE7Cz341PMTfYgFw1KdLWdzcM1UMFTcdQfCYhhXZ2HJvTW/1/*),and_v(v:pkh([abcdef01]xpub688Hn4wScQAAiYJLPg9yH27hUpfZAUnmJejRQBCiwfP5PEDzjWMNW1wChcninxr5gyavFqbbDjdV1aK5USJz8NDVjUy7FRQaaqqXHh5SbXe/1/*),older(52560))))"));
+    }
+
+    #[test]
+    fn test_check_timelocks() {
+        let check = |ms: &str| {
+            check_timelocks(
+                &Miniscript::<DescriptorPublicKey, Segwitv0>::from_str_insane(ms).unwrap(),
+            )
+        };
+        let primary = "[abcdef01]xpub6Eze7yAT3Y1wGrnzedCNVYDXUqa9NmHVWck5emBaTbXtURbe1NWZbK9bsz1TiVE7Cz341PMTfYgFw1KdLWdzcM1UMFTcdQfCYhhXZ2HJvTW/<0;1>/*";
+        let recovery = "[abcdef01]xpub688Hn4wScQAAiYJLPg9yH27hUpfZAUnmJejRQBCiwfP5PEDzjWMNW1wChcninxr5gyavFqbbDjdV1aK5USJz8NDVjUy7FRQaaqqXHh5SbXe/<0;1>/*";
+
+        assert!(check(&format!(
+            "or_d(pk({}),and_v(v:pkh({}),older(52560)))",
+            primary, recovery
+        ))
+        .is_ok());
+        // A relative timelock in blocks and one in time in the same branch.
+        let err = check(&format!(
+            "or_d(pk({}),and_v(v:pkh({}),and_v(v:older(4194305),older(10))))",
+            primary, recovery
+        ))
+        .unwrap_err();
+        assert!(err.contains("both a relative timelock in blocks and one in time"));
+        // A relative timelock in time alone is never set by Liana.
+        assert!(check(&format!(
+            "or_d(pk({}),and_v(v:pkh({}),older(4194305)))",
+            primary, recovery
+        ))
+        .unwrap_err()
+        .contains("in time"));
+        // Timelocks of different units in separate branches are fine.
+        assert!(check(&format!(
+            "or_d(pk({}),and_v(v:pkh({}),or_i(after(500000001),after(800000))))",
+            primary, recovery
+        ))
+        .is_ok());
+        // But not in the same one, even through a threshold.
+        assert!(check(&format!(
+            "or_d(pk({}),thresh(3,pkh({}),a:after(500000001),a:after(800000)))",
+            primary, recovery
+        ))
+        .is_err());
+
+        // The descriptors generated by the installer are consistent.
+        let descriptor = LianaDescriptor::from_str("wsh(or_d(pk([abcdef01]xpub6Eze7yAT3Y1wGrnzedCNVYDXUqa9NmHVWck5emBaTbXtURbe1NWZbK9bsz1TiVE7Cz341PMTfYgFw1KdLWdzcM1UMFTcdQfCYhhXZ2HJvTW/<0;1>/*),and_v(v:pkh([abcdef01]xpub688Hn4wScQAAiYJLPg9yH27hUpfZAUnmJejRQBCiwfP5PEDzjWMNW1wChcninxr5gyavFqbbDjdV1aK5USJz8NDVjUy7FRQaaqqXHh5SbXe/<0;1>/*),older(52560))))#g7vk9r5l").unwrap();
+        assert!(check_descriptor_timelocks(&descriptor).is_ok());
+    }
+
+    #[test]
+    fn test_manual_device_fingerprint() {
+        let descriptor = LianaDescriptor::from_str("wsh(or_d(pk([9e1c1983/48'/1'/0'/2']tpubDEWCLCRcyEWgDA6kgcFvq3xbn5EjMwr3ZF3LQyJEE5pvbsLNKnLBNSsGreZm8NRkGoqA3mTjBR7RxuJ6vKQzhCTCq1GXZjfPvAXXzrmuoWj/<0;1>/*),and_v(v:pkh([f5acc2fd/48'/1'/0'/2']tpubDFAqEGNyad35aBCKUAXbQGDjdVhNueno5ZZVEn3sQbW5ci457gLR7HyTmHBg93oourBssgUxuWz1jX5uhc1qaqFo9VsybY1J5FuedLfm4dK/<0;1>/*),older(20))))").unwrap();
+        let primary = Fingerprint::from_str("9e1c1983").unwrap();
+        let recovery = Fingerprint::from_str("f5acc2fd").unwrap();
+        let cosigners = vec![primary, recovery];
+
+        assert_eq!(
+            manual_device_fingerprint(&descriptor, &cosigners, " 9e1c1983 "),
+            Ok(primary)
+        );
+        assert_eq!(
+            manual_device_fingerprint(&descriptor, &cosigners, "tpubDFAqEGNyad35aBCKUAXbQGDjdVhNueno5ZZVEn3sQbW5ci457gLR7HyTmHBg93oourBssgUxuWz1jX5uhc1qaqFo9VsybY1J5FuedLfm4dK"),
+            Ok(recovery)
+        );
+        assert_eq!(
+            manual_device_fingerprint(&descriptor, &cosigners, "[f5acc2fd/48'/1'/0'/2']tpubDFAqEGNyad35aBCKUAXbQGDjdVhNueno5ZZVEn3sQbW5ci457gLR7HyTmHBg93oourBssgUxuWz1jX5uhc1qaqFo9VsybY1J5FuedLfm4dK/<0;1>/*"),
+            Ok(recovery)
+        );
+        // The origin does not match the one of the key in the descriptor.
+        assert!(manual_device_fingerprint(&descriptor, &cosigners, "[9e1c1983/48'/1'/0'/2']tpubDFAqEGNyad35aBCKUAXbQGDjdVhNueno5ZZVEn3sQbW5ci457gLR7HyTmHBg93oourBssgUxuWz1jX5uhc1qaqFo9VsybY1J5FuedLfm4dK").is_err());
+        // The device of the hot signer is not a cosigner.
+        assert!(manual_device_fingerprint(&descriptor, &[recovery], "9e1c1983").is_err());
+        assert!(manual_device_fingerprint(&descriptor, &cosigners, "abcdef01").is_err());
+        assert!(manual_device_fingerprint(&descriptor, &cosigners, "9e1c198g").is_err());
+        assert!(manual_device_fingerprint(&descriptor, &cosigners, "tpubinvalid").is_err());
+    }
+
+    #[test]
+    fn test_check_key_origin() {
+        // The master key and its first hardened child of the BIP32 test vector 1.
+        const MASTER: &str = "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+        const CHILD: &str = "xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw";
+        const KEY: &str = "tpubDFAqEGNyad35aBCKUAXbQGDjdVhNueno5ZZVEn3sQbW5ci457gLR7HyTmHBg93oourBssgUxuWz1jX5uhc1qaqFo9VsybY1J5FuedLfm4dK";
+
+        assert_eq!(check_key_origin(KEY), Ok(None));
+        assert_eq!(check_key_origin(&format!("[3442193e]{}", MASTER)), Ok(None));
+        assert_eq!(
+            check_key_origin(&format!("[3442193e/0']{}", CHILD)),
+            Ok(None)
+        );
+        assert_eq!(
+            check_key_origin(&format!("[3442193E/0h]{}", CHILD)),
+            Ok(None)
+        );
+        assert_eq!(
+            check_key_origin(&format!("[f5acc2fd/48'/1'/0'/2']{}", KEY)),
+            Ok(None)
+        );
+
+        // Well formed but inconsistent with the key.
+        assert!(check_key_origin(&format!("[deadbeef]{}", MASTER))
+            .unwrap()
+            .unwrap()
+            .contains("3442193e"));
+        assert!(check_key_origin(&format!("[deadbeef/0']{}", CHILD))
+            .unwrap()
+            .unwrap()
+            .contains("3442193e"));
+        assert!(check_key_origin(&format!("[f5acc2fd/48'/1']{}", KEY))
+            .unwrap()
+            .is_some());
+        assert!(check_key_origin(&format!("[f5acc2fd]{}", KEY))
+            .unwrap()
+            .is_some());
+
+        // Malformed.
+        assert!(check_key_origin(&format!("[f5acc2f/48'/1'/0'/2']{}", KEY)).is_err());
+        assert!(check_key_origin(&format!("[f5acc2fdd/48'/1'/0'/2']{}", KEY)).is_err());
+        assert!(check_key_origin(&format!("[f5acc2fg/48'/1'/0'/2']{}", KEY)).is_err());
+        assert!(check_key_origin(&format!("[/48'/1'/0'/2']{}", KEY)).is_err());
+        assert!(check_key_origin(&format!("[f5acc2fd/48x/1'/0'/2']{}", KEY)).is_err());
+        assert!(check_key_origin(&format!("[f5acc2fd/48'//0'/2']{}", KEY)).is_err());
+        assert!(check_key_origin(&format!("[f5acc2fd/48'/1'/0'/2'{}", KEY)).is_err());
+    }
+
+    #[test]
+    fn test_descriptor_network() {
+        let mainnet = LianaDescriptor::from_str("wsh(or_d(pk([abcdef01]xpub6Eze7yAT3Y1wGrnzedCNVYDXUqa9NmHVWck5emBaTbXtURbe1NWZbK9bsz1TiVE7Cz341PMTfYgFw1KdLWdzcM1UMFTcdQfCYhhXZ2HJvTW/<0;1>/*),and_v(v:pkh([abcdef01]xpub688Hn4wScQAAiYJLPg9yH27hUpfZAUnmJejRQBCiwfP5PEDzjWMNW1wChcninxr5gyavFqbbDjdV1aK5USJz8NDVjUy7FRQaaqqXHh5SbXe/<0;1>/*),older(52560))))").unwrap();
+        assert_eq!(descriptor_network(&mainnet), Some(Network::Bitcoin));
+        assert_eq!(
+            descriptor_network_mismatch(&mainnet, Network::Bitcoin),
+            Ok(None)
+        );
+        assert_eq!(
+            descriptor_network_mismatch(&mainnet, Network::Signet),
+            Ok(Some(Network::Bitcoin))
+        );
+
+        let testnet = LianaDescriptor::from_str("wsh(or_d(pk([9e1c1983/48'/1'/0'/2']tpubDEWCLCRcyEWgDA6kgcFvq3xbn5EjMwr3ZF3LQyJEE5pvbsLNKnLBNSsGreZm8NRkGoqA3mTjBR7RxuJ6vKQzhCTCq1GXZjfPvAXXzrmuoWj/<0;1>/*),and_v(v:pkh([f5acc2fd/48'/1'/0'/2']tpubDFAqEGNyad35aBCKUAXbQGDjdVhNueno5ZZVEn3sQbW5ci457gLR7HyTmHBg93oourBssgUxuWz1jX5uhc1qaqFo9VsybY1J5FuedLfm4dK/<0;1>/*),older(20))))").unwrap();
+        assert_eq!(descriptor_network(&testnet), Some(Network::Testnet));
+        // All the test networks share the same keys encoding.
+        assert_eq!(
+            descriptor_network_mismatch(&testnet, Network::Regtest),
+            Ok(None)
+        );
+        assert_eq!(
+            descriptor_network_mismatch(&testnet, Network::Bitcoin),
+            Ok(Some(Network::Testnet))
+        );
+
+        let mixed = LianaDescriptor::from_str("wsh(or_d(pk([abcdef01]xpub6Eze7yAT3Y1wGrnzedCNVYDXUqa9NmHVWck5emBaTbXtURbe1NWZbK9bsz1TiVE7Cz341PMTfYgFw1KdLWdzcM1UMFTcdQfCYhhXZ2HJvTW/<0;1>/*),and_v(v:pkh([f5acc2fd/48'/1'/0'/2']tpubDFAqEGNyad35aBCKUAXbQGDjdVhNueno5ZZVEn3sQbW5ci457gLR7HyTmHBg93oourBssgUxuWz1jX5uhc1qaqFo9VsybY1J5FuedLfm4dK/<0;1>/*),older(20))))").unwrap();
+        assert_eq!(descriptor_network(&mixed), None);
+        assert!(descriptor_network_mismatch(&mixed, Network::Bitcoin).is_err());
+    }
+
+    #[test]
+    fn test_descriptor_analysis() {
+        let descriptor = "wsh(or_d(pk([abcdef01]xpub6Eze7yAT3Y1wGrnzedCNVYDXUqa9NmHVWck5emBaTbXtURbe1NWZbK9bsz1TiVE7Cz341PMTfYgFw1KdLWdzcM1UMFTcdQfCYhhXZ2HJvTW/<0;1>/*),and_v(v:pkh([abcdef01]xpub688Hn4wScQAAiYJLPg9yH27hUpfZAUnmJejRQBCiwfP5PEDzjWMNW1wChcninxr5gyavFqbbDjdV1aK5USJz8NDVjUy7FRQaaqqXHh5SbXe/<0;1>/*),older(52560))))";
+
+        let analysis = DescriptorAnalysis::new(&format!("{}#g7vk9r5l", descriptor)).unwrap();
+        assert_eq!(analysis.checksum, "g7vk9r5l");
+        assert!(analysis.checksum_provided);
+        assert_eq!(analysis.network, Network::Bitcoin);
+        assert_eq!(analysis.policy_summary.len(), 3);
+        assert!(analysis.policy_summary[1].contains("after 52560 blocks (~365 days)"));
+        assert!(analysis.policy_summary[2].contains("simple inheritance"));
+        assert_eq!(
+            analysis.paths_policies[1].0,
+            "Recovery path after 52560 blocks"
+        );
+        assert!(analysis.paths_policies[1].1.ends_with(",older(52560))"));
+        assert_eq!(analysis.key_origins, vec!["[abcdef01]".to_string()]);
+        assert_eq!(
+            analysis.receive_addresses.len(),
+            VERIFIED_ADDRESSES_COUNT as usize
+        );
+        assert!(analysis
+            .receive_addresses
+            .iter()
+            .chain(analysis.change_addresses.iter())
+            .all(|addr| addr.starts_with("bc1q")));
+        assert_ne!(analysis.receive_addresses, analysis.change_addresses);
+
+        // Without checksum, it is computed.
+        let analysis = DescriptorAnalysis::new(descriptor).unwrap();
+        assert_eq!(analysis.checksum, "g7vk9r5l");
+        assert!(!analysis.checksum_provided);
+
+        // Exported by a coordinator.
+        assert!(DescriptorAnalysis::new(&format!("addwallet Liana&{}", descriptor)).is_ok());
+
+        let analysis = DescriptorAnalysis::new("wsh(or_d(pk([9e1c1983/48'/1'/0'/2']tpubDEWCLCRcyEWgDA6kgcFvq3xbn5EjMwr3ZF3LQyJEE5pvbsLNKnLBNSsGreZm8NRkGoqA3mTjBR7RxuJ6vKQzhCTCq1GXZjfPvAXXzrmuoWj/<0;1>/*),and_v(v:pkh([f5acc2fd/48'/1'/0'/2']tpubDFAqEGNyad35aBCKUAXbQGDjdVhNueno5ZZVEn3sQbW5ci457gLR7HyTmHBg93oourBssgUxuWz1jX5uhc1qaqFo9VsybY1J5FuedLfm4dK/<0;1>/*),older(20))))").unwrap();
+        assert_eq!(analysis.network, Network::Testnet);
+        assert_eq!(
+            analysis.key_origins,
+            vec![
+                "[9e1c1983/48'/1'/0'/2']".to_string(),
+                "[f5acc2fd/48'/1'/0'/2']".to_string()
+            ]
+        );
+        assert!(analysis.receive_addresses[0].starts_with("tb1q"));
+
+        // A wrong checksum is detected.
+        assert!(DescriptorAnalysis::new(&format!("{}#g7vk9r5m", descriptor)).is_err());
+        assert!(DescriptorAnalysis::new("wsh(pk(").is_err());
+    }
+
+    #[test]
+    fn test_descriptor_from_psbt() {
+        let secp = secp256k1::Secp256k1::verification_only();
+        let descriptor = LianaDescriptor::from_str("wsh(or_d(pk([abcdef01]xpub6Eze7yAT3Y1wGrnzedCNVYDXUqa9NmHVWck5emBaTbXtURbe1NWZbK9bsz1TiVE7Cz341PMTfYgFw1KdLWdzcM1UMFTcdQfCYhhXZ2HJvTW/<0;1>/*),and_v(v:pkh([abcdef01]xpub688Hn4wScQAAiYJLPg9yH27hUpfZAUnmJejRQBCiwfP5PEDzjWMNW1wChcninxr5gyavFqbbDjdV1aK5USJz8NDVjUy7FRQaaqqXHh5SbXe/<0;1>/*),older(52560))))#g7vk9r5l").unwrap();
+        let derived = descriptor.change_descriptor().derive(3.into(), &secp);
+        let mut psbt = Psbt::from_unsigned_tx(bitcoin::Transaction {
+            version: 2,
+            lock_time: bitcoin::PackedLockTime(0),
+            input: vec![bitcoin::TxIn::default()],
+            output: Vec::new(),
+        })
+        .unwrap();
+        psbt.inputs[0].witness_script = Some(derived.witness_script());
+        psbt.inputs[0].bip32_derivation = derived.bip32_derivations();
+
+        // The extended keys are missing.
+        let err = descriptor_from_psbt(&psbt, &BTreeMap::new()).unwrap_err();
+        assert!(err.contains("[abcdef01]"));
+        // Both keys have the same origin, which is only listed once.
+        assert_eq!(err.matches("[abcdef01]").count(), 1);
+
+        // One is given by the user, the other one is missing.
+        let primary_xpub = ExtendedPubKey::from_str("xpub6Eze7yAT3Y1wGrnzedCNVYDXUqa9NmHVWck5emBaTbXtURbe1NWZbK9bsz1TiVE7Cz341PMTfYgFw1KdLWdzcM1UMFTcdQfCYhhXZ2HJvTW").unwrap();
+        let recovery_xpub = ExtendedPubKey::from_str("xpub688Hn4wScQAAiYJLPg9yH27hUpfZAUnmJejRQBCiwfP5PEDzjWMNW1wChcninxr5gyavFqbbDjdV1aK5USJz8NDVjUy7FRQaaqqXHh5SbXe").unwrap();
+        let origin = (
+            Fingerprint::from_str("abcdef01").unwrap(),
+            DerivationPath::master(),
+        );
+        let user_xpubs = parse_xpubs_with_origin("[abcdef01]xpub6Eze7yAT3Y1wGrnzedCNVYDXUqa9NmHVWck5emBaTbXtURbe1NWZbK9bsz1TiVE7Cz341PMTfYgFw1KdLWdzcM1UMFTcdQfCYhhXZ2HJvTW").unwrap();
+        assert_eq!(user_xpubs.get(&primary_xpub), Some(&origin));
+        assert!(descriptor_from_psbt(&psbt, &user_xpubs).is_err());
+
+        // The other one is in the global xpubs of the PSBT.
+        psbt.xpub.insert(recovery_xpub, origin);
+        assert_eq!(
+            descriptor_from_psbt(&psbt, &user_xpubs).unwrap(),
+            descriptor
+        );
+
+        // Keys without origin are rejected.
+        assert!(parse_xpubs_with_origin("xpub6Eze7yAT3Y1wGrnzedCNVYDXUqa9NmHVWck5emBaTbXtURbe1NWZbK9bsz1TiVE7Cz341PMTfYgFw1KdLWdzcM1UMFTcdQfCYhhXZ2HJvTW").is_err());
     }
 }
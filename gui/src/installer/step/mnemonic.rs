@@ -1,30 +1,198 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::sync::{Arc, Mutex};
 
 use iced::Command;
-use liana::{bip39, signer::HotSigner};
+use liana::{
+    bip39,
+    descriptors::{LianaDescriptor, LianaPolicy, PathInfo},
+    miniscript::{
+        bitcoin::{util::bip32::Fingerprint, Network},
+        descriptor::{DescriptorMultiXKey, DescriptorPublicKey, DescriptorXKey},
+    },
+    signer::HotSigner,
+};
 
-use liana_ui::widget::Element;
+use liana_ui::{component::form, widget::Element};
 
 use crate::{
-    installer::{context::Context, message::Message, step::Step, view},
+    installer::{
+        context::Context,
+        message::Message,
+        step::{policy_builder::PolicyBuilder, second_opinion::second_opinion, Step},
+        view,
+    },
     signer::Signer,
 };
 
 pub struct BackupMnemonic {
-    words: [&'static str; 12],
+    words: Vec<&'static str>,
     done: bool,
     signer: Arc<Mutex<Signer>>,
+    /// Whether the hot signer can be replaced, that is if its keys were not shared yet.
+    can_regenerate: bool,
+    network: Network,
+    descriptor: Option<LianaDescriptor>,
+    /// The fingerprints of the hot signers replaced by the user, whose keys in the descriptor
+    /// are replaced by the ones of the current hot signer.
+    discarded: HashSet<Fingerprint>,
+    /// The BIP39 passphrase to derive the seed of the hot signer with, and its confirmation.
+    passphrase: form::Value<String>,
+    passphrase_confirm: form::Value<String>,
+    /// Whether the seed of the hot signer is derived with the passphrase as entered.
+    passphrase_applied: bool,
+    passphrase_error: Option<String>,
 }
 
 impl BackupMnemonic {
-    pub fn new(signer: Arc<Mutex<Signer>>) -> Self {
+    pub fn new(signer: Arc<Mutex<Signer>>, can_regenerate: bool) -> Self {
         let words = signer.lock().unwrap().mnemonic();
         Self {
             done: false,
             words,
             signer,
+            can_regenerate,
+            network: Network::Bitcoin,
+            descriptor: None,
+            discarded: HashSet::new(),
+            passphrase: form::Value::default(),
+            passphrase_confirm: form::Value::default(),
+            passphrase_applied: true,
+            passphrase_error: None,
+        }
+    }
+
+    fn regenerate(&mut self) {
+        let signer = match Signer::generate(self.network) {
+            Ok(signer) => signer,
+            Err(e) => {
+                tracing::error!("Failed to generate a new hot signer: {}", e);
+                return;
+            }
+        };
+        self.words = signer.mnemonic();
+        self.replace_signer(signer);
+        // The seed of the new mnemonic is derived without passphrase.
+        self.passphrase = form::Value::default();
+        self.passphrase_confirm = form::Value::default();
+        self.passphrase_applied = true;
+        self.passphrase_error = None;
+    }
+
+    /// Derive the seed of the hot signer from its mnemonic and the confirmed passphrase.
+    fn apply_passphrase(&mut self) {
+        if self.passphrase.value != self.passphrase_confirm.value {
+            self.passphrase_error = Some("The passphrases do not match".to_string());
+            return;
+        }
+        let signer = match self
+            .signer
+            .lock()
+            .unwrap()
+            .with_passphrase(&self.passphrase.value)
+        {
+            Ok(signer) => signer,
+            Err(e) => {
+                self.passphrase_error = Some(e.to_string());
+                return;
+            }
+        };
+        if signer.fingerprint() != self.signer.lock().unwrap().fingerprint() {
+            self.replace_signer(signer);
+        }
+        self.passphrase_applied = true;
+        self.passphrase_error = None;
+    }
+
+    fn replace_signer(&mut self, signer: Signer) {
+        self.discarded.remove(&signer.fingerprint());
+        let discarded = std::mem::replace(&mut *self.signer.lock().unwrap(), signer);
+        self.discarded.insert(discarded.fingerprint());
+        // Dropping the replaced signer wipes its seed from memory.
+        drop(discarded);
+        self.done = false;
+        self.descriptor = self.descriptor.as_ref().map(|desc| self.replace_keys(desc));
+    }
+
+    fn edit_passphrase(&mut self, passphrase: Option<String>, confirm: Option<String>) {
+        if let Some(passphrase) = passphrase {
+            self.passphrase.value = passphrase;
+        }
+        if let Some(confirm) = confirm {
+            self.passphrase_confirm.value = confirm;
+        }
+        self.passphrase_confirm.valid = self.passphrase.value == self.passphrase_confirm.value;
+        self.passphrase_applied = false;
+        self.passphrase_error = None;
+    }
+
+    /// The descriptor with the keys of the discarded hot signers replaced by the keys of the
+    /// current one, at the same derivation paths.
+    fn replace_keys(&self, descriptor: &LianaDescriptor) -> LianaDescriptor {
+        if self.discarded.is_empty() {
+            return descriptor.clone();
+        }
+        let signer = self.signer.lock().unwrap();
+        let replace = |key: &DescriptorPublicKey| match key {
+            DescriptorPublicKey::MultiXPub(xpub) => match &xpub.origin {
+                Some((fingerprint, path)) if self.discarded.contains(fingerprint) => {
+                    DescriptorPublicKey::MultiXPub(DescriptorMultiXKey {
+                        origin: Some((signer.fingerprint(), path.clone())),
+                        xkey: signer.get_extended_pubkey(path),
+                        derivation_paths: xpub.derivation_paths.clone(),
+                        wildcard: xpub.wildcard,
+                    })
+                }
+                _ => key.clone(),
+            },
+            _ => key.clone(),
+        };
+        let replace_path = |path: &PathInfo| match path {
+            PathInfo::Single(key) => PathInfo::Single(replace(key)),
+            PathInfo::Multi(threshold, keys) => {
+                PathInfo::Multi(*threshold, keys.iter().map(replace).collect())
+            }
+        };
+        let policy = descriptor.policy();
+        let recovery_paths: BTreeMap<_, _> = policy
+            .recovery_paths()
+            .iter()
+            .map(|(sequence, path)| (*sequence, replace_path(path)))
+            .collect();
+        LianaPolicy::new(replace_path(policy.primary_path()), recovery_paths)
+            .map(LianaDescriptor::new)
+            .expect("Only the keys changed")
+    }
+
+    /// The policy with the keys of the discarded hot signers replaced as in the descriptor.
+    fn replace_builder_keys(&self, builder: &PolicyBuilder) -> PolicyBuilder {
+        let signer = self.signer.lock().unwrap();
+        let mut builder = builder.clone();
+        for key in builder.primary.keys.iter_mut().chain(
+            builder
+                .recovery
+                .values_mut()
+                .flat_map(|path| path.keys.iter_mut()),
+        ) {
+            let path = match &key.xpub.origin {
+                Some((fingerprint, path)) if self.discarded.contains(fingerprint) => path.clone(),
+                _ => continue,
+            };
+            key.xpub = DescriptorXKey {
+                origin: Some((signer.fingerprint(), path.clone())),
+                xkey: signer.get_extended_pubkey(&path),
+                ..key.xpub.clone()
+            };
         }
+        builder
+    }
+
+    fn uses_hot_signer(&self, descriptor: &LianaDescriptor) -> bool {
+        let fingerprint = self.signer.lock().unwrap().fingerprint();
+        let policy = descriptor.policy();
+        std::iter::once(policy.primary_path())
+            .chain(policy.recovery_paths().values())
+            .flat_map(|path| path.thresh_origins().1.into_iter().map(|(fp, _)| fp))
+            .any(|fp| fp == fingerprint || self.discarded.contains(&fp))
     }
 }
 
@@ -35,29 +203,101 @@ impl From<BackupMnemonic> for Box<dyn Step> {
 }
 
 impl Step for BackupMnemonic {
+    fn load_context(&mut self, ctx: &Context) {
+        self.network = ctx.bitcoin_config.network;
+        // The descriptor may still use the keys of a discarded signer if it was edited again.
+        self.descriptor = ctx.descriptor.as_ref().map(|desc| self.replace_keys(desc));
+    }
     fn update(&mut self, message: Message) -> Command<Message> {
-        if let Message::UserActionDone(done) = message {
-            self.done = done;
+        match message {
+            Message::UserActionDone(done) => self.done = done,
+            Message::RegenerateHotSigner if self.can_regenerate => self.regenerate(),
+            // The keys of the hot signer were shared already if it cannot be replaced.
+            Message::MnemonicPassphrase(passphrase) if self.can_regenerate => {
+                self.edit_passphrase(Some(passphrase), None)
+            }
+            Message::MnemonicPassphraseConfirm(confirm) if self.can_regenerate => {
+                self.edit_passphrase(None, Some(confirm))
+            }
+            Message::ApplyPassphrase if self.can_regenerate => self.apply_passphrase(),
+            _ => {}
         }
         Command::none()
     }
     fn skip(&self, ctx: &Context) -> bool {
         if let Some(descriptor) = &ctx.descriptor {
-            !descriptor
-                .to_string()
-                .contains(&self.signer.lock().unwrap().fingerprint().to_string())
+            !self.uses_hot_signer(descriptor)
         } else {
             false
         }
     }
+    fn apply(&mut self, ctx: &mut Context) -> bool {
+        if self.discarded.is_empty() {
+            return true;
+        }
+        let builder = ctx
+            .policy_builder
+            .as_ref()
+            .map(|builder| self.replace_builder_keys(builder));
+        // The descriptor is checked against the policy it must have, as when it was defined.
+        if let (Some(descriptor), Some(builder)) = (&self.descriptor, &builder) {
+            if let Err(e) = second_opinion(
+                descriptor,
+                &builder.primary.defined_path(),
+                &builder
+                    .recovery
+                    .iter()
+                    .map(|(sequence, path)| (*sequence, path.defined_path()))
+                    .collect(),
+            ) {
+                self.passphrase_error = Some(format!(
+                    "The descriptor does not match the policy defined, this is a bug: {}",
+                    e
+                ));
+                return false;
+            }
+        }
+        let fingerprint = self.signer.lock().unwrap().fingerprint();
+        for key in ctx.keys.iter_mut() {
+            if self.discarded.contains(&key.master_fingerprint) {
+                key.master_fingerprint = fingerprint;
+            }
+        }
+        ctx.descriptor = self.descriptor.clone();
+        ctx.policy_builder = builder;
+        true
+    }
     fn view(&self, progress: (usize, usize)) -> Element<Message> {
-        view::backup_mnemonic(progress, &self.words, self.done)
+        let signer = self.signer.lock().unwrap();
+        view::backup_mnemonic(
+            progress,
+            &self.words,
+            self.done,
+            self.can_regenerate,
+            signer.fingerprint(),
+            (
+                &self.passphrase,
+                &self.passphrase_confirm,
+                self.passphrase_applied,
+                signer.has_passphrase(),
+                self.passphrase_error.as_ref(),
+            ),
+        )
     }
 }
 
+/// The numbers of words a BIP39 mnemonic can have.
+pub const MNEMONIC_LENGTHS: [usize; 5] = [12, 15, 18, 21, 24];
+
+/// The number of letters identifying a word of the BIP39 english list, as engraved on some
+/// metal backups.
+const WORD_PREFIX_LENGTH: usize = 4;
+
 pub struct RecoverMnemonic {
     language: bip39::Language,
-    words: [(String, bool); 12],
+    words: Vec<(String, bool)>,
+    /// The optional BIP39 passphrase the seed is derived with.
+    passphrase: form::Value<String>,
     current: usize,
     suggestions: Vec<String>,
     error: Option<String>,
@@ -69,7 +309,8 @@ impl Default for RecoverMnemonic {
     fn default() -> Self {
         Self {
             language: bip39::Language::English,
-            words: Default::default(),
+            words: vec![Default::default(); MNEMONIC_LENGTHS[0]],
+            passphrase: form::Value::default(),
             current: 0,
             suggestions: Vec::new(),
             error: None,
@@ -85,26 +326,107 @@ impl From<RecoverMnemonic> for Box<dyn Step> {
     }
 }
 
+impl RecoverMnemonic {
+    fn set_length(&mut self, length: usize) {
+        self.words.resize(length, Default::default());
+        self.current = self.current.min(length - 1);
+        self.suggestions = Vec::new();
+        self.check_words();
+    }
+
+    fn edit_word(&mut self, index: usize, value: String) {
+        if let Some((word, valid)) = self.words.get_mut(index) {
+            let value = value.trim().to_lowercase();
+            if value.len() >= 3 {
+                let suggestions = self.language.words_by_prefix(&value);
+                if is_word(self.language, &value) {
+                    *valid = true;
+                    self.suggestions = Vec::new();
+                } else {
+                    self.suggestions = suggestions.iter().map(|s| s.to_string()).collect();
+                    *valid = false;
+                }
+            } else {
+                self.suggestions = Vec::new();
+                *valid = false;
+            }
+            self.current = index;
+            *word = value;
+        }
+    }
+
+    /// Distribute the words of a phrase pasted in a field over this one and the following. A
+    /// whole mnemonic pasted in the first field sets the number of words.
+    fn paste(&mut self, index: usize, phrase: &str) {
+        let words = split_mnemonic(self.language, phrase);
+        if index == 0 && MNEMONIC_LENGTHS.contains(&words.len()) {
+            self.words.resize(words.len(), Default::default());
+        }
+        for (i, word) in words.into_iter().enumerate() {
+            if let Some(w) = self.words.get_mut(index + i) {
+                let valid = is_word(self.language, &word);
+                *w = (word, valid);
+                self.current = index + i;
+            }
+        }
+        self.suggestions = Vec::new();
+    }
+
+    /// Check the checksum of the mnemonic once all its words are from the list.
+    fn check_words(&mut self) {
+        self.error = if self.words.iter().all(|(_, valid)| *valid) {
+            let phrase: Vec<&str> = self.words.iter().map(|(w, _)| w.as_str()).collect();
+            bip39::Mnemonic::parse_in_normalized(self.language, &phrase.join(" "))
+                .err()
+                .map(|e| format!("Invalid mnemonic: {}", e))
+        } else {
+            None
+        };
+    }
+}
+
+fn is_word(language: bip39::Language, word: &str) -> bool {
+    language.words_by_prefix(word).contains(&word)
+}
+
+/// Split a pasted phrase into its words, whatever the whitespaces separating them. The
+/// abbreviations of the words to their first letters are expanded, since they are unique.
+pub fn split_mnemonic(language: bip39::Language, phrase: &str) -> Vec<String> {
+    phrase
+        .split_whitespace()
+        .map(|word| {
+            let word = word.to_lowercase();
+            if word.len() == WORD_PREFIX_LENGTH && !is_word(language, &word) {
+                if let [full] = language.words_by_prefix(&word) {
+                    return full.to_string();
+                }
+            }
+            word
+        })
+        .collect()
+}
+
 impl Step for RecoverMnemonic {
     fn update(&mut self, message: Message) -> Command<Message> {
         match message {
             Message::MnemonicWord(index, value) => {
-                if let Some((word, valid)) = self.words.get_mut(index) {
-                    if value.len() >= 3 {
-                        let suggestions = self.language.words_by_prefix(&value);
-                        if suggestions.contains(&value.as_ref()) {
-                            *valid = true;
-                            self.suggestions = Vec::new();
-                        } else {
-                            self.suggestions = suggestions.iter().map(|s| s.to_string()).collect();
-                            *valid = false;
-                        }
-                    } else {
-                        self.suggestions = Vec::new();
-                        *valid = false;
-                    }
-                    self.current = index;
-                    *word = value;
+                if value.split_whitespace().count() > 1 {
+                    self.paste(index, &value);
+                } else {
+                    self.edit_word(index, value);
+                }
+                self.check_words();
+            }
+            Message::MnemonicLength(length) => {
+                if MNEMONIC_LENGTHS.contains(&length) {
+                    self.set_length(length);
+                }
+            }
+            Message::MnemonicPassphrase(passphrase) => {
+                self.passphrase.value = passphrase;
+                // The fingerprint checked against the descriptor depends on it.
+                if self.error.is_some() {
+                    self.check_words();
                 }
             }
             Message::ImportMnemonic(recover) => self.recover = recover,
@@ -130,7 +452,11 @@ impl Step for RecoverMnemonic {
             .filter_map(|(s, valid)| if *valid { Some(s.clone()) } else { None })
             .collect();
 
-        let seed = match HotSigner::from_str(ctx.bitcoin_config.network, &words.join(" ")) {
+        let seed = match HotSigner::from_str_with_passphrase(
+            ctx.bitcoin_config.network,
+            &words.join(" "),
+            &self.passphrase.value,
+        ) {
             Ok(seed) => seed,
             Err(e) => {
                 self.error = Some(e.to_string());
@@ -153,8 +479,12 @@ impl Step for RecoverMnemonic {
                 }
             }
             if !descriptor_keys.contains(&fingerprint) {
-                self.error =
-                    Some("The descriptor does not use a key derived from this seed".to_string());
+                self.error = Some(if self.passphrase.value.is_empty() {
+                    "The descriptor does not use a key derived from this seed".to_string()
+                } else {
+                    "The descriptor does not use a key derived from this seed and passphrase"
+                        .to_string()
+                });
                 return false;
             }
         }
@@ -166,6 +496,7 @@ impl Step for RecoverMnemonic {
         view::recover_mnemonic(
             progress,
             &self.words,
+            &self.passphrase,
             self.current,
             &self.suggestions,
             self.recover,
@@ -173,3 +504,216 @@ impl Step for RecoverMnemonic {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(step: &RecoverMnemonic) -> Vec<&str> {
+        step.words.iter().map(|(w, _)| w.as_str()).collect()
+    }
+
+    #[test]
+    fn paste_mnemonic() {
+        let mut step = RecoverMnemonic::default();
+        let _ = step.update(Message::MnemonicWord(
+            0,
+            "  abandon abandon\tabandon abandon abandon  abandon\nabandon abandon abandon abandon abandon ABOUT "
+                .to_string(),
+        ));
+        assert_eq!(words(&step).len(), 12);
+        assert_eq!(words(&step)[11], "about");
+        assert!(step.words.iter().all(|(_, valid)| *valid));
+        assert!(step.error.is_none());
+
+        // A 24 words mnemonic, abbreviated, resizes the inputs.
+        let phrase = format!("{} art", ["aban"; 23].join("   "));
+        let _ = step.update(Message::MnemonicWord(0, phrase));
+        assert_eq!(words(&step).len(), 24);
+        assert!(words(&step)[..23].iter().all(|w| *w == "abandon"));
+        assert_eq!(words(&step)[23], "art");
+        assert!(step.words.iter().all(|(_, valid)| *valid));
+        assert!(step.error.is_none());
+
+        // The checksum is checked once all the words are from the list.
+        let _ = step.update(Message::MnemonicWord(23, "abandon".to_string()));
+        assert!(step.error.is_some());
+        let _ = step.update(Message::MnemonicWord(23, "art".to_string()));
+        assert!(step.error.is_none());
+
+        // Words pasted in another field are distributed from it, without resizing.
+        let _ = step.update(Message::MnemonicLength(12));
+        let _ = step.update(Message::MnemonicWord(10, "zoo zoo zoo".to_string()));
+        assert_eq!(words(&step).len(), 12);
+        assert_eq!(&words(&step)[9..], &["abandon", "zoo", "zoo"]);
+        assert!(step.error.is_some());
+
+        // Words out of the list are kept for the user to fix them.
+        let mut step = RecoverMnemonic::default();
+        let _ = step.update(Message::MnemonicWord(0, "abandon notaword".to_string()));
+        assert_eq!(&words(&step)[..2], &["abandon", "notaword"]);
+        assert!(step.words[0].1 && !step.words[1].1);
+    }
+
+    #[test]
+    fn mnemonic_length() {
+        let mut step = RecoverMnemonic::default();
+        let _ = step.update(Message::MnemonicLength(24));
+        assert_eq!(step.words.len(), 24);
+        let _ = step.update(Message::MnemonicLength(13));
+        assert_eq!(step.words.len(), 24);
+        // A pasted phrase of an invalid length fills the existing inputs.
+        let _ = step.update(Message::MnemonicWord(0, ["abandon"; 13].join(" ")));
+        assert_eq!(step.words.len(), 24);
+        assert!(words(&step)[..13].iter().all(|w| *w == "abandon"));
+        assert!(words(&step)[13..].iter().all(|w| w.is_empty()));
+    }
+
+    #[test]
+    fn recover_long_mnemonic() {
+        let network = Network::Testnet;
+        let mnemonic = format!("{} art", ["abandon"; 23].join(" "));
+        let mut ctx = Context::new(network, std::path::PathBuf::from("/tmp/liana"));
+        let mut step = RecoverMnemonic::default();
+        let _ = step.update(Message::MnemonicWord(0, mnemonic.clone()));
+        assert!(step.apply(&mut ctx));
+
+        // The recovered signer is stored with all its words.
+        let signer = ctx.recovered_signer.take().unwrap();
+        assert_eq!(signer.mnemonic().len(), 24);
+        let datadir =
+            std::env::temp_dir().join(format!("liana-gui-test-recover-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&datadir);
+        signer.store(&datadir, network).unwrap();
+        let stored = HotSigner::from_datadir(&datadir, network).unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].mnemonic_str(), mnemonic);
+        std::fs::remove_dir_all(&datadir).unwrap();
+    }
+
+    #[test]
+    fn backup_mnemonic_replaced_keys() {
+        use crate::installer::step::policy_builder::{BuilderKey, BuilderPath, ScriptType};
+        use liana::miniscript::{bitcoin::util::bip32::DerivationPath, descriptor::Wildcard};
+        use std::str::FromStr;
+
+        let network = Network::Testnet;
+        let key = |signer: &Signer| {
+            let path = DerivationPath::from_str("m/48'/1'/0'/2'").unwrap();
+            BuilderKey {
+                name: String::new(),
+                xpub: DescriptorXKey {
+                    origin: Some((signer.fingerprint(), path.clone())),
+                    xkey: signer.get_extended_pubkey(&path),
+                    derivation_path: DerivationPath::master(),
+                    wildcard: Wildcard::None,
+                },
+                device_kind: None,
+            }
+        };
+        let signer = Arc::new(Mutex::new(Signer::generate(network).unwrap()));
+        let builder = PolicyBuilder {
+            script_type: ScriptType::Wsh,
+            primary: BuilderPath {
+                threshold: 1,
+                keys: vec![key(&signer.lock().unwrap())],
+            },
+            recovery: vec![(
+                52560,
+                BuilderPath {
+                    threshold: 1,
+                    keys: vec![key(&Signer::generate(network).unwrap())],
+                },
+            )]
+            .into_iter()
+            .collect(),
+        };
+        let mut ctx = Context::new(network, std::path::PathBuf::from("/tmp/liana"));
+        ctx.descriptor = Some(builder.to_descriptor().unwrap());
+        ctx.policy_builder = Some(builder.clone());
+
+        // The policy gets the keys of the new hot signer, as the descriptor.
+        let mut step = BackupMnemonic::new(signer.clone(), true);
+        step.load_context(&ctx);
+        let _ = step.update(Message::RegenerateHotSigner);
+        assert!(step.apply(&mut ctx));
+        let replaced = ctx.policy_builder.clone().unwrap();
+        assert_eq!(
+            replaced.primary.keys[0].xpub.origin.as_ref().unwrap().0,
+            signer.lock().unwrap().fingerprint()
+        );
+        assert_eq!(replaced.recovery, builder.recovery);
+        assert_eq!(
+            replaced.to_descriptor().unwrap(),
+            ctx.descriptor.clone().unwrap()
+        );
+    }
+
+    #[test]
+    fn mnemonic_passphrase() {
+        let mnemonic = "burger ball theme dog light account produce chest warrior swarm flip equip";
+        let mut ctx = Context::new(Network::Testnet, std::path::PathBuf::from("/tmp/liana"));
+        let mut recovered = |passphrase: &str| {
+            let mut step = RecoverMnemonic::default();
+            let _ = step.update(Message::MnemonicWord(0, mnemonic.to_string()));
+            let _ = step.update(Message::MnemonicPassphrase(passphrase.to_string()));
+            assert!(step.apply(&mut ctx));
+            ctx.recovered_signer.take().unwrap().fingerprint()
+        };
+
+        // An empty passphrase recovers the same signer as before, another gives another seed.
+        let fingerprint =
+            Signer::new(HotSigner::from_str(Network::Testnet, mnemonic).unwrap()).fingerprint();
+        assert_eq!(recovered(""), fingerprint);
+        let with_passphrase = recovered("correct horse");
+        assert_ne!(with_passphrase, fingerprint);
+
+        // An invalid mnemonic is still refused, whatever the passphrase.
+        let mut step = RecoverMnemonic::default();
+        let _ = step.update(Message::MnemonicWord(0, ["abandon"; 12].join(" ")));
+        let _ = step.update(Message::MnemonicPassphrase("correct horse".to_string()));
+        assert!(step.error.is_some());
+        assert!(!step.apply(&mut ctx));
+        assert!(ctx.recovered_signer.is_none());
+
+        // The backed up hot signer gets the fingerprint of the confirmed passphrase.
+        let signer = Signer::new(HotSigner::from_str(Network::Testnet, mnemonic).unwrap());
+        let signer = Arc::new(Mutex::new(signer));
+        let mut step = BackupMnemonic::new(signer.clone(), true);
+        let _ = step.update(Message::UserActionDone(true));
+        let _ = step.update(Message::MnemonicPassphrase("correct horse".to_string()));
+        let _ = step.update(Message::MnemonicPassphraseConfirm(
+            "correct hors".to_string(),
+        ));
+        assert!(!step.passphrase_confirm.valid);
+        let _ = step.update(Message::ApplyPassphrase);
+        assert!(step.passphrase_error.is_some());
+        assert_eq!(signer.lock().unwrap().fingerprint(), fingerprint);
+
+        let _ = step.update(Message::MnemonicPassphraseConfirm(
+            "correct horse".to_string(),
+        ));
+        let _ = step.update(Message::ApplyPassphrase);
+        assert!(step.passphrase_applied && step.passphrase_error.is_none());
+        assert!(!step.done);
+        assert_eq!(signer.lock().unwrap().fingerprint(), with_passphrase);
+        assert!(signer.lock().unwrap().has_passphrase());
+        assert_eq!(step.words, signer.lock().unwrap().mnemonic());
+
+        // Removing it gives the initial signer back.
+        let _ = step.update(Message::MnemonicPassphrase(String::new()));
+        let _ = step.update(Message::MnemonicPassphraseConfirm(String::new()));
+        let _ = step.update(Message::ApplyPassphrase);
+        assert_eq!(signer.lock().unwrap().fingerprint(), fingerprint);
+        assert!(!step.discarded.contains(&fingerprint));
+
+        // Once the keys of the hot signer are shared, its passphrase cannot be changed.
+        let mut step = BackupMnemonic::new(signer.clone(), false);
+        let _ = step.update(Message::MnemonicPassphrase("correct horse".to_string()));
+        let _ = step.update(Message::MnemonicPassphraseConfirm(
+            "correct horse".to_string(),
+        ));
+        let _ = step.update(Message::ApplyPassphrase);
+        assert_eq!(signer.lock().unwrap().fingerprint(), fingerprint);
+    }
+}
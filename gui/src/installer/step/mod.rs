@@ -1,29 +1,57 @@
+mod aliases;
+mod attestation;
+mod coordinator;
 mod descriptor;
 mod mnemonic;
+mod payload;
+mod policy_builder;
+mod remote;
+mod second_opinion;
+mod template;
+mod tor;
 
+pub use coordinator::DefineCoordinator;
 pub use descriptor::{
-    BackupDescriptor, DefineDescriptor, ImportDescriptor, ParticipateXpub, RegisterDescriptor,
+    AddressBranch, BackupDescriptor, DefineDescriptor, DescriptorAnalysis, ImportDescriptor,
+    ParticipateXpub, RegisterDescriptor, RegistrationFormat, VerifyDescriptor,
+    MAX_EXPORTED_ADDRESSES,
 };
+pub use policy_builder::PolicyBuilder;
+pub use tor::{publish_onion_service, remove_onion_service, republish_onion_service, DefineTor};
 
-pub use mnemonic::{BackupMnemonic, RecoverMnemonic};
+use descriptor::{descriptor_policy_summary, export_addresses, first_addresses};
+use remote::{
+    check_remote, descriptor_import_request, export_to_remote, parse_remote_target, RemoteTarget,
+    DESCRIPTOR_IMPORT_FILE_NAME,
+};
+
+pub use mnemonic::{BackupMnemonic, RecoverMnemonic, MNEMONIC_LENGTHS};
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::sync::{Arc, Mutex};
 
-use iced::Command;
+use chrono::{Datelike, NaiveDate, NaiveDateTime, Utc};
+use iced::{time, Command, Subscription};
 use liana::{
-    config::BitcoindConfig,
+    config::{BitcoindConfig, BitcoindRpcAuth, DescriptorImportConfig},
+    descriptors::{LianaDescriptor, PathInfo},
     miniscript::bitcoin::{util::bip32::Fingerprint, Network},
 };
 
 use jsonrpc::{client::Client, simple_http::SimpleHttpTransport};
+use serde::{Deserialize, Serialize};
 
 use liana_ui::{component::form, widget::*};
 
-use crate::installer::{
-    context::Context,
-    message::{self, Message},
-    view, Error,
+use crate::{
+    app::{config as gui_config, menu::Menu},
+    installer::{
+        context::{Context, Rescan, DEFAULT_RESCAN_TIMESTAMP},
+        message::{self, Message},
+        prompt, view, watchonly_wallet_path, Error,
+    },
+    signer::Signer,
 };
 
 pub trait Step {
@@ -41,6 +69,37 @@ pub trait Step {
     fn apply(&mut self, _ctx: &mut Context) -> bool {
         true
     }
+    /// The section of the pre-install summary edited by going back to this step, if any.
+    fn summary_section(&self) -> Option<SummarySection> {
+        None
+    }
+    fn subscription(&self) -> Subscription<Message> {
+        Subscription::none()
+    }
+    /// The window got the focus back after the subscription of the step was paused: catch up
+    /// with what happened meanwhile.
+    fn resume(&mut self) -> Command<Message> {
+        Command::none()
+    }
+    /// Clear the inputs of the step, and what was checked of them. The defaults derived from
+    /// the context are then set again by `load_context`.
+    fn reset(&mut self) {}
+}
+
+/// The sections of the summary displayed before the install, each with a link to the step
+/// where its settings are edited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SummarySection {
+    /// The network and the descriptor, with the keys and their names.
+    Descriptor,
+    /// The registration of the descriptor on the signing devices.
+    Registration,
+    /// The connection to bitcoind.
+    Bitcoind,
+    /// The coordination server shared with the other participants.
+    Coordinator,
+    /// The control port of Tor the onion service of the daemon is published through.
+    Tor,
 }
 
 #[derive(Default)]
@@ -58,10 +117,58 @@ impl From<Welcome> for Box<dyn Step> {
     }
 }
 
+/// Timeout of the requests to bitcoind made by the installer.
+const BITCOIND_TIMEOUT_SECS: u64 = 3;
+
 pub struct DefineBitcoind {
+    network: Network,
     cookie_path: form::Value<String>,
     address: form::Value<String>,
+    use_rpc_auth: bool,
+    rpc_user: form::Value<String>,
+    rpc_password: form::Value<String>,
     is_running: Option<Result<(), Error>>,
+    warning: Option<String>,
+    version: Option<String>,
+}
+
+/// What was learnt about bitcoind when pinging it.
+#[derive(Debug, Clone)]
+pub struct BitcoindPing {
+    /// The user agent of bitcoind, such as `/Satoshi:24.0.1/`.
+    pub version: Option<String>,
+    pub warning: Option<String>,
+}
+
+/// The network of the chain name returned by `getblockchaininfo`.
+fn chain_network(chain: &str) -> Option<Network> {
+    match chain {
+        "main" => Some(Network::Bitcoin),
+        "test" => Some(Network::Testnet),
+        "signet" => Some(Network::Signet),
+        "regtest" => Some(Network::Regtest),
+        _ => None,
+    }
+}
+
+/// Check bitcoind runs on the network of the wallet. Only the chain is looked at, the other
+/// fields of `getblockchaininfo` may be missing while the node is still syncing.
+fn check_network(client: &Client, network: Network) -> Result<(), Error> {
+    let info: serde_json::Value = client
+        .send_request(client.build_request("getblockchaininfo", &[]))?
+        .result()?;
+    let chain = info.get("chain").and_then(serde_json::Value::as_str);
+    if chain.and_then(chain_network) == Some(network) {
+        Ok(())
+    } else {
+        Err(Error::BitcoindNetworkMismatch(format!(
+            "the wallet is on {} but bitcoind is running on {}. Connect to a node of the network of the wallet, or go back to change the network.",
+            network,
+            chain.and_then(chain_network).map(|n| n.to_string()).unwrap_or_else(|| {
+                format!("an unknown chain '{}'", chain.unwrap_or_default())
+            }),
+        )))
+    }
 }
 
 fn bitcoind_default_cookie_path(network: &Network) -> Option<String> {
@@ -110,36 +217,203 @@ fn bitcoind_default_address(network: &Network) -> String {
 impl DefineBitcoind {
     pub fn new() -> Self {
         Self {
+            network: Network::Bitcoin,
             cookie_path: form::Value::default(),
             address: form::Value::default(),
+            use_rpc_auth: false,
+            rpc_user: form::Value::default(),
+            rpc_password: form::Value::default(),
             is_running: None,
+            warning: None,
+            version: None,
         }
     }
 
+    /// A summary of the connection settings for a support request. The host, the cookie and the
+    /// credentials are never included.
+    fn support_summary(&self) -> String {
+        let address = match std::net::SocketAddr::from_str(&self.address.value) {
+            Ok(addr) => {
+                let host = match addr.ip() {
+                    std::net::IpAddr::V4(ip) if ip.is_loopback() => "loopback",
+                    std::net::IpAddr::V6(ip) if ip.is_loopback() => "loopback",
+                    std::net::IpAddr::V4(ip) if ip.is_private() || ip.is_link_local() => {
+                        "private network"
+                    }
+                    _ => "public",
+                };
+                format!("[REDACTED {} host]:{}", host, addr.port())
+            }
+            Err(_) => "[REDACTED invalid address]".to_string(),
+        };
+        let last_error = match &self.is_running {
+            None => "connection not checked",
+            Some(Ok(())) => "none",
+            Some(Err(Error::BitcoindWalletDisabled)) => "wallet disabled",
+            Some(Err(Error::BitcoindRemoteRpcRefused(_))) => "remote RPC access refused",
+            Some(Err(Error::BitcoindWorkQueueExceeded)) => "RPC work queue full",
+            Some(Err(Error::BitcoindCookieUnreadable(_))) => "cookie file unreadable",
+            Some(Err(Error::BitcoindUnauthorized)) => "authentication refused",
+            Some(Err(Error::BitcoindNetworkMismatch(_))) => "network mismatch",
+            Some(Err(Error::Bitcoind(_))) => "connection or RPC error",
+            Some(Err(_)) => "other",
+        };
+        format!(
+            "Network: {}\n\
+             Address: {}\n\
+             Authentication: {}\n\
+             Timeout: {}s\n\
+             bitcoind version: {}\n\
+             Last error: {}\n\
+             Warning: {}",
+            self.network,
+            address,
+            if self.use_rpc_auth {
+                "user and password ([REDACTED])"
+            } else {
+                "cookie file (path and content [REDACTED])"
+            },
+            BITCOIND_TIMEOUT_SECS,
+            self.version.as_deref().unwrap_or("unknown"),
+            last_error,
+            if self.warning.is_some() {
+                "inconsistent chain tips"
+            } else {
+                "none"
+            },
+        )
+    }
+
     pub fn ping(&self) -> Command<Message> {
         let address = self.address.value.to_owned();
         let cookie_path = self.cookie_path.value.to_owned();
+        let rpc_auth = self.rpc_auth();
+        let network = self.network;
         Command::perform(
             async move {
-                let cookie = std::fs::read_to_string(&cookie_path)
-                    .map_err(|e| Error::Bitcoind(format!("Failed to read cookie file: {}", e)))?;
-                let client = Client::with_transport(
-                    SimpleHttpTransport::builder()
-                        .url(&address)?
-                        .timeout(std::time::Duration::from_secs(3))
-                        .cookie_auth(cookie)
-                        .build(),
-                );
-                client.send_request(client.build_request("echo", &[]))?;
-                Ok(())
+                let builder = SimpleHttpTransport::builder()
+                    .url(&address)?
+                    .timeout(std::time::Duration::from_secs(BITCOIND_TIMEOUT_SECS));
+                let builder = match rpc_auth {
+                    Some(auth) => builder.auth(auth.user, Some(auth.password)),
+                    None => builder.cookie_auth(
+                        std::fs::read_to_string(&cookie_path)
+                            .map_err(|e| Error::BitcoindCookieUnreadable(e.to_string()))?,
+                    ),
+                };
+                let client = Client::with_transport(builder.build());
+                client
+                    .send_request(client.build_request("echo", &[]))
+                    .map_err(|e| connection_error(&address, e))?;
+                check_network(&client, network)?;
+                let version = client
+                    .send_request(client.build_request("getnetworkinfo", &[]))
+                    .ok()
+                    .and_then(|res| res.result::<serde_json::Value>().ok())
+                    .and_then(|info| {
+                        info.get("subversion")
+                            .and_then(serde_json::Value::as_str)
+                            .map(|v| v.to_string())
+                    });
+                check_wallet_enabled(&client)?;
+                Ok(BitcoindPing {
+                    version,
+                    warning: inconsistent_node_warning(&client)?,
+                })
             },
             |res| Message::DefineBitcoind(message::DefineBitcoind::PingBitcoindResult(res)),
         )
     }
+
+    /// The user and password entered, if bitcoind is not authenticated with its cookie file.
+    fn rpc_auth(&self) -> Option<BitcoindRpcAuth> {
+        if self.use_rpc_auth {
+            Some(BitcoindRpcAuth {
+                user: self.rpc_user.value.clone(),
+                password: self.rpc_password.value.clone(),
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// bitcoind refuses the connections on a non-loopback address unless it binds to it with
+/// `rpcbind`, and forbids the RPC access (HTTP 403) to the clients not allowed by `rpcallowip`.
+fn connection_error(address: &str, error: jsonrpc::Error) -> Error {
+    let is_remote = std::net::SocketAddr::from_str(address)
+        .map(|addr| !addr.ip().is_loopback())
+        .unwrap_or(false);
+    let refused = match &error {
+        jsonrpc::Error::Transport(e) => match e.downcast_ref::<jsonrpc::simple_http::Error>() {
+            Some(jsonrpc::simple_http::Error::SocketError(e)) => {
+                e.kind() == std::io::ErrorKind::ConnectionRefused
+            }
+            Some(jsonrpc::simple_http::Error::HttpErrorCode(403)) => true,
+            _ => false,
+        },
+        _ => false,
+    };
+    if is_remote && refused {
+        Error::BitcoindRemoteRpcRefused(address.to_string())
+    } else {
+        error.into()
+    }
+}
+
+/// Liana relies on the wallet RPCs of bitcoind. They are not registered at all, and therefore
+/// unknown, if bitcoind runs with `disablewallet=1`.
+fn check_wallet_enabled(client: &Client) -> Result<(), Error> {
+    const RPC_METHOD_NOT_FOUND: i32 = -32601;
+    let res = client.send_request(client.build_request("listwallets", &[]))?;
+    match res.result::<serde_json::Value>() {
+        Ok(_) => Ok(()),
+        Err(jsonrpc::Error::Rpc(e)) if e.code == RPC_METHOD_NOT_FOUND => {
+            Err(Error::BitcoindWalletDisabled)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Query the best block hash twice to detect whether the requests are being served by different
+/// nodes. If the tip changed in between, the second one must at least be a child of the first.
+fn inconsistent_node_warning(client: &Client) -> Result<Option<String>, Error> {
+    let first: String = client
+        .send_request(client.build_request("getbestblockhash", &[]))?
+        .result()?;
+    let second: String = client
+        .send_request(client.build_request("getbestblockhash", &[]))?
+        .result()?;
+    if first == second {
+        return Ok(None);
+    }
+
+    let res =
+        client.send_request(client.build_request("getblockheader", &[jsonrpc::arg(&second)]))?;
+    let consistent = match res.result::<serde_json::Value>() {
+        Ok(header) => {
+            header
+                .get("previousblockhash")
+                .and_then(serde_json::Value::as_str)
+                == Some(first.as_str())
+        }
+        // The node does not know about the tip it (or another one) just returned.
+        Err(jsonrpc::Error::Rpc(_)) => false,
+        Err(e) => return Err(e.into()),
+    };
+    if consistent {
+        Ok(None)
+    } else {
+        Ok(Some(prompt::INCONSISTENT_NODE_WARNING.to_string()))
+    }
 }
 
 impl Step for DefineBitcoind {
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
     fn load_context(&mut self, ctx: &Context) {
+        self.network = ctx.bitcoin_config.network;
         if self.cookie_path.value.is_empty() {
             self.cookie_path.value =
                 bitcoind_default_cookie_path(&ctx.bitcoin_config.network).unwrap_or_default()
@@ -153,25 +427,73 @@ impl Step for DefineBitcoind {
             match msg {
                 message::DefineBitcoind::PingBitcoind => {
                     self.is_running = None;
+                    self.warning = None;
                     return self.ping();
                 }
-                message::DefineBitcoind::PingBitcoindResult(res) => self.is_running = Some(res),
+                message::DefineBitcoind::PingBitcoindResult(res) => match res {
+                    Ok(ping) => {
+                        self.warning = ping.warning;
+                        self.version = ping.version;
+                        self.is_running = Some(Ok(()));
+                    }
+                    Err(e) => self.is_running = Some(Err(e)),
+                },
                 message::DefineBitcoind::AddressEdited(address) => {
                     self.is_running = None;
+                    self.warning = None;
                     self.address.value = address;
                     self.address.valid = true;
                 }
                 message::DefineBitcoind::CookiePathEdited(path) => {
                     self.is_running = None;
+                    self.warning = None;
                     self.cookie_path.value = path;
                     self.address.valid = true;
                 }
+                message::DefineBitcoind::RpcAuthToggled(use_rpc_auth) => {
+                    self.is_running = None;
+                    self.warning = None;
+                    self.use_rpc_auth = use_rpc_auth;
+                }
+                message::DefineBitcoind::RpcUserEdited(user) => {
+                    self.is_running = None;
+                    self.rpc_user.value = user;
+                    self.rpc_user.valid = true;
+                }
+                message::DefineBitcoind::RpcPasswordEdited(password) => {
+                    self.is_running = None;
+                    self.rpc_password.value = password;
+                    self.rpc_password.valid = true;
+                }
             };
         };
         Command::none()
     }
 
+    fn summary_section(&self) -> Option<SummarySection> {
+        Some(SummarySection::Bitcoind)
+    }
+
     fn apply(&mut self, ctx: &mut Context) -> bool {
+        if self.use_rpc_auth {
+            // The cookie file is not read, its path is not checked.
+            self.rpc_user.valid = !self.rpc_user.value.is_empty();
+            self.rpc_password.valid = !self.rpc_password.value.is_empty();
+            let addr = std::net::SocketAddr::from_str(&self.address.value);
+            self.address.valid = addr.is_ok();
+            return match addr {
+                Ok(addr) if self.rpc_user.valid && self.rpc_password.valid => {
+                    ctx.bitcoind_config = Some(BitcoindConfig {
+                        cookie_path: PathBuf::new(),
+                        addr,
+                        rpc_auth: self.rpc_auth(),
+                        descriptor_import: DescriptorImportConfig::default(),
+                    });
+                    true
+                }
+                _ => false,
+            };
+        }
         match (
             PathBuf::from_str(&self.cookie_path.value),
             std::net::SocketAddr::from_str(&self.address.value),
@@ -193,6 +515,8 @@ impl Step for DefineBitcoind {
                 ctx.bitcoind_config = Some(BitcoindConfig {
                     cookie_path: path,
                     addr,
+                    rpc_auth: None,
+                    descriptor_import: DescriptorImportConfig::default(),
                 });
                 true
             }
@@ -204,7 +528,10 @@ impl Step for DefineBitcoind {
             progress,
             &self.address,
             &self.cookie_path,
+            (self.use_rpc_auth, &self.rpc_user, &self.rpc_password),
             self.is_running.as_ref(),
+            self.warning.as_ref(),
+            self.support_summary(),
         )
     }
 
@@ -213,6 +540,213 @@ impl Step for DefineBitcoind {
     }
 }
 
+/// The view of bitcoind on one of the single-path descriptors, as returned by
+/// `getdescriptorinfo`.
+#[derive(Debug, Clone)]
+pub struct NodeDescriptorInfo {
+    /// The single-path descriptor, without its checksum.
+    pub descriptor: String,
+    /// The checksum computed by Liana.
+    pub checksum: String,
+    /// The checksum and solvability reported by bitcoind, or the reason it rejected the
+    /// descriptor.
+    pub node_info: Result<(String, bool), String>,
+}
+
+impl NodeDescriptorInfo {
+    /// Whether bitcoind agrees with Liana on this descriptor.
+    pub fn is_consistent(&self) -> bool {
+        match &self.node_info {
+            Ok((checksum, solvable)) => *solvable && checksum == &self.checksum,
+            Err(_) => false,
+        }
+    }
+}
+
+pub fn bitcoind_client(config: &BitcoindConfig) -> Result<Client, Error> {
+    bitcoind_client_at(config, &config.addr.to_string())
+}
+
+fn bitcoind_client_at(config: &BitcoindConfig, url: &str) -> Result<Client, Error> {
+    let builder = SimpleHttpTransport::builder()
+        .url(url)?
+        .timeout(std::time::Duration::from_secs(BITCOIND_TIMEOUT_SECS));
+    let builder = match &config.rpc_auth {
+        Some(auth) => builder.auth(auth.user.clone(), Some(auth.password.clone())),
+        None => builder.cookie_auth(
+            std::fs::read_to_string(&config.cookie_path)
+                .map_err(|e| Error::BitcoindCookieUnreadable(e.to_string()))?,
+        ),
+    };
+    Ok(Client::with_transport(builder.build()))
+}
+
+/// Interval between two requests for the progress of the rescan.
+const RESCAN_POLL_INTERVAL_SECS: u64 = 2;
+
+/// The progress of the rescan of the watchonly wallet, or `None` once it is over.
+async fn rescan_progress(config: BitcoindConfig, wallet: String) -> Result<Option<f64>, Error> {
+    let client = bitcoind_client_at(
+        &config,
+        &format!("http://{}/wallet/{}", config.addr, wallet),
+    )?;
+    let info = client
+        .send_request(client.build_request("getwalletinfo", &[]))?
+        .result::<serde_json::Value>()
+        .map_err(|e| Error::Bitcoind(e.to_string()))?;
+    // "scanning" is false when no rescan is running.
+    Ok(info
+        .get("scanning")
+        .and_then(|scanning| scanning.get("progress"))
+        .and_then(serde_json::Value::as_f64))
+}
+
+/// The time of the genesis block of the network. No Liana wallet can have coins before it.
+fn network_genesis_time(network: Network) -> u32 {
+    match network {
+        Network::Bitcoin => 1_231_006_505,
+        Network::Testnet | Network::Regtest => 1_296_688_602,
+        Network::Signet => 1_598_918_400,
+    }
+}
+
+/// The first block rescanned for the date picked by the user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RescanStart {
+    pub height: u32,
+    /// The median time of the block, given to the daemon as the time of the rescan.
+    pub timestamp: u32,
+    /// The date is before the launch of the network: the rescan starts at its genesis.
+    pub before_launch: bool,
+}
+
+/// The timestamp of the start of the given day, which can neither be in the future nor before
+/// the first block of Bitcoin.
+fn parse_rescan_date(year: &str, month: &str, day: &str, now: u32) -> Result<u32, String> {
+    let date = NaiveDate::from_ymd_opt(
+        i32::from_str(year.trim()).map_err(|_| "Please enter a valid year".to_string())?,
+        u32::from_str(month.trim()).map_err(|_| "Please enter a valid month".to_string())?,
+        u32::from_str(day.trim()).map_err(|_| "Please enter a valid day".to_string())?,
+    )
+    .ok_or_else(|| "This date does not exist".to_string())?;
+    let timestamp = date.and_hms_opt(0, 0, 0).expect("Valid time").timestamp();
+    if timestamp < network_genesis_time(Network::Bitcoin) as i64 - 86_400 {
+        Err("The date is before the first block of Bitcoin, on January 3rd 2009".to_string())
+    } else if timestamp > now as i64 {
+        Err("The date is in the future".to_string())
+    } else {
+        Ok(timestamp.max(0) as u32)
+    }
+}
+
+/// The first block of the chain up to the given tip whose median time is at or after the
+/// timestamp, found by bisection as the median time of the blocks always increases.
+fn first_block_after(
+    tip: u32,
+    timestamp: u32,
+    mut median_time: impl FnMut(u32) -> Result<u32, Error>,
+) -> Result<Option<u32>, Error> {
+    if median_time(tip)? < timestamp {
+        return Ok(None);
+    }
+    let (mut low, mut high) = (0, tip);
+    while low < high {
+        let middle = low + (high - low) / 2;
+        if median_time(middle)? < timestamp {
+            low = middle + 1;
+        } else {
+            high = middle;
+        }
+    }
+    Ok(Some(low))
+}
+
+/// Find, from the headers of bitcoind, the first block rescanned for the given date.
+async fn rescan_start(
+    config: BitcoindConfig,
+    network: Network,
+    timestamp: u32,
+) -> Result<RescanStart, Error> {
+    let client = bitcoind_client(&config)?;
+    let call = |method: &str,
+                args: &[Box<serde_json::value::RawValue>]|
+     -> Result<serde_json::Value, Error> {
+        client
+            .send_request(client.build_request(method, args))?
+            .result::<serde_json::Value>()
+            .map_err(|e| Error::Bitcoind(e.to_string()))
+    };
+    let median_time = |height: u32| -> Result<u32, Error> {
+        let hash = call("getblockhash", &[jsonrpc::arg(height)])?;
+        call("getblockheader", &[jsonrpc::arg(hash)])?
+            .get("mediantime")
+            .and_then(serde_json::Value::as_u64)
+            .map(|time| time as u32)
+            .ok_or_else(|| Error::Bitcoind("No median time in the block header".to_string()))
+    };
+
+    let genesis_time = network_genesis_time(network);
+    if timestamp <= genesis_time {
+        return Ok(RescanStart {
+            height: 0,
+            timestamp: genesis_time,
+            // The day of the launch starts before its first block.
+            before_launch: timestamp + 86_400 <= genesis_time,
+        });
+    }
+    let tip = call("getblockcount", &[])?
+        .as_u64()
+        .ok_or_else(|| Error::Bitcoind("Invalid block count".to_string()))? as u32;
+    let height = first_block_after(tip, timestamp, &median_time)?.ok_or_else(|| {
+        Error::Bitcoind("the date is after the last block known by bitcoind".to_string())
+    })?;
+    Ok(RescanStart {
+        height,
+        timestamp: median_time(height)?,
+        before_launch: false,
+    })
+}
+
+/// Ask bitcoind for its view of the receive and change descriptors. A descriptor rejected by
+/// bitcoind is not an error, the reason is recorded in the returned info instead.
+async fn get_descriptors_info(
+    config: BitcoindConfig,
+    descriptor: LianaDescriptor,
+) -> Result<Vec<NodeDescriptorInfo>, Error> {
+    let client = bitcoind_client(&config)?;
+    let mut infos = Vec::new();
+    for desc in vec![
+        descriptor.receive_descriptor().to_string(),
+        descriptor.change_descriptor().to_string(),
+    ] {
+        let (desc, checksum) = desc
+            .split_once('#')
+            .map(|(desc, checksum)| (desc.to_string(), checksum.to_string()))
+            .unwrap_or_else(|| (desc.clone(), String::new()));
+        let res = client
+            .send_request(client.build_request("getdescriptorinfo", &[jsonrpc::arg(&desc)]))?;
+        let node_info = match res.result::<serde_json::Value>() {
+            Ok(info) => Ok((
+                info.get("checksum")
+                    .and_then(serde_json::Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+                info.get("issolvable")
+                    .and_then(serde_json::Value::as_bool)
+                    .unwrap_or(false),
+            )),
+            Err(jsonrpc::Error::Rpc(e)) => Err(e.message),
+            Err(e) => return Err(e.into()),
+        };
+        infos.push(NodeDescriptorInfo {
+            descriptor: desc,
+            checksum,
+            node_info,
+        });
+    }
+    Ok(infos)
+}
+
 impl Default for DefineBitcoind {
     fn default() -> Self {
         Self::new()
@@ -225,45 +759,588 @@ impl From<DefineBitcoind> for Box<dyn Step> {
     }
 }
 
+/// The spending paths of the descriptor the keys of the hot signer are in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HotSignerPlacement {
+    pub primary: bool,
+    /// The timelocks of the recovery paths.
+    pub recovery: Vec<u16>,
+}
+
+impl HotSignerPlacement {
+    /// The placement of the keys of the given master key, if the descriptor has any.
+    pub fn new(descriptor: &LianaDescriptor, fingerprint: Fingerprint) -> Option<Self> {
+        let has_key = |path: &PathInfo| {
+            path.thresh_origins()
+                .1
+                .iter()
+                .any(|(origin, _)| *origin == fingerprint)
+        };
+        let policy = descriptor.policy();
+        let placement = Self {
+            primary: has_key(policy.primary_path()),
+            recovery: policy
+                .recovery_paths()
+                .iter()
+                .filter(|(_, path)| has_key(path))
+                .map(|(sequence, _)| *sequence)
+                .collect(),
+        };
+        if placement.primary || !placement.recovery.is_empty() {
+            Some(placement)
+        } else {
+            None
+        }
+    }
+
+    /// The role of the hot signer in the policy, for the user.
+    pub fn description(&self) -> String {
+        let timelocks = self
+            .recovery
+            .iter()
+            .map(|sequence| sequence.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        match (self.primary, self.recovery.is_empty()) {
+            (true, true) => "Primary key".to_string(),
+            (true, false) => format!("Primary key and recovery key after {} blocks", timelocks),
+            (false, false) => format!("Recovery key after {} blocks", timelocks),
+            (false, true) => "Not used".to_string(),
+        }
+    }
+}
+
 pub struct Final {
     generating: bool,
     context: Option<Context>,
     warning: Option<String>,
     config_path: Option<PathBuf>,
+    hot_signer: Arc<Mutex<Signer>>,
     hot_signer_fingerprint: Fingerprint,
-    hot_signer_is_not_used: bool,
+    hot_signer_placement: Option<HotSignerPlacement>,
+    descriptor_check: DescriptorCheck,
+    descriptor_import: DescriptorImport,
+    daemon_interface: DaemonInterface,
+    rescan: RescanOptions,
+    /// The first receive and change addresses of the descriptor.
+    first_addresses: Option<(String, String)>,
+    address_export: AddressExport,
+    /// The context with the options of this step applied, reviewed by the user before the
+    /// install.
+    summary: Option<Context>,
+    checklist: Checklist,
+    /// The authorization of the client of the published onion service, shown once installed.
+    tor_client_auth: Option<String>,
+    /// Whether the clean up of the files left by a failed install is running, if it is needed.
+    cleanup: Option<bool>,
+    remote_export: RemoteExport,
+    another_wallet: AnotherWallet,
+}
+
+/// The check by bitcoind of the descriptors of the wallet.
+#[derive(Default)]
+struct DescriptorCheck {
+    checking: bool,
+    info: Option<Result<Vec<NodeDescriptorInfo>, Error>>,
+}
+
+impl DescriptorCheck {
+    fn view(&self) -> Element<Message> {
+        view::node_descriptor_check(self.checking, self.info.as_ref())
+    }
+}
+
+/// The ranges of the descriptor imported in the watchonly wallet of bitcoind.
+struct DescriptorImport {
+    import_change: bool,
+    receive_range_end: form::Value<String>,
+    change_range_end: form::Value<String>,
+}
+
+impl DescriptorImport {
+    fn new() -> Self {
+        let import = DescriptorImportConfig::default();
+        Self {
+            import_change: import.import_change,
+            receive_range_end: form::Value {
+                value: import.receive_range_end.to_string(),
+                valid: true,
+            },
+            change_range_end: form::Value {
+                value: import.change_range_end.to_string(),
+                valid: true,
+            },
+        }
+    }
+
+    fn view(&self) -> Element<Message> {
+        view::descriptor_import_options(
+            self.import_change,
+            &self.receive_range_end,
+            &self.change_range_end,
+            import_range_warning(&self.receive_range_end.value, &self.change_range_end.value),
+        )
+    }
+}
+
+/// The socket of a daemon managed separately from Liana, if it does not start its own.
+#[derive(Default)]
+struct DaemonInterface {
+    external: bool,
+    rpc_path: form::Value<String>,
+}
+
+impl DaemonInterface {
+    fn view(&self) -> Element<Message> {
+        view::daemon_interface_options(self.external, &self.rpc_path)
+    }
+}
+
+/// The rescan of the chain for the coins of the descriptor.
+struct RescanOptions {
+    /// Whether to rescan, and to wait for the rescan before completing the install.
+    enabled: bool,
+    wait: bool,
+    /// The date the rescan starts at, and the first block rescanned as found for it or why it
+    /// could not be.
+    year: form::Value<String>,
+    month: form::Value<String>,
+    day: form::Value<String>,
+    start: Option<Result<RescanStart, String>>,
+    /// The timestamp of the date the first block is being looked up for.
+    lookup: Option<u32>,
+    /// The progress of the rescan started by the install, until it is over.
+    progress: Option<f64>,
+}
+
+impl RescanOptions {
+    fn new() -> Self {
+        let default_date = NaiveDateTime::from_timestamp_opt(DEFAULT_RESCAN_TIMESTAMP as i64, 0)
+            .expect("Valid timestamp")
+            .date();
+        Self {
+            enabled: false,
+            wait: true,
+            year: form::Value {
+                value: default_date.year().to_string(),
+                valid: true,
+            },
+            month: form::Value {
+                value: default_date.month().to_string(),
+                valid: true,
+            },
+            day: form::Value {
+                value: default_date.day().to_string(),
+                valid: true,
+            },
+            start: None,
+            lookup: None,
+            progress: None,
+        }
+    }
+
+    /// The timestamp of the date the rescan starts at, if valid.
+    fn date(&self) -> Result<u32, String> {
+        parse_rescan_date(
+            &self.year.value,
+            &self.month.value,
+            &self.day.value,
+            Utc::now().timestamp() as u32,
+        )
+    }
+
+    /// The first block rescanned, if the chain is to be rescanned.
+    fn first_block(&self) -> Option<&RescanStart> {
+        self.start
+            .as_ref()
+            .and_then(|start| start.as_ref().ok())
+            .filter(|_| self.enabled)
+    }
+
+    /// Whether the install is not complete until the rescan it started is over.
+    fn is_waited(&self) -> bool {
+        self.wait && self.progress.is_some()
+    }
+
+    fn view(&self) -> Element<Message> {
+        view::rescan_options(
+            self.enabled,
+            self.wait,
+            [&self.year, &self.month, &self.day],
+            self.start.as_ref(),
+        )
+    }
+
+    fn progress_view(&self) -> Option<Element<Message>> {
+        self.progress
+            .map(|progress| view::rescan_progress(progress, self.wait))
+    }
+}
+
+/// The export to a file of the first addresses of the descriptor.
+struct AddressExport {
+    count: form::Value<String>,
+    branch: AddressBranch,
+    path: form::Value<String>,
+    result: Option<Result<PathBuf, Error>>,
+}
+
+impl AddressExport {
+    fn new() -> Self {
+        Self {
+            count: form::Value {
+                value: "100".to_string(),
+                valid: true,
+            },
+            branch: AddressBranch::Both,
+            path: form::Value::default(),
+            result: None,
+        }
+    }
+
+    fn view(&self) -> Element<Message> {
+        view::address_export_options(&self.count, self.branch, &self.path, self.result.as_ref())
+    }
+}
+
+/// The items of the post-install checklist checked by the user.
+#[derive(Default)]
+struct Checklist(Vec<ChecklistItem>);
+
+impl Checklist {
+    fn toggle(&mut self, item: ChecklistItem, checked: bool) {
+        self.0.retain(|i| *i != item);
+        if checked {
+            self.0.push(item);
+        }
+    }
+
+    fn view(&self, config_path: &Path) -> Element<Message> {
+        view::post_install_checklist(
+            config_path,
+            ChecklistItem::ALL
+                .iter()
+                .map(|item| (*item, self.0.contains(item)))
+                .collect(),
+        )
+    }
+}
+
+/// The export of the generated configuration to a remote server, once installed.
+struct RemoteExport {
+    enabled: bool,
+    host: form::Value<String>,
+    directory: form::Value<String>,
+    descriptor_import: bool,
+    processing: bool,
+    result: Option<Result<&'static str, Error>>,
+}
+
+impl RemoteExport {
+    fn new() -> Self {
+        Self {
+            enabled: false,
+            host: form::Value::default(),
+            directory: form::Value::default(),
+            descriptor_import: true,
+            processing: false,
+            result: None,
+        }
+    }
+
+    fn target(&mut self) -> Option<RemoteTarget> {
+        match parse_remote_target(&self.host.value, &self.directory.value) {
+            Ok(target) => Some(target),
+            Err(e) => {
+                self.result = Some(Err(Error::RemoteExport(e)));
+                None
+            }
+        }
+    }
+
+    fn view(&self) -> Element<Message> {
+        view::remote_export_options(if self.enabled {
+            Some((
+                &self.host,
+                &self.directory,
+                self.descriptor_import,
+                self.processing,
+                self.result.as_ref(),
+            ))
+        } else {
+            None
+        })
+    }
+}
+
+/// The name of another wallet to set up once this one is installed, and why it cannot be used.
+#[derive(Default)]
+struct AnotherWallet {
+    name: form::Value<String>,
+    error: Option<&'static str>,
+}
+
+impl AnotherWallet {
+    fn view<'a>(&'a self, ctx: &'a Context, waiting_rescan: bool) -> Element<'a, Message> {
+        view::installed_wallets(
+            ctx,
+            // On Windows the watchonly wallets of the daemons would share the same name in the
+            // data directory of bitcoind.
+            if cfg!(windows) {
+                None
+            } else {
+                Some((&self.name, self.error))
+            },
+            waiting_rescan,
+        )
+    }
+}
+
+/// The file, next to the gui configuration, where the checked items of the post-install
+/// checklist are stored.
+const CHECKLIST_FILE_NAME: &str = "checklist.json";
+
+/// The things a user should do once the wallet is installed, before relying on it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChecklistItem {
+    BackupStoredOffline,
+    FundReceiveAddress,
+    TestSmallSpend,
+    UnderstandRecoveryPath,
+}
+
+impl ChecklistItem {
+    pub const ALL: [ChecklistItem; 4] = [
+        ChecklistItem::BackupStoredOffline,
+        ChecklistItem::FundReceiveAddress,
+        ChecklistItem::TestSmallSpend,
+        ChecklistItem::UnderstandRecoveryPath,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::BackupStoredOffline => "Verify the descriptor backup is stored offline",
+            Self::FundReceiveAddress => "Receive some coins on a new address",
+            Self::TestSmallSpend => "Test a small spend with your signing devices",
+            Self::UnderstandRecoveryPath => {
+                "Make sure you understand how and when the recovery path can be used"
+            }
+        }
+    }
+
+    /// The panel of the application where the item can be done, if any.
+    pub fn menu(&self) -> Option<Menu> {
+        match self {
+            Self::BackupStoredOffline => None,
+            Self::FundReceiveAddress => Some(Menu::Receive),
+            Self::TestSmallSpend => Some(Menu::CreateSpendTx),
+            Self::UnderstandRecoveryPath => Some(Menu::Recovery),
+        }
+    }
+}
+
+fn checklist_path(config_path: &Path) -> PathBuf {
+    let mut path = config_path.to_path_buf();
+    path.set_file_name(CHECKLIST_FILE_NAME);
+    path
+}
+
+fn read_checklist(config_path: &Path) -> Vec<ChecklistItem> {
+    std::fs::read(checklist_path(config_path))
+        .ok()
+        .and_then(|content| serde_json::from_slice(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_checklist(config_path: &Path, checklist: &[ChecklistItem]) {
+    let res = serde_json::to_vec_pretty(checklist)
+        .map_err(|e| e.to_string())
+        .and_then(|content| {
+            std::fs::write(checklist_path(config_path), content).map_err(|e| e.to_string())
+        });
+    if let Err(e) = res {
+        tracing::warn!("Failed to store the post-install checklist: {}", e);
+    }
+}
+
+/// The range imported is never extended afterward: the addresses past its end would not be
+/// detected. It is at least bitcoind's default.
+const MIN_IMPORT_RANGE_END: u32 = 1_000;
+
+/// bitcoind refuses to import ranges of more than a million indexes.
+const MAX_IMPORT_RANGE_END: u32 = 999_999;
+
+/// Past this index, deriving and scanning for the imported addresses noticeably slows down the
+/// rescan of the chain.
+const SLOW_IMPORT_RANGE_END: u32 = 100_000;
+
+fn parse_range_end(value: &str) -> Option<u32> {
+    u32::from_str(value)
+        .ok()
+        .filter(|end| *end >= MIN_IMPORT_RANGE_END && *end <= MAX_IMPORT_RANGE_END)
+}
+
+/// A warning if one of the ranges to import is valid but large enough to slow the rescan.
+fn import_range_warning(receive_range_end: &str, change_range_end: &str) -> Option<String> {
+    let slow: Vec<String> = [
+        ("receive", parse_range_end(receive_range_end)),
+        ("change", parse_range_end(change_range_end)),
+    ]
+    .iter()
+    .filter_map(|(branch, end)| {
+        end.filter(|end| *end > SLOW_IMPORT_RANGE_END)
+            .map(|end| format!("{} addresses up to index {}", branch, end))
+    })
+    .collect();
+    if slow.is_empty() {
+        None
+    } else {
+        Some(format!(
+            "Importing the {} will make the rescan of the chain slow.",
+            slow.join(" and the ")
+        ))
+    }
+}
+
+/// The daemon only listens on a unix socket: its path must be absolute, in an existing
+/// directory, and must not be a directory itself.
+fn parse_daemon_rpc_path(value: &str) -> Option<PathBuf> {
+    parse_file_path(value)
+}
+
+/// The absolute path of a file to be created or replaced, in an existing directory.
+fn parse_file_path(value: &str) -> Option<PathBuf> {
+    let path = PathBuf::from(value.trim());
+    if path.is_absolute() && path.parent().map(Path::is_dir).unwrap_or(false) && !path.is_dir() {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+fn parse_address_count(value: &str) -> Option<u32> {
+    u32::from_str(value.trim())
+        .ok()
+        .filter(|count| *count > 0 && *count <= MAX_EXPORTED_ADDRESSES)
 }
 
 impl Final {
-    pub fn new(hot_signer_fingerprint: Fingerprint) -> Self {
+    pub fn new(hot_signer: Arc<Mutex<Signer>>) -> Self {
+        let hot_signer_fingerprint = hot_signer.lock().unwrap().fingerprint();
         Self {
             context: None,
             generating: false,
             warning: None,
             config_path: None,
+            hot_signer,
             hot_signer_fingerprint,
-            hot_signer_is_not_used: false,
+            hot_signer_placement: None,
+            descriptor_check: DescriptorCheck::default(),
+            descriptor_import: DescriptorImport::new(),
+            daemon_interface: DaemonInterface::default(),
+            rescan: RescanOptions::new(),
+            first_addresses: None,
+            address_export: AddressExport::new(),
+            summary: None,
+            checklist: Checklist::default(),
+            tor_client_auth: None,
+            cleanup: None,
+            remote_export: RemoteExport::new(),
+            another_wallet: AnotherWallet::default(),
         }
     }
+
+    /// Look up the first block rescanned for the date entered, once it is valid. A lookup for a
+    /// previous date still running is ignored once over.
+    fn lookup_rescan_start(&mut self) -> Command<Message> {
+        self.rescan.lookup = None;
+        match self.rescan.date() {
+            Ok(timestamp) => {
+                self.rescan.start = None;
+                self.rescan.lookup = Some(timestamp);
+                self.load()
+            }
+            Err(e) => {
+                self.rescan.start = Some(Err(e));
+                Command::none()
+            }
+        }
+    }
+
+    /// The files exported to the server: the configuration of the daemon as it was written and,
+    /// if requested, the import of the descriptor in the watchonly wallet. The mnemonics of the
+    /// hot signers are never part of them.
+    fn remote_files(&self) -> Result<Vec<(&'static str, String)>, Error> {
+        // The daemon configuration is written next to the gui one.
+        let path = self
+            .config_path
+            .as_ref()
+            .and_then(|path| path.parent())
+            .map(|dir| dir.join("daemon.toml"))
+            .ok_or_else(|| Error::RemoteExport("the wallet is not installed yet".to_string()))?;
+        let content = std::fs::read_to_string(&path).map_err(|e| {
+            Error::RemoteExport(format!("failed to read the daemon configuration: {}", e))
+        })?;
+        let descriptor_import = if self.remote_export.descriptor_import {
+            let config: liana::config::Config = toml::from_str(&content)
+                .map_err(|e| Error::RemoteExport(format!("invalid daemon configuration: {}", e)))?;
+            // The remote wallet is rescanned from the date chosen for the local one, if any.
+            let rescan_timestamp = self.rescan.first_block().map(|start| start.timestamp);
+            Some(descriptor_import_request(
+                &config.main_descriptor,
+                &config
+                    .bitcoind_config
+                    .map(|c| c.descriptor_import)
+                    .unwrap_or_default(),
+                rescan_timestamp,
+            ))
+        } else {
+            None
+        };
+        let mut files = vec![("daemon.toml", content)];
+        files.extend(descriptor_import.map(|request| (DESCRIPTOR_IMPORT_FILE_NAME, request)));
+        Ok(files)
+    }
 }
 
 impl Step for Final {
+    fn load(&self) -> Command<Message> {
+        match (self.rescan.lookup, &self.context) {
+            (Some(timestamp), Some(ctx)) => match ctx.bitcoind_config.clone() {
+                Some(config) => Command::perform(
+                    rescan_start(config, ctx.bitcoin_config.network, timestamp),
+                    move |res| Message::Final(message::Final::RescanStartFound(timestamp, res)),
+                ),
+                None => Command::none(),
+            },
+            _ => Command::none(),
+        }
+    }
     fn load_context(&mut self, ctx: &Context) {
         self.context = Some(ctx.clone());
+        // The descriptor may have changed since the last check.
+        self.descriptor_check.info = None;
+        // And the node the first block rescanned is looked up from.
+        if self.rescan.enabled {
+            let date = self.rescan.date();
+            self.rescan.lookup = date.as_ref().ok().copied();
+            self.rescan.start = date.err().map(Err);
+        }
+        self.summary = None;
+        self.first_addresses = ctx
+            .descriptor
+            .as_ref()
+            .map(|desc| first_addresses(desc, ctx.bitcoin_config.network));
+        // The hot signer may have been regenerated by the user.
+        self.hot_signer_fingerprint = self.hot_signer.lock().unwrap().fingerprint();
         if let Some(signer) = &ctx.recovered_signer {
             self.hot_signer_fingerprint = signer.fingerprint();
-            self.hot_signer_is_not_used = false;
-        } else if ctx
+        }
+        self.hot_signer_placement = ctx
             .descriptor
             .as_ref()
-            .unwrap()
-            .to_string()
-            .contains(&self.hot_signer_fingerprint.to_string())
-        {
-            self.hot_signer_is_not_used = false;
-        } else {
-            self.hot_signer_is_not_used = true;
-        }
+            .and_then(|desc| HotSignerPlacement::new(desc, self.hot_signer_fingerprint));
     }
     fn update(&mut self, message: Message) -> Command<Message> {
         match message {
@@ -272,36 +1349,374 @@ impl Step for Final {
                 match res {
                     Err(e) => {
                         self.config_path = None;
+                        self.tor_client_auth = None;
+                        self.cleanup = Some(false);
                         self.warning = Some(e.to_string());
                     }
-                    Ok(path) => self.config_path = Some(path),
+                    Ok(path) => {
+                        self.checklist = Checklist(read_checklist(&path));
+                        // The onion address is only known once the service is published.
+                        self.tor_client_auth = self
+                            .context
+                            .as_ref()
+                            .and_then(|ctx| ctx.tor_client_key.as_ref())
+                            .and_then(|key| {
+                                let address = gui_config::Config::from_file(&path)
+                                    .ok()?
+                                    .tor?
+                                    .onion_address?;
+                                Some(tor::client_auth_line(&address, key))
+                            });
+                        if let Some(ctx) = &mut self.context {
+                            ctx.record_installed_wallet(path.clone());
+                        }
+                        self.config_path = Some(path);
+                        if self.rescan.enabled {
+                            self.rescan.progress = Some(0.0);
+                        }
+                    }
+                }
+            }
+            Message::Final(message::Final::AddressExportCountEdited(count)) => {
+                self.address_export.count.valid = parse_address_count(&count).is_some();
+                self.address_export.count.value = count;
+                self.address_export.result = None;
+            }
+            Message::Final(message::Final::AddressExportBranchSelected(branch)) => {
+                self.address_export.branch = branch;
+                self.address_export.result = None;
+            }
+            Message::Final(message::Final::AddressExportPathEdited(path)) => {
+                self.address_export.path.valid = true;
+                self.address_export.path.value = path;
+                self.address_export.result = None;
+            }
+            Message::Final(message::Final::ExportAddresses) => {
+                let count = parse_address_count(&self.address_export.count.value);
+                let path = parse_file_path(&self.address_export.path.value);
+                self.address_export.count.valid = count.is_some();
+                self.address_export.path.valid = path.is_some();
+                if let (Some(count), Some(path), Some(ctx)) = (count, path, &self.context) {
+                    let descriptor = ctx.descriptor.clone().expect("Descriptor is set");
+                    let network = ctx.bitcoin_config.network;
+                    let branch = self.address_export.branch;
+                    return Command::perform(
+                        async move {
+                            std::fs::write(
+                                &path,
+                                export_addresses(&descriptor, network, branch, count),
+                            )
+                            .map_err(|e| Error::CannotWriteToFile(e.to_string()))?;
+                            Ok(path)
+                        },
+                        |res| Message::Final(message::Final::AddressesExported(res)),
+                    );
+                }
+            }
+            Message::Final(message::Final::AddressesExported(res)) => {
+                self.address_export.result = Some(res);
+            }
+            Message::Final(message::Final::RescanToggled(rescan)) => {
+                self.rescan.enabled = rescan;
+                if rescan && self.rescan.start.is_none() && self.rescan.lookup.is_none() {
+                    return self.lookup_rescan_start();
+                }
+            }
+            Message::Final(message::Final::RescanDateEdited(field, value)) => {
+                if value.is_empty() || u32::from_str(&value).is_ok() {
+                    match field {
+                        "year" => self.rescan.year.value = value,
+                        "month" => self.rescan.month.value = value,
+                        "day" => self.rescan.day.value = value,
+                        _ => {}
+                    }
+                    return self.lookup_rescan_start();
+                }
+            }
+            Message::Final(message::Final::RescanStartFound(timestamp, res)) => {
+                if self.rescan.lookup == Some(timestamp) {
+                    self.rescan.lookup = None;
+                    self.rescan.start = Some(res.map_err(|e| e.to_string()));
+                }
+            }
+            Message::Final(message::Final::RescanWaitToggled(wait)) => {
+                self.rescan.wait = wait;
+            }
+            Message::Final(message::Final::PollRescan) => {
+                if let Some(ctx) = &self.context {
+                    if let Some(config) = ctx.bitcoind_config.clone() {
+                        let mut network_datadir = ctx.data_dir.clone();
+                        network_datadir.push(ctx.bitcoin_config.network.to_string());
+                        return Command::perform(
+                            rescan_progress(config, watchonly_wallet_path(&network_datadir)),
+                            |res| Message::Final(message::Final::RescanProgress(res)),
+                        );
+                    }
+                }
+            }
+            Message::Final(message::Final::RescanProgress(res)) => match res {
+                Ok(progress) => self.rescan.progress = progress,
+                Err(e) => {
+                    // The rescan goes on in bitcoind, the wallet displays its progress.
+                    self.rescan.progress = None;
+                    self.warning = Some(format!("Failed to follow the rescan: {}", e));
+                }
+            },
+            Message::CleanUpInstall => {
+                self.cleanup = Some(true);
+            }
+            Message::InstallCleanedUp(res) => {
+                self.cleanup = Some(false);
+                match res {
+                    Ok(()) => {
+                        self.cleanup = None;
+                        self.warning = None;
+                    }
+                    Err(e) => {
+                        self.warning = Some(format!("Failed to clean up the install: {}", e));
+                    }
                 }
             }
             Message::Install => {
                 self.generating = true;
                 self.config_path = None;
                 self.warning = None;
+                self.summary = None;
+            }
+            Message::Final(message::Final::Review(review)) => {
+                self.summary = None;
+                if review {
+                    if let Some(mut ctx) = self.context.clone() {
+                        if self.apply(&mut ctx) {
+                            self.summary = Some(ctx);
+                        }
+                    }
+                }
+            }
+            Message::Final(message::Final::CheckDescriptor) => {
+                if let Some(ctx) = &self.context {
+                    if let (Some(config), Some(descriptor)) =
+                        (ctx.bitcoind_config.clone(), ctx.descriptor.clone())
+                    {
+                        self.descriptor_check.checking = true;
+                        self.descriptor_check.info = None;
+                        return Command::perform(get_descriptors_info(config, descriptor), |res| {
+                            Message::Final(message::Final::DescriptorChecked(res))
+                        });
+                    }
+                }
+            }
+            Message::Final(message::Final::DescriptorChecked(res)) => {
+                self.descriptor_check.checking = false;
+                self.descriptor_check.info = Some(res);
+            }
+            Message::Final(message::Final::ImportChangeToggled(import_change)) => {
+                self.descriptor_import.import_change = import_change;
+            }
+            Message::Final(message::Final::ReceiveRangeEdited(value)) => {
+                self.descriptor_import.receive_range_end.valid = parse_range_end(&value).is_some();
+                self.descriptor_import.receive_range_end.value = value;
+            }
+            Message::Final(message::Final::ChangeRangeEdited(value)) => {
+                self.descriptor_import.change_range_end.valid = parse_range_end(&value).is_some();
+                self.descriptor_import.change_range_end.value = value;
+            }
+            Message::Final(message::Final::ExternalDaemonToggled(external_daemon)) => {
+                self.daemon_interface.external = external_daemon;
+                if external_daemon && self.daemon_interface.rpc_path.value.is_empty() {
+                    // Suggest the default location of the socket of the daemon.
+                    if let Some(ctx) = &self.context {
+                        let mut path = ctx.data_dir.clone();
+                        path.push(ctx.bitcoin_config.network.to_string());
+                        path.push("lianad_rpc");
+                        self.daemon_interface.rpc_path.value = path.to_string_lossy().to_string();
+                    }
+                }
+                self.daemon_interface.rpc_path.valid = true;
+            }
+            Message::Final(message::Final::DaemonRpcPathEdited(value)) => {
+                self.daemon_interface.rpc_path.valid = true;
+                self.daemon_interface.rpc_path.value = value;
+            }
+            Message::Final(message::Final::ChecklistToggled(item, checked)) => {
+                self.checklist.toggle(item, checked);
+                if let Some(path) = &self.config_path {
+                    write_checklist(path, &self.checklist.0);
+                }
+            }
+            Message::Final(message::Final::RemoteExportToggled(remote_export)) => {
+                self.remote_export.enabled = remote_export;
+            }
+            Message::Final(message::Final::RemoteHostEdited(value)) => {
+                self.remote_export.host.valid = true;
+                self.remote_export.host.value = value;
+                self.remote_export.result = None;
+            }
+            Message::Final(message::Final::RemoteDirectoryEdited(value)) => {
+                self.remote_export.directory.valid = true;
+                self.remote_export.directory.value = value;
+                self.remote_export.result = None;
+            }
+            Message::Final(message::Final::RemoteDescriptorImportToggled(import)) => {
+                self.remote_export.descriptor_import = import;
+            }
+            Message::Final(message::Final::CheckRemote) => {
+                if let Some(target) = self.remote_export.target() {
+                    self.remote_export.processing = true;
+                    self.remote_export.result = None;
+                    return Command::perform(check_remote(target), |res| {
+                        Message::Final(message::Final::RemoteDone(
+                            res.map(|_| "The server can be connected to."),
+                        ))
+                    });
+                }
+            }
+            Message::Final(message::Final::ExportToRemote) => {
+                if let Some(target) = self.remote_export.target() {
+                    match self.remote_files() {
+                        Ok(files) => {
+                            self.remote_export.processing = true;
+                            self.remote_export.result = None;
+                            return Command::perform(export_to_remote(target, files), |res| {
+                                Message::Final(message::Final::RemoteDone(
+                                    res.map(|_| "The configuration was exported to the server."),
+                                ))
+                            });
+                        }
+                        Err(e) => self.remote_export.result = Some(Err(e)),
+                    }
+                }
+            }
+            Message::Final(message::Final::RemoteDone(res)) => {
+                self.remote_export.processing = false;
+                self.remote_export.result = Some(res);
+            }
+            Message::Final(message::Final::AnotherWalletNameEdited(name)) => {
+                self.another_wallet.error = self
+                    .context
+                    .as_ref()
+                    .and_then(|ctx| ctx.check_wallet_name(&name).err());
+                // An empty name is not an error until the wallet is set up.
+                self.another_wallet.name.valid =
+                    name.trim().is_empty() || self.another_wallet.error.is_none();
+                self.another_wallet.name.value = name;
             }
             _ => {}
         };
         Command::none()
     }
 
+    fn apply(&mut self, ctx: &mut Context) -> bool {
+        let import = &mut self.descriptor_import;
+        let receive_range_end = parse_range_end(&import.receive_range_end.value);
+        let change_range_end = parse_range_end(&import.change_range_end.value);
+        import.receive_range_end.valid = receive_range_end.is_some();
+        import.change_range_end.valid = change_range_end.is_some();
+        if self.daemon_interface.external {
+            match parse_daemon_rpc_path(&self.daemon_interface.rpc_path.value) {
+                Some(path) => {
+                    self.daemon_interface.rpc_path.valid = true;
+                    ctx.daemon_rpc_path = Some(path);
+                }
+                None => {
+                    self.daemon_interface.rpc_path.valid = false;
+                    return false;
+                }
+            }
+        } else {
+            ctx.daemon_rpc_path = None;
+        }
+        ctx.rescan = match (self.rescan.enabled, &self.rescan.start) {
+            (false, _) => None,
+            (true, Some(Ok(start))) => Some(Rescan {
+                timestamp: start.timestamp,
+                height: start.height,
+                wait: self.rescan.wait,
+            }),
+            // The first block rescanned must be known before the install.
+            (true, _) => return false,
+        };
+        match (
+            receive_range_end,
+            change_range_end,
+            ctx.bitcoind_config.as_mut(),
+        ) {
+            (Some(receive_range_end), Some(change_range_end), Some(bitcoind_config)) => {
+                bitcoind_config.descriptor_import = DescriptorImportConfig {
+                    receive_range_end,
+                    import_change: self.descriptor_import.import_change,
+                    change_range_end,
+                };
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        if self.rescan.progress.is_some() {
+            time::every(std::time::Duration::from_secs(RESCAN_POLL_INTERVAL_SECS))
+                .map(|_| Message::Final(message::Final::PollRescan))
+        } else {
+            Subscription::none()
+        }
+    }
+
+    /// The rescan may have completed while the polling was paused.
+    fn resume(&mut self) -> Command<Message> {
+        if self.rescan.progress.is_some() {
+            self.update(Message::Final(message::Final::PollRescan))
+        } else {
+            Command::none()
+        }
+    }
+
     fn view(&self, progress: (usize, usize)) -> Element<Message> {
+        let hot_signer = self
+            .hot_signer_placement
+            .as_ref()
+            .map(|placement| (self.hot_signer_fingerprint, placement));
+        if let Some(ctx) = &self.summary {
+            let aliases = ctx
+                .keys
+                .iter()
+                .filter(|k| !k.name.is_empty())
+                .map(|k| (k.master_fingerprint, k.name.clone()))
+                .collect();
+            return view::install_summary(
+                progress,
+                ctx,
+                descriptor_policy_summary(ctx.descriptor.as_ref().unwrap(), &aliases),
+                hot_signer,
+            );
+        }
         let ctx = self.context.as_ref().unwrap();
-        let desc = ctx.descriptor.as_ref().unwrap().to_string();
+        let status = if let Some(path) = &self.config_path {
+            let mut sections = Vec::new();
+            sections.extend(self.rescan.progress_view());
+            sections.extend(self.tor_client_auth.as_deref().map(view::tor_client_auth));
+            sections.push(self.checklist.view(path));
+            sections.push(self.remote_export.view());
+            // The rescan this install waits for would not be polled anymore.
+            sections.push(self.another_wallet.view(ctx, self.rescan.is_waited()));
+            view::installed(path, sections, self.rescan.is_waited())
+        } else {
+            view::install_button(self.generating, self.cleanup)
+        };
         view::install(
             progress,
             ctx,
-            desc,
-            self.generating,
-            self.config_path.as_ref(),
+            hot_signer,
+            self.first_addresses.as_ref(),
+            vec![
+                self.descriptor_check.view(),
+                self.descriptor_import.view(),
+                self.daemon_interface.view(),
+                self.rescan.view(),
+                self.address_export.view(),
+            ],
             self.warning.as_ref(),
-            if self.hot_signer_is_not_used {
-                None
-            } else {
-                Some(self.hot_signer_fingerprint)
-            },
+            status,
         )
     }
 }
@@ -311,3 +1726,378 @@ impl From<Final> for Box<dyn Step> {
         Box::new(s)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn install_summary_review() {
+        let mut ctx = Context::new(Network::Testnet, PathBuf::from("/tmp/liana-gui-test"));
+        ctx.descriptor = Some(LianaDescriptor::from_str("wsh(or_d(pk([9e1c1983/48'/1'/0'/2']tpubDEWCLCRcyEWgDA6kgcFvq3xbn5EjMwr3ZF3LQyJEE5pvbsLNKnLBNSsGreZm8NRkGoqA3mTjBR7RxuJ6vKQzhCTCq1GXZjfPvAXXzrmuoWj/<0;1>/*),and_v(v:pkh([f5acc2fd/48'/1'/0'/2']tpubDFAqEGNyad35aBCKUAXbQGDjdVhNueno5ZZVEn3sQbW5ci457gLR7HyTmHBg93oourBssgUxuWz1jX5uhc1qaqFo9VsybY1J5FuedLfm4dK/<0;1>/*),older(20))))").unwrap());
+        ctx.bitcoind_config = Some(BitcoindConfig {
+            cookie_path: PathBuf::from("/home/alice/.bitcoin/testnet3/.cookie"),
+            addr: "127.0.0.1:18332".parse().unwrap(),
+            rpc_auth: None,
+            descriptor_import: DescriptorImportConfig::default(),
+        });
+        let mut step = Final::new(Arc::new(Mutex::new(
+            Signer::generate(Network::Testnet).unwrap(),
+        )));
+        step.load_context(&ctx);
+
+        // The summary carries the options of the final step.
+        let _ = step.update(Message::Final(message::Final::ReceiveRangeEdited(
+            "2000".to_string(),
+        )));
+        let _ = step.update(Message::Final(message::Final::Review(true)));
+        let summary = step.summary.as_ref().unwrap();
+        assert_eq!(
+            summary
+                .bitcoind_config
+                .as_ref()
+                .unwrap()
+                .descriptor_import
+                .receive_range_end,
+            2000
+        );
+
+        // It can be left to edit the options, invalid ones prevent it from being displayed.
+        let _ = step.update(Message::Final(message::Final::Review(false)));
+        assert!(step.summary.is_none());
+        let _ = step.update(Message::Final(message::Final::ChangeRangeEdited(
+            "0".to_string(),
+        )));
+        let _ = step.update(Message::Final(message::Final::Review(true)));
+        assert!(step.summary.is_none());
+
+        // Coming back to the step from an edited one drops the summary.
+        let _ = step.update(Message::Final(message::Final::ChangeRangeEdited(
+            "1000".to_string(),
+        )));
+        let _ = step.update(Message::Final(message::Final::Review(true)));
+        assert!(step.summary.is_some());
+        step.load_context(&ctx);
+        assert!(step.summary.is_none());
+    }
+
+    #[test]
+    fn distinct_import_ranges() {
+        let mut ctx = Context::new(Network::Testnet, PathBuf::from("/tmp/liana-gui-test"));
+        ctx.descriptor = Some(LianaDescriptor::from_str("wsh(or_d(pk([9e1c1983/48'/1'/0'/2']tpubDEWCLCRcyEWgDA6kgcFvq3xbn5EjMwr3ZF3LQyJEE5pvbsLNKnLBNSsGreZm8NRkGoqA3mTjBR7RxuJ6vKQzhCTCq1GXZjfPvAXXzrmuoWj/<0;1>/*),and_v(v:pkh([f5acc2fd/48'/1'/0'/2']tpubDFAqEGNyad35aBCKUAXbQGDjdVhNueno5ZZVEn3sQbW5ci457gLR7HyTmHBg93oourBssgUxuWz1jX5uhc1qaqFo9VsybY1J5FuedLfm4dK/<0;1>/*),older(20))))").unwrap());
+        ctx.bitcoind_config = Some(BitcoindConfig {
+            cookie_path: PathBuf::from("/home/alice/.bitcoin/testnet3/.cookie"),
+            addr: "127.0.0.1:18332".parse().unwrap(),
+            rpc_auth: None,
+            descriptor_import: DescriptorImportConfig::default(),
+        });
+        let mut step = Final::new(Arc::new(Mutex::new(
+            Signer::generate(Network::Testnet).unwrap(),
+        )));
+        step.load_context(&ctx);
+
+        // Both ranges default to the same value.
+        assert_eq!(
+            step.descriptor_import.receive_range_end.value,
+            step.descriptor_import.change_range_end.value
+        );
+
+        // The change branch gets its own range.
+        let _ = step.update(Message::Final(message::Final::ReceiveRangeEdited(
+            "2000".to_string(),
+        )));
+        let _ = step.update(Message::Final(message::Final::ChangeRangeEdited(
+            "50000".to_string(),
+        )));
+        let _ = step.update(Message::Final(message::Final::Review(true)));
+        let import = &step
+            .summary
+            .as_ref()
+            .unwrap()
+            .bitcoind_config
+            .as_ref()
+            .unwrap()
+            .descriptor_import;
+        assert_eq!(import.receive_range_end, 2000);
+        assert_eq!(import.change_range_end, 50000);
+        assert!(import.import_change);
+    }
+
+    #[test]
+    fn import_range_bounds() {
+        assert_eq!(parse_range_end("1000"), Some(1000));
+        assert_eq!(parse_range_end("999999"), Some(999_999));
+        // The addresses past a small range would go undetected.
+        assert_eq!(parse_range_end("10"), None);
+        assert_eq!(parse_range_end("999"), None);
+        assert_eq!(parse_range_end("1000000"), None);
+    }
+
+    #[test]
+    fn import_range_warnings() {
+        assert_eq!(import_range_warning("1000", "1000"), None);
+        assert_eq!(import_range_warning("100000", "0"), None);
+        // Invalid ranges are already reported by the form.
+        assert_eq!(import_range_warning("1000000", "abc"), None);
+        assert_eq!(
+            import_range_warning("1000", "200000"),
+            Some(
+                "Importing the change addresses up to index 200000 will make the rescan of the chain slow."
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            import_range_warning("500000", "200000"),
+            Some("Importing the receive addresses up to index 500000 and the change addresses up to index 200000 will make the rescan of the chain slow.".to_string())
+        );
+    }
+
+    #[test]
+    fn support_summary_is_redacted() {
+        let mut step = DefineBitcoind::new();
+        step.address.value = "192.168.1.12:8332".to_string();
+        step.cookie_path.value = "/home/alice/.bitcoin/.cookie".to_string();
+        step.is_running = Some(Err(Error::BitcoindCookieUnreadable(
+            "No such file or directory".to_string(),
+        )));
+
+        let summary = step.support_summary();
+        assert!(summary.contains("Network: bitcoin"));
+        assert!(summary.contains("[REDACTED private network host]:8332"));
+        assert!(summary.contains("Last error: cookie file unreadable"));
+        assert!(!summary.contains("192.168.1.12"));
+        assert!(!summary.contains("alice"));
+
+        // Neither are the user and password.
+        let _ = step.update(Message::DefineBitcoind(
+            message::DefineBitcoind::RpcAuthToggled(true),
+        ));
+        let _ = step.update(Message::DefineBitcoind(
+            message::DefineBitcoind::RpcUserEdited("liana".to_string()),
+        ));
+        let _ = step.update(Message::DefineBitcoind(
+            message::DefineBitcoind::RpcPasswordEdited("hunter2".to_string()),
+        ));
+        let summary = step.support_summary();
+        assert!(summary.contains("Authentication: user and password"));
+        assert!(!summary.contains("liana") && !summary.contains("hunter2"));
+    }
+
+    #[test]
+    fn bitcoind_chain_network() {
+        assert_eq!(chain_network("main"), Some(Network::Bitcoin));
+        assert_eq!(chain_network("test"), Some(Network::Testnet));
+        assert_eq!(chain_network("signet"), Some(Network::Signet));
+        assert_eq!(chain_network("regtest"), Some(Network::Regtest));
+        assert_eq!(chain_network("testnet4"), None);
+
+        let mut step = DefineBitcoind::new();
+        step.is_running = Some(Err(Error::BitcoindNetworkMismatch(
+            "the wallet is on signet but bitcoind is running on testnet.".to_string(),
+        )));
+        assert!(step
+            .support_summary()
+            .contains("Last error: network mismatch"));
+        step.is_running = Some(Err(Error::BitcoindUnauthorized));
+        assert!(step
+            .support_summary()
+            .contains("Last error: authentication refused"));
+        // An error merely mentioning 401 is not taken for a refused authentication.
+        step.is_running = Some(Err(Error::Bitcoind(
+            "connection reset after 401 ms".to_string(),
+        )));
+        assert!(step
+            .support_summary()
+            .contains("Last error: connection or RPC error"));
+    }
+
+    #[test]
+    fn bitcoind_rpc_auth() {
+        let mut ctx = Context::new(Network::Testnet, PathBuf::from("/tmp/liana-gui-test"));
+        let mut step = DefineBitcoind::new();
+        step.load_context(&ctx);
+        let default_cookie_path = step.cookie_path.value.clone();
+        let _ = step.update(Message::DefineBitcoind(
+            message::DefineBitcoind::RpcAuthToggled(true),
+        ));
+
+        // Both the user and the password are needed.
+        let _ = step.update(Message::DefineBitcoind(
+            message::DefineBitcoind::RpcUserEdited("liana".to_string()),
+        ));
+        assert!(!step.apply(&mut ctx));
+        assert!(!step.rpc_password.valid);
+
+        // The cookie path is not checked in this mode.
+        let _ = step.update(Message::DefineBitcoind(
+            message::DefineBitcoind::RpcPasswordEdited("hunter2".to_string()),
+        ));
+        let _ = step.update(Message::DefineBitcoind(
+            message::DefineBitcoind::CookiePathEdited(String::new()),
+        ));
+        assert!(step.apply(&mut ctx));
+        assert_eq!(
+            ctx.bitcoind_config.as_ref().unwrap().rpc_auth,
+            Some(BitcoindRpcAuth {
+                user: "liana".to_string(),
+                password: "hunter2".to_string(),
+            })
+        );
+
+        // Back to the cookie file, the credentials are not kept in the configuration.
+        let _ = step.update(Message::DefineBitcoind(
+            message::DefineBitcoind::RpcAuthToggled(false),
+        ));
+        let _ = step.update(Message::DefineBitcoind(
+            message::DefineBitcoind::CookiePathEdited(default_cookie_path.clone()),
+        ));
+        assert!(step.apply(&mut ctx));
+        let config = ctx.bitcoind_config.as_ref().unwrap();
+        assert_eq!(config.rpc_auth, None);
+        assert_eq!(config.cookie_path, PathBuf::from(default_cookie_path));
+    }
+
+    #[test]
+    fn daemon_rpc_path_validation() {
+        let dir = std::env::temp_dir();
+        assert_eq!(
+            parse_daemon_rpc_path(&dir.join("lianad_rpc").to_string_lossy()),
+            Some(dir.join("lianad_rpc"))
+        );
+        assert!(parse_daemon_rpc_path(&dir.to_string_lossy()).is_none());
+        assert!(parse_daemon_rpc_path("lianad_rpc").is_none());
+        assert!(parse_daemon_rpc_path(
+            &dir.join("missing-liana-dir")
+                .join("lianad_rpc")
+                .to_string_lossy()
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn remote_rpc_refusal_detection() {
+        let refused = || {
+            jsonrpc::Error::Transport(Box::new(jsonrpc::simple_http::Error::SocketError(
+                std::io::Error::from(std::io::ErrorKind::ConnectionRefused),
+            )))
+        };
+        assert!(matches!(
+            connection_error("192.168.1.12:8332", refused()),
+            Error::BitcoindRemoteRpcRefused(addr) if addr == "192.168.1.12:8332"
+        ));
+        // On the same machine, bitcoind is simply not running.
+        assert!(matches!(
+            connection_error("127.0.0.1:8332", refused()),
+            Error::Bitcoind(_)
+        ));
+        let timeout =
+            jsonrpc::Error::Transport(Box::new(jsonrpc::simple_http::Error::SocketError(
+                std::io::Error::from(std::io::ErrorKind::TimedOut),
+            )));
+        assert!(matches!(
+            connection_error("192.168.1.12:8332", timeout),
+            Error::Bitcoind(_)
+        ));
+        let forbidden =
+            || jsonrpc::Error::Transport(Box::new(jsonrpc::simple_http::Error::HttpErrorCode(403)));
+        assert!(matches!(
+            connection_error("192.168.1.12:8332", forbidden()),
+            Error::BitcoindRemoteRpcRefused(_)
+        ));
+        assert!(matches!(
+            connection_error("127.0.0.1:8332", forbidden()),
+            Error::Bitcoind(_)
+        ));
+        // An error merely mentioning 403 is not taken for a forbidden access.
+        let other = jsonrpc::Error::Transport(Box::new(jsonrpc::simple_http::Error::SocketError(
+            std::io::Error::new(std::io::ErrorKind::Other, "connection reset after 403 ms"),
+        )));
+        assert!(matches!(
+            connection_error("192.168.1.12:8332", other),
+            Error::Bitcoind(_)
+        ));
+    }
+
+    #[test]
+    fn work_queue_saturation_detection() {
+        let saturated =
+            || jsonrpc::Error::Transport(Box::new(jsonrpc::simple_http::Error::HttpErrorCode(503)));
+        assert!(matches!(
+            Error::from(saturated()),
+            Error::BitcoindWorkQueueExceeded
+        ));
+        // Whether bitcoind runs on the same machine or not.
+        assert!(matches!(
+            connection_error("127.0.0.1:8332", saturated()),
+            Error::BitcoindWorkQueueExceeded
+        ));
+        assert!(matches!(
+            connection_error("192.168.1.12:8332", saturated()),
+            Error::BitcoindWorkQueueExceeded
+        ));
+        let unauthorized =
+            jsonrpc::Error::Transport(Box::new(jsonrpc::simple_http::Error::HttpErrorCode(401)));
+        assert!(matches!(
+            Error::from(unauthorized),
+            Error::BitcoindUnauthorized
+        ));
+        let internal =
+            jsonrpc::Error::Transport(Box::new(jsonrpc::simple_http::Error::HttpErrorCode(500)));
+        assert!(matches!(Error::from(internal), Error::Bitcoind(_)));
+        // Another error mentioning 503 is not taken for it.
+        let other = jsonrpc::Error::Transport(Box::new(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            "connection reset after 503 ms",
+        )));
+        assert!(matches!(Error::from(other), Error::Bitcoind(_)));
+    }
+
+    #[test]
+    fn rescan_date() {
+        let now = 1_700_000_000;
+        assert_eq!(parse_rescan_date("2022", "11", "1", now), Ok(1_667_260_800));
+        assert_eq!(
+            parse_rescan_date(" 2009", "01 ", "3", now),
+            Ok(1_230_940_800)
+        );
+        assert!(parse_rescan_date("2009", "1", "2", now).is_err());
+        assert!(parse_rescan_date("2023", "2", "29", now).is_err());
+        assert!(parse_rescan_date("2024", "1", "1", now).is_err());
+        assert!(parse_rescan_date("", "1", "1", now).is_err());
+    }
+
+    #[test]
+    fn rescan_first_block() {
+        // The median time of a block is never lower than the one of its parent.
+        let times = [100, 200, 200, 300, 400, 400, 400, 500];
+        let median_time = |height: u32| -> Result<u32, Error> { Ok(times[height as usize]) };
+        let tip = times.len() as u32 - 1;
+        assert_eq!(first_block_after(tip, 50, median_time).unwrap(), Some(0));
+        assert_eq!(first_block_after(tip, 200, median_time).unwrap(), Some(1));
+        assert_eq!(first_block_after(tip, 201, median_time).unwrap(), Some(3));
+        assert_eq!(first_block_after(tip, 400, median_time).unwrap(), Some(4));
+        assert_eq!(first_block_after(tip, 500, median_time).unwrap(), Some(7));
+        assert_eq!(first_block_after(tip, 501, median_time).unwrap(), None);
+    }
+
+    #[test]
+    fn hot_signer_placement() {
+        let descriptor = LianaDescriptor::from_str("wsh(or_d(pk([abcdef01]xpub6Eze7yAT3Y1wGrnzedCNVYDXUqa9NmHVWck5emBaTbXtURbe1NWZbK9bsz1TiVE7Cz341PMTfYgFw1KdLWdzcM1UMFTcdQfCYhhXZ2HJvTW/<0;1>/*),and_v(v:pkh([abcdef01]xpub688Hn4wScQAAiYJLPg9yH27hUpfZAUnmJejRQBCiwfP5PEDzjWMNW1wChcninxr5gyavFqbbDjdV1aK5USJz8NDVjUy7FRQaaqqXHh5SbXe/<0;1>/*),older(52560))))").unwrap();
+        let placement =
+            HotSignerPlacement::new(&descriptor, Fingerprint::from_str("abcdef01").unwrap())
+                .unwrap();
+        assert_eq!(
+            placement.description(),
+            "Primary key and recovery key after 52560 blocks"
+        );
+        let recovery = HotSignerPlacement {
+            primary: false,
+            recovery: vec![52560],
+        };
+        assert_eq!(recovery.description(), "Recovery key after 52560 blocks");
+        assert!(
+            HotSignerPlacement::new(&descriptor, Fingerprint::from_str("9e1c1983").unwrap())
+                .is_none()
+        );
+    }
+}
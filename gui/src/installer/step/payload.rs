@@ -0,0 +1,245 @@
+//! Parsing of the keys and descriptors shared by mobile companion apps and NFC or QR capable
+//! signers. Their payloads are recognized by their structure and converted to the plain key or
+//! descriptor expressions the installer expects.
+
+use std::str::FromStr;
+
+use liana::{
+    descriptors::LianaDescriptor,
+    miniscript::{bitcoin::util::base58, DescriptorPublicKey},
+};
+use serde_json::Value;
+
+/// The version bytes of the extended public keys encodings (SLIP-132), mapped to the ones of the
+/// `xpub` or `tpub` encoding of the same network.
+const XPUB_VERSIONS: [([u8; 4], [u8; 4]); 10] = [
+    // xpub, ypub, zpub, Ypub, Zpub
+    ([0x04, 0x88, 0xb2, 0x1e], [0x04, 0x88, 0xb2, 0x1e]),
+    ([0x04, 0x9d, 0x7c, 0xb2], [0x04, 0x88, 0xb2, 0x1e]),
+    ([0x04, 0xb2, 0x47, 0x46], [0x04, 0x88, 0xb2, 0x1e]),
+    ([0x02, 0x95, 0xb4, 0x3f], [0x04, 0x88, 0xb2, 0x1e]),
+    ([0x02, 0xaa, 0x7e, 0xd3], [0x04, 0x88, 0xb2, 0x1e]),
+    // tpub, upub, vpub, Upub, Vpub
+    ([0x04, 0x35, 0x87, 0xcf], [0x04, 0x35, 0x87, 0xcf]),
+    ([0x04, 0x4a, 0x52, 0x62], [0x04, 0x35, 0x87, 0xcf]),
+    ([0x04, 0x5f, 0x1c, 0xf6], [0x04, 0x35, 0x87, 0xcf]),
+    ([0x02, 0x42, 0x89, 0xef], [0x04, 0x35, 0x87, 0xcf]),
+    ([0x02, 0x57, 0x54, 0x83], [0x04, 0x35, 0x87, 0xcf]),
+];
+
+/// Re-encode an extended public key using the `xpub` or `tpub` version bytes.
+fn normalize_xpub(xpub: &str) -> Option<String> {
+    let mut data = base58::from_check(xpub.trim()).ok()?;
+    if data.len() != 78 {
+        return None;
+    }
+    let (_, version) = XPUB_VERSIONS.iter().find(|(v, _)| data[..4] == v[..])?;
+    data[..4].copy_from_slice(version);
+    Some(base58::check_encode_slice(&data))
+}
+
+fn key_with_origin(fingerprint: &str, derivation_path: &str, xpub: &str) -> Option<String> {
+    let fingerprint = fingerprint.trim().to_lowercase();
+    let derivation_path = derivation_path
+        .trim()
+        .trim_start_matches('m')
+        .trim_start_matches('/')
+        .replace('h', "'");
+    let key = if derivation_path.is_empty() {
+        format!("[{}]{}", fingerprint, normalize_xpub(xpub)?)
+    } else {
+        format!(
+            "[{}/{}]{}",
+            fingerprint,
+            derivation_path,
+            normalize_xpub(xpub)?
+        )
+    };
+    match DescriptorPublicKey::from_str(&key) {
+        Ok(DescriptorPublicKey::XPub(_)) => Some(key),
+        _ => None,
+    }
+}
+
+fn json_str<'a>(value: &'a Value, field: &str) -> Option<&'a str> {
+    value.get(field).and_then(Value::as_str)
+}
+
+/// The key shared by a mobile companion or an air-gapped signer, as a key expression with its
+/// origin. Returns `None` if the payload is not recognized.
+///
+/// The following payloads are supported:
+/// - a key expression, possibly encoded with a SLIP-132 version (`Zpub`, `Vpub`, ..).
+/// - the JSON exported by Keystone, Cobo or BlueWallet: `{"ExtPubKey", "MasterFingerprint",
+///   "AccountKeyPath"}`.
+/// - the JSON exported by Coldcard, either the generic export with its `bip48_2` account or the
+///   multisig one with its `p2wsh` key.
+pub fn parse_key_payload(payload: &str) -> Option<String> {
+    let payload = payload.trim();
+    if let Some(json) = serde_json::from_str::<Value>(payload)
+        .ok()
+        .filter(Value::is_object)
+    {
+        if let (Some(xpub), Some(fingerprint), Some(path)) = (
+            json_str(&json, "ExtPubKey"),
+            json_str(&json, "MasterFingerprint"),
+            json_str(&json, "AccountKeyPath"),
+        ) {
+            return key_with_origin(fingerprint, path, xpub);
+        }
+        let fingerprint = json_str(&json, "xfp")?;
+        if let Some(account) = json.get("bip48_2") {
+            return key_with_origin(
+                json_str(account, "xfp").unwrap_or(fingerprint),
+                json_str(account, "deriv")?,
+                json_str(account, "xpub")?,
+            );
+        }
+        return key_with_origin(
+            fingerprint,
+            json_str(&json, "p2wsh_deriv")?,
+            json_str(&json, "p2wsh")?,
+        );
+    }
+
+    // A key expression: `[fingerprint/derivation/path]xpub`, possibly with a SLIP-132 encoding.
+    let (origin, xpub) = match payload.split_once(']') {
+        Some((origin, xpub)) => (Some(origin.strip_prefix('[')?), xpub),
+        None => (None, payload),
+    };
+    // Keep the derivation steps following the extended key, if any.
+    let (xpub, derivation) = match xpub.split_once('/') {
+        Some((xpub, derivation)) => (xpub, format!("/{}", derivation)),
+        None => (xpub, String::new()),
+    };
+    let key = match origin {
+        Some(origin) => format!("[{}]{}{}", origin, normalize_xpub(xpub)?, derivation),
+        None => format!("{}{}", normalize_xpub(xpub)?, derivation),
+    };
+    match DescriptorPublicKey::from_str(&key) {
+        Ok(DescriptorPublicKey::XPub(_)) => Some(key),
+        _ => None,
+    }
+}
+
+/// The descriptor shared by a mobile companion or an air-gapped coordinator. Returns `None` if
+/// the payload is not recognized or if it does not contain a valid Liana descriptor.
+///
+/// The following payloads are supported:
+/// - a descriptor.
+/// - the Specter wallet import QR: `addwallet <name>&<descriptor>`.
+/// - a BSMS (BIP129) descriptor record, whose second line is the descriptor.
+/// - a JSON object with a `descriptor` field, as exported by Sparrow or the Liana wallet.
+/// - a JSON wallet policy (BIP388): `{"name", "descriptor_template", "keys_info"}`.
+pub fn parse_descriptor_payload(payload: &str) -> Option<String> {
+    let payload = payload.trim();
+    let descriptor = if let Some(json) = serde_json::from_str::<Value>(payload)
+        .ok()
+        .filter(Value::is_object)
+    {
+        if let Some(descriptor) = json_str(&json, "descriptor") {
+            descriptor.to_string()
+        } else {
+            let mut descriptor = json_str(&json, "descriptor_template")?.to_string();
+            let keys = json.get("keys_info").and_then(Value::as_array)?;
+            // Replace the last placeholders first, for `@1` not to match the beginning of `@10`.
+            for (i, key) in keys.iter().enumerate().rev() {
+                descriptor = descriptor
+                    .replace(&format!("@{}/**", i), &format!("{}/<0;1>/*", key.as_str()?));
+            }
+            descriptor
+        }
+    } else if payload.starts_with("BSMS") {
+        payload.lines().nth(1)?.trim().to_string()
+    } else if let Some((_, descriptor)) = payload.split_once('&') {
+        descriptor.trim().to_string()
+    } else {
+        payload.to_string()
+    };
+    LianaDescriptor::from_str(&descriptor)
+        .ok()
+        .map(|_| descriptor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &str = "[f5acc2fd/48'/1'/0'/2']tpubDFAqEGNyad35aBCKUAXbQGDjdVhNueno5ZZVEn3sQbW5ci457gLR7HyTmHBg93oourBssgUxuWz1jX5uhc1qaqFo9VsybY1J5FuedLfm4dK";
+    const DESCRIPTOR: &str = "wsh(or_d(pk([9e1c1983/48'/1'/0'/2']tpubDEWCLCRcyEWgDA6kgcFvq3xbn5EjMwr3ZF3LQyJEE5pvbsLNKnLBNSsGreZm8NRkGoqA3mTjBR7RxuJ6vKQzhCTCq1GXZjfPvAXXzrmuoWj/<0;1>/*),and_v(v:pkh([f5acc2fd/48'/1'/0'/2']tpubDFAqEGNyad35aBCKUAXbQGDjdVhNueno5ZZVEn3sQbW5ci457gLR7HyTmHBg93oourBssgUxuWz1jX5uhc1qaqFo9VsybY1J5FuedLfm4dK/<0;1>/*),older(20))))";
+
+    #[test]
+    fn key_payloads() {
+        assert_eq!(parse_key_payload(KEY).unwrap(), KEY);
+        // The same key with the SLIP-132 encoding used for testnet multisig.
+        let data = base58::from_check(KEY.split_once(']').unwrap().1).unwrap();
+        let vpub =
+            base58::check_encode_slice(&[&[0x02, 0x57, 0x54, 0x83][..], &data[4..]].concat());
+        assert_eq!(
+            parse_key_payload(&format!("[f5acc2fd/48'/1'/0'/2']{}", vpub)).unwrap(),
+            KEY
+        );
+
+        let xpub = KEY.split_once(']').unwrap().1;
+        let keystone = serde_json::json!({
+            "ExtPubKey": vpub,
+            "MasterFingerprint": "F5ACC2FD",
+            "AccountKeyPath": "m/48'/1'/0'/2'",
+        });
+        assert_eq!(parse_key_payload(&keystone.to_string()).unwrap(), KEY);
+
+        let coldcard = serde_json::json!({
+            "xfp": "F5ACC2FD",
+            "bip48_2": { "xpub": xpub, "deriv": "m/48h/1h/0h/2h" },
+        });
+        assert_eq!(parse_key_payload(&coldcard.to_string()).unwrap(), KEY);
+
+        let coldcard = serde_json::json!({
+            "xfp": "F5ACC2FD",
+            "p2wsh": vpub,
+            "p2wsh_deriv": "m/48'/1'/0'/2'",
+        });
+        assert_eq!(parse_key_payload(&coldcard.to_string()).unwrap(), KEY);
+
+        assert_eq!(
+            parse_key_payload(&format!("{}/<0;1>/*", KEY)).unwrap(),
+            format!("{}/<0;1>/*", KEY)
+        );
+        assert!(parse_key_payload("ur:crypto-hdkey/oeadykadykaoykaocy").is_none());
+        assert!(parse_key_payload("{\"xfp\": \"F5ACC2FD\"}").is_none());
+        assert!(parse_key_payload(&KEY[..KEY.len() - 1]).is_none());
+    }
+
+    #[test]
+    fn descriptor_payloads() {
+        assert_eq!(parse_descriptor_payload(DESCRIPTOR).unwrap(), DESCRIPTOR);
+        assert_eq!(
+            parse_descriptor_payload(&format!("addwallet Liana&{}", DESCRIPTOR)).unwrap(),
+            DESCRIPTOR
+        );
+        assert_eq!(
+            parse_descriptor_payload(&format!("BSMS 1.0\n{}\n/0/*,/1/*\ntb1qexample", DESCRIPTOR))
+                .unwrap(),
+            DESCRIPTOR
+        );
+        let sparrow = serde_json::json!({ "label": "Liana", "descriptor": DESCRIPTOR });
+        assert_eq!(
+            parse_descriptor_payload(&sparrow.to_string()).unwrap(),
+            DESCRIPTOR
+        );
+
+        // The wallet policy exported for the registration on the cosigners devices.
+        let policy = super::super::descriptor::registration_artifact(
+            &LianaDescriptor::from_str(DESCRIPTOR).unwrap(),
+            super::super::RegistrationFormat::WalletPolicy,
+        );
+        assert_eq!(
+            LianaDescriptor::from_str(&parse_descriptor_payload(&policy).unwrap()).unwrap(),
+            LianaDescriptor::from_str(DESCRIPTOR).unwrap()
+        );
+
+        assert!(parse_descriptor_payload("ur:crypto-output/taadmetaaddlo").is_none());
+        assert!(parse_descriptor_payload("addwallet Liana&wsh(pk(").is_none());
+        assert!(parse_descriptor_payload("{\"label\": \"Liana\"}").is_none());
+    }
+}
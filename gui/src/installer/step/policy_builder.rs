@@ -0,0 +1,300 @@
+//! The structured form of the policy of the descriptor: its spending paths with their keys,
+//! thresholds and timelocks. The descriptor is only generated from it by
+//! [`PolicyBuilder::to_descriptor`], and read back into it by [`PolicyBuilder::from_descriptor`],
+//! so both stay in sync while the policy is edited.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use async_hwi::DeviceKind;
+use liana::{
+    descriptors::{LianaDescriptor, LianaPolicy, PathInfo},
+    miniscript::{
+        bitcoin::util::bip32::{DerivationPath, ExtendedPubKey},
+        descriptor::{
+            DerivPaths, DescriptorMultiXKey, DescriptorPublicKey, DescriptorXKey, Wildcard,
+        },
+    },
+};
+
+use super::{aliases::KeyAliases, second_opinion::DefinedPath};
+
+/// The script the policy is compiled to. Liana descriptors are all P2WSH for now.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptType {
+    Wsh,
+}
+
+/// A key of a spending path: the extended key at its account, the receive and change branches
+/// being derived from it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuilderKey {
+    pub name: String,
+    pub xpub: DescriptorXKey<ExtendedPubKey>,
+    /// The signing device the key was imported from, if known: the descriptor must then be
+    /// registered on it.
+    pub device_kind: Option<DeviceKind>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuilderPath {
+    pub threshold: usize,
+    pub keys: Vec<BuilderKey>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyBuilder {
+    pub script_type: ScriptType,
+    pub primary: BuilderPath,
+    /// The recovery paths by timelock, in blocks.
+    pub recovery: BTreeMap<u16, BuilderPath>,
+}
+
+/// The multipath key with the receive and change branches for the extended key entered by the
+/// user.
+pub fn multipath_key(xpub: &DescriptorXKey<ExtendedPubKey>) -> DescriptorPublicKey {
+    DescriptorPublicKey::MultiXPub(DescriptorMultiXKey {
+        origin: xpub.origin.clone(),
+        xkey: xpub.xkey,
+        derivation_paths: DerivPaths::new(vec![
+            DerivationPath::from_str("m/0").unwrap(),
+            DerivationPath::from_str("m/1").unwrap(),
+        ])
+        .unwrap(),
+        wildcard: Wildcard::Unhardened,
+    })
+}
+
+pub fn path_info(keys: Vec<DescriptorPublicKey>, threshold: usize) -> PathInfo {
+    if keys.len() == 1 {
+        PathInfo::Single(keys[0].clone())
+    } else {
+        PathInfo::Multi(threshold, keys)
+    }
+}
+
+/// The extended key of the multipath key of the receive and change branches, as generated by
+/// [`multipath_key`].
+fn account_key(key: &DescriptorPublicKey) -> Result<DescriptorXKey<ExtendedPubKey>, String> {
+    let branches = [
+        DerivationPath::from_str("m/0").unwrap(),
+        DerivationPath::from_str("m/1").unwrap(),
+    ];
+    match key {
+        DescriptorPublicKey::MultiXPub(xpub)
+            if xpub.derivation_paths.paths().as_slice() == branches
+                && xpub.wildcard == Wildcard::Unhardened =>
+        {
+            Ok(DescriptorXKey {
+                origin: xpub.origin.clone(),
+                xkey: xpub.xkey,
+                derivation_path: DerivationPath::master(),
+                wildcard: Wildcard::None,
+            })
+        }
+        _ => Err(format!(
+            "The key {} is not an extended key with the receive and change branches <0;1>",
+            key
+        )),
+    }
+}
+
+impl BuilderPath {
+    /// The path as defined, for the descriptor to be checked against.
+    pub fn defined_path(&self) -> DefinedPath {
+        DefinedPath {
+            threshold: self.threshold,
+            keys: self.keys.iter().map(|key| key.xpub.clone()).collect(),
+        }
+    }
+
+    fn path_info(&self) -> Result<PathInfo, String> {
+        if self.keys.is_empty() {
+            return Err("A spending path has no key".to_string());
+        }
+        Ok(path_info(
+            self.keys
+                .iter()
+                .map(|key| multipath_key(&key.xpub))
+                .collect(),
+            self.threshold,
+        ))
+    }
+
+    fn from_path_info(path: &PathInfo, names: &KeyAliases) -> Result<Self, String> {
+        let (threshold, keys) = match path {
+            PathInfo::Single(key) => (1, vec![key]),
+            PathInfo::Multi(threshold, keys) => (*threshold, keys.iter().collect()),
+        };
+        Ok(Self {
+            threshold,
+            keys: keys
+                .into_iter()
+                .map(|key| {
+                    Ok(BuilderKey {
+                        name: names
+                            .get(&key.master_fingerprint())
+                            .cloned()
+                            .unwrap_or_default(),
+                        xpub: account_key(key)?,
+                        device_kind: None,
+                    })
+                })
+                .collect::<Result<_, String>>()?,
+        })
+    }
+}
+
+impl PolicyBuilder {
+    /// Generate the descriptor of the policy.
+    pub fn to_descriptor(&self) -> Result<LianaDescriptor, String> {
+        let recovery = self
+            .recovery
+            .iter()
+            .map(|(sequence, path)| Ok((*sequence, path.path_info()?)))
+            .collect::<Result<BTreeMap<_, _>, String>>()?;
+        let policy =
+            LianaPolicy::new(self.primary.path_info()?, recovery).map_err(|e| e.to_string())?;
+        match self.script_type {
+            ScriptType::Wsh => Ok(LianaDescriptor::new(policy)),
+        }
+    }
+
+    /// Read the policy of the descriptor, naming its keys by the fingerprint of their signer.
+    /// Only the descriptors whose keys are all extended keys with the receive and change
+    /// branches, as generated by the installer, can be read.
+    pub fn from_descriptor(
+        descriptor: &LianaDescriptor,
+        names: &KeyAliases,
+    ) -> Result<Self, String> {
+        let policy = descriptor.policy();
+        Ok(Self {
+            script_type: ScriptType::Wsh,
+            primary: BuilderPath::from_path_info(policy.primary_path(), names)?,
+            recovery: policy
+                .recovery_paths()
+                .iter()
+                .map(|(sequence, path)| Ok((*sequence, BuilderPath::from_path_info(path, names)?)))
+                .collect::<Result<_, String>>()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::installer::step::second_opinion::second_opinion;
+    use liana::miniscript::bitcoin::{secp256k1, util::bip32::ExtendedPrivKey, Network};
+
+    fn key(seed: u8, name: &str) -> BuilderKey {
+        let secp = secp256k1::Secp256k1::signing_only();
+        let master = ExtendedPrivKey::new_master(Network::Testnet, &[seed; 32]).unwrap();
+        let path = DerivationPath::from_str("m/48'/1'/0'/2'").unwrap();
+        BuilderKey {
+            name: name.to_string(),
+            xpub: DescriptorXKey {
+                origin: Some((master.fingerprint(&secp), path.clone())),
+                xkey: ExtendedPubKey::from_priv(&secp, &master.derive_priv(&secp, &path).unwrap()),
+                derivation_path: DerivationPath::master(),
+                wildcard: Wildcard::None,
+            },
+            device_kind: None,
+        }
+    }
+
+    fn keys(builder: &PolicyBuilder) -> impl Iterator<Item = &BuilderKey> {
+        builder
+            .primary
+            .keys
+            .iter()
+            .chain(builder.recovery.values().flat_map(|path| path.keys.iter()))
+    }
+
+    fn names(builder: &PolicyBuilder) -> KeyAliases {
+        keys(builder)
+            .map(|key| (key.xpub.origin.as_ref().unwrap().0, key.name.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn policy_builder_conversions() {
+        let builder = PolicyBuilder {
+            script_type: ScriptType::Wsh,
+            primary: BuilderPath {
+                threshold: 2,
+                keys: vec![key(1, "Alice"), key(2, "Bob"), key(3, "Carol")],
+            },
+            recovery: vec![
+                (
+                    26352,
+                    BuilderPath {
+                        threshold: 1,
+                        keys: vec![key(4, "Lawyer")],
+                    },
+                ),
+                (
+                    52560,
+                    BuilderPath {
+                        threshold: 2,
+                        keys: vec![key(5, "Dave"), key(6, "Eve")],
+                    },
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        };
+
+        // The structured form is read back from the descriptor generated from it, as is the
+        // descriptor from its string.
+        let descriptor = builder.to_descriptor().unwrap();
+        let parsed = LianaDescriptor::from_str(&descriptor.to_string()).unwrap();
+        assert_eq!(
+            PolicyBuilder::from_descriptor(&parsed, &names(&builder)).unwrap(),
+            builder
+        );
+        let read = PolicyBuilder::from_descriptor(&parsed, &KeyAliases::new()).unwrap();
+        assert!(keys(&read).all(|key| key.name.is_empty()));
+        assert_eq!(
+            read.to_descriptor().unwrap().to_string(),
+            descriptor.to_string()
+        );
+
+        // The generated descriptor has the paths as defined.
+        assert_eq!(
+            second_opinion(
+                &descriptor,
+                &builder.primary.defined_path(),
+                &builder
+                    .recovery
+                    .iter()
+                    .map(|(sequence, path)| (*sequence, path.defined_path()))
+                    .collect(),
+            ),
+            Ok(())
+        );
+
+        let desc = "wsh(or_d(pk([9e1c1983/48'/1'/0'/2']tpubDEWCLCRcyEWgDA6kgcFvq3xbn5EjMwr3ZF3LQyJEE5pvbsLNKnLBNSsGreZm8NRkGoqA3mTjBR7RxuJ6vKQzhCTCq1GXZjfPvAXXzrmuoWj/<0;1>/*),and_v(v:pkh([f5acc2fd/48'/1'/0'/2']tpubDFAqEGNyad35aBCKUAXbQGDjdVhNueno5ZZVEn3sQbW5ci457gLR7HyTmHBg93oourBssgUxuWz1jX5uhc1qaqFo9VsybY1J5FuedLfm4dK/<0;1>/*),older(20))))";
+        let descriptor = LianaDescriptor::from_str(desc).unwrap();
+        let read = PolicyBuilder::from_descriptor(&descriptor, &KeyAliases::new()).unwrap();
+        assert_eq!(read.primary.keys.len(), 1);
+        assert_eq!(read.recovery.keys().copied().collect::<Vec<_>>(), vec![20]);
+        assert_eq!(read.to_descriptor().unwrap(), descriptor);
+
+        // A key with other branches than the ones generated cannot be edited. A Liana descriptor
+        // cannot have such a key, so it is checked directly.
+        let key = "[9e1c1983/48'/1'/0'/2']tpubDEWCLCRcyEWgDA6kgcFvq3xbn5EjMwr3ZF3LQyJEE5pvbsLNKnLBNSsGreZm8NRkGoqA3mTjBR7RxuJ6vKQzhCTCq1GXZjfPvAXXzrmuoWj";
+        assert!(
+            account_key(&DescriptorPublicKey::from_str(&format!("{}/<0;1>/*", key)).unwrap())
+                .is_ok()
+        );
+        assert!(
+            account_key(&DescriptorPublicKey::from_str(&format!("{}/<2;3>/*", key)).unwrap())
+                .is_err()
+        );
+
+        // Neither can a path without key be generated.
+        let mut empty = builder;
+        empty.recovery.get_mut(&26352).unwrap().keys.clear();
+        assert!(empty.to_descriptor().is_err());
+    }
+}
@@ -0,0 +1,296 @@
+//! Export of the generated configuration to a remote server, for the daemon to run on a headless
+//! machine while the wallet is set up on a desktop. The transfer goes through the `sftp` program
+//! of the system: the authentication is left to the ssh configuration of the user (keys, agent)
+//! and the installer never asks for a password.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use liana::{config::DescriptorImportConfig, descriptors::LianaDescriptor};
+
+use crate::installer::Error;
+
+/// The name of the exported file with the request importing the descriptor in the watchonly
+/// wallet of the remote bitcoind.
+pub const DESCRIPTOR_IMPORT_FILE_NAME: &str = "importdescriptors.json";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteTarget {
+    /// The `user@host` to connect to.
+    destination: String,
+    port: Option<u16>,
+    directory: String,
+}
+
+/// Parse the `user@host[:port]` of the server and the directory the files are exported to.
+/// Anything which could be read by `sftp` as an option or break its batch commands is refused.
+pub fn parse_remote_target(host: &str, directory: &str) -> Result<RemoteTarget, String> {
+    let host = host.trim();
+    let (user, address) = host
+        .split_once('@')
+        .filter(|(user, address)| !user.is_empty() && !address.is_empty())
+        .ok_or_else(|| "The server must be given as user@host".to_string())?;
+    if host.starts_with('-') || host.contains(|c: char| c.is_whitespace() || c == '"') {
+        return Err(format!("'{}' is not a valid server", host));
+    }
+    let (address, port) = match address.rsplit_once(':') {
+        Some((address, port)) => (
+            address,
+            Some(
+                port.parse::<u16>()
+                    .map_err(|_| format!("'{}' is not a valid port", port))?,
+            ),
+        ),
+        None => (address, None),
+    };
+
+    let directory = directory.trim();
+    if directory.is_empty() || directory.contains(|c: char| c == '"' || c == '\n' || c == '\r') {
+        return Err("Please enter a valid directory on the server".to_string());
+    }
+
+    Ok(RemoteTarget {
+        destination: format!("{}@{}", user, address),
+        port,
+        directory: directory.to_string(),
+    })
+}
+
+/// The request of the `importdescriptors` command of bitcoind for the receive and change
+/// descriptors: the same the daemon makes when creating its watchonly wallet. The chain is
+/// rescanned from the given timestamp for the coins already received, if any.
+pub fn descriptor_import_request(
+    descriptor: &LianaDescriptor,
+    import: &DescriptorImportConfig,
+    rescan_timestamp: Option<u32>,
+) -> String {
+    let timestamp = rescan_timestamp
+        .map(serde_json::Value::from)
+        .unwrap_or_else(|| serde_json::Value::from("now"));
+    let mut descriptors = vec![(
+        descriptor.receive_descriptor().to_string(),
+        import.receive_range_end,
+    )];
+    if import.import_change {
+        descriptors.push((
+            descriptor.change_descriptor().to_string(),
+            import.change_range_end,
+        ));
+    }
+    let request: Vec<serde_json::Value> = descriptors
+        .into_iter()
+        .map(|(desc, range_end)| {
+            serde_json::json!({
+                "desc": desc,
+                "timestamp": timestamp,
+                "active": false,
+                "range": range_end,
+            })
+        })
+        .collect();
+    serde_json::to_string_pretty(&request).expect("Serializing a JSON array")
+}
+
+/// Run the given commands on the server, failing at the first failing one.
+fn run_batch(target: &RemoteTarget, commands: &str) -> Result<(), Error> {
+    let mut cmd = Command::new("sftp");
+    cmd.args(["-b", "-", "-o", "BatchMode=yes"]);
+    if let Some(port) = target.port {
+        cmd.arg("-P").arg(port.to_string());
+    }
+    let mut child = cmd
+        .arg(&target.destination)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| {
+            Error::RemoteExport(if e.kind() == std::io::ErrorKind::NotFound {
+                "the sftp program was not found on this computer".to_string()
+            } else {
+                format!("failed to run sftp: {}", e)
+            })
+        })?;
+    child
+        .stdin
+        .take()
+        .expect("Stdin is piped")
+        .write_all(commands.as_bytes())
+        .map_err(|e| Error::RemoteExport(format!("failed to send the commands to sftp: {}", e)))?;
+    let output = child
+        .wait_with_output()
+        .map_err(|e| Error::RemoteExport(format!("failed to run sftp: {}", e)))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(Error::RemoteExport(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ))
+    }
+}
+
+/// Check the server can be connected to without any interaction.
+pub async fn check_remote(target: RemoteTarget) -> Result<(), Error> {
+    run_batch(&target, "pwd\n")
+}
+
+/// Export the files, given by name and content, to the directory of the server. They are only
+/// written to a temporary directory of this computer for the time of the transfer, only
+/// readable by the current user since the daemon configuration may contain the RPC password of
+/// bitcoind.
+pub async fn export_to_remote(
+    target: RemoteTarget,
+    files: Vec<(&'static str, String)>,
+) -> Result<(), Error> {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or_default();
+    let local_dir =
+        std::env::temp_dir().join(format!("liana-export-{}-{}", std::process::id(), nanos));
+    // The directory is not created if it exists already: it could belong to another user.
+    create_private_dir(&local_dir).map_err(|e| Error::CannotCreateFile(e.to_string()))?;
+    let res = write_files(&local_dir, &files).and_then(|paths| {
+        let mut commands = format!("-mkdir \"{}\"\n", target.directory);
+        for (path, (name, _)) in paths.iter().zip(files.iter()) {
+            commands.push_str(&format!(
+                "put \"{}\" \"{}/{}\"\n",
+                path.to_string_lossy(),
+                target.directory.trim_end_matches('/'),
+                name
+            ));
+        }
+        run_batch(&target, &commands)
+    });
+    if let Err(e) = std::fs::remove_dir_all(&local_dir) {
+        tracing::warn!(
+            "Failed to remove the exported files from this computer: {}",
+            e
+        );
+    }
+    res
+}
+
+fn create_private_dir(path: &Path) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::fs::DirBuilder;
+        use std::os::unix::fs::DirBuilderExt;
+        DirBuilder::new().mode(0o700).create(path)
+    }
+
+    #[cfg(not(unix))]
+    std::fs::create_dir(path)
+}
+
+fn write_files(dir: &Path, files: &[(&'static str, String)]) -> Result<Vec<PathBuf>, Error> {
+    files
+        .iter()
+        .map(|(name, content)| {
+            let path = dir.join(name);
+            let mut options = std::fs::OpenOptions::new();
+            options.write(true).create_new(true);
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::OpenOptionsExt;
+                options.mode(0o600);
+            }
+            options
+                .open(&path)
+                .and_then(|mut file| file.write_all(content.as_bytes()))
+                .map_err(|e| Error::CannotWriteToFile(e.to_string()))?;
+            Ok(path)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn remote_target() {
+        assert_eq!(
+            parse_remote_target(" alice@node.local ", "/home/alice/.liana"),
+            Ok(RemoteTarget {
+                destination: "alice@node.local".to_string(),
+                port: None,
+                directory: "/home/alice/.liana".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_remote_target("alice@192.168.1.12:2222", ".liana")
+                .unwrap()
+                .port,
+            Some(2222)
+        );
+        assert!(parse_remote_target("node.local", ".liana").is_err());
+        assert!(parse_remote_target("@node.local", ".liana").is_err());
+        assert!(parse_remote_target("-oProxyCommand=x@node", ".liana").is_err());
+        assert!(parse_remote_target("alice@node local", ".liana").is_err());
+        assert!(parse_remote_target("alice@node.local:port", ".liana").is_err());
+        assert!(parse_remote_target("alice@node.local", "").is_err());
+        assert!(parse_remote_target("alice@node.local", "liana\"\nrm x").is_err());
+    }
+
+    #[test]
+    fn descriptor_import() {
+        let descriptor = LianaDescriptor::from_str("wsh(or_d(pk([abcdef01]xpub6Eze7yAT3Y1wGrnzedCNVYDXUqa9NmHVWck5emBaTbXtURbe1NWZbK9bsz1TiVE7Cz341PMTfYgFw1KdLWdzcM1UMFTcdQfCYhhXZ2HJvTW/<0;1>/*),and_v(v:pkh([abcdef01]xpub688Hn4wScQAAiYJLPg9yH27hUpfZAUnmJejRQBCiwfP5PEDzjWMNW1wChcninxr5gyavFqbbDjdV1aK5USJz8NDVjUy7FRQaaqqXHh5SbXe/<0;1>/*),older(52560))))#g7vk9r5l").unwrap();
+        let mut import = DescriptorImportConfig {
+            receive_range_end: 2000,
+            import_change: true,
+            change_range_end: 500,
+        };
+        let request: serde_json::Value =
+            serde_json::from_str(&descriptor_import_request(&descriptor, &import, None)).unwrap();
+        let request = request.as_array().unwrap();
+        assert_eq!(request.len(), 2);
+        assert_eq!(request[0]["timestamp"], "now");
+        assert_eq!(
+            request[0]["desc"],
+            descriptor.receive_descriptor().to_string()
+        );
+        assert_eq!(request[0]["range"], 2000);
+        assert_eq!(
+            request[1]["desc"],
+            descriptor.change_descriptor().to_string()
+        );
+        assert_eq!(request[1]["range"], 500);
+
+        import.import_change = false;
+        let request: serde_json::Value = serde_json::from_str(&descriptor_import_request(
+            &descriptor,
+            &import,
+            Some(1_667_260_800),
+        ))
+        .unwrap();
+        let request = request.as_array().unwrap();
+        assert_eq!(request.len(), 1);
+        // The chain is rescanned from the date chosen for the coins already received.
+        assert_eq!(request[0]["timestamp"], 1_667_260_800);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn private_export_files() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir =
+            std::env::temp_dir().join(format!("liana-gui-test-export-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        create_private_dir(&dir).unwrap();
+        // An existing directory is not reused.
+        assert!(create_private_dir(&dir).is_err());
+        let paths = write_files(&dir, &[("daemon.toml", "[bitcoind_config]".to_string())]).unwrap();
+        let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&dir), 0o700);
+        assert_eq!(mode(&paths[0]), 0o600);
+        assert_eq!(
+            std::fs::read_to_string(&paths[0]).unwrap(),
+            "[bitcoind_config]"
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
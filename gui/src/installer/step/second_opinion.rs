@@ -0,0 +1,251 @@
+//! The second opinion on a descriptor built by the installer: the spending paths are read back
+//! from the descriptor, parsed again from its string, and compared to the paths as defined by
+//! the user. It does not share code with the builder, so a bug in the builder is caught before
+//! the descriptor gets used rather than silently giving a wallet with another policy.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use liana::{
+    descriptors::{LianaDescriptor, PathInfo},
+    miniscript::{
+        bitcoin::util::bip32::{ChildNumber, ExtendedPubKey},
+        descriptor::{DescriptorPublicKey, DescriptorXKey, Wildcard},
+    },
+};
+
+/// A spending path as defined by the user.
+#[derive(Debug, Clone)]
+pub struct DefinedPath {
+    pub threshold: usize,
+    pub keys: Vec<DescriptorXKey<ExtendedPubKey>>,
+}
+
+/// Whether the key of the descriptor is the multipath key of the receive and change branches of
+/// the defined extended key.
+fn is_defined_key(key: &DescriptorPublicKey, defined: &DescriptorXKey<ExtendedPubKey>) -> bool {
+    let branches = [0, 1].map(|branch| {
+        defined
+            .derivation_path
+            .child(ChildNumber::from_normal_idx(branch).expect("Not hardened"))
+    });
+    match key {
+        DescriptorPublicKey::MultiXPub(xpub) => {
+            xpub.origin == defined.origin
+                && xpub.xkey == defined.xkey
+                && xpub.derivation_paths.paths().as_slice() == branches
+                && xpub.wildcard == Wildcard::Unhardened
+        }
+        _ => false,
+    }
+}
+
+/// Compare a spending path of the descriptor with its definition.
+fn check_path(path: &PathInfo, defined: &DefinedPath) -> Result<(), String> {
+    let (threshold, keys) = match path {
+        PathInfo::Single(key) => (1, vec![key]),
+        PathInfo::Multi(threshold, keys) => (*threshold, keys.iter().collect()),
+    };
+    // A path of a single key needs its signature whatever the threshold entered.
+    let defined_threshold = if defined.keys.len() == 1 {
+        1
+    } else {
+        defined.threshold
+    };
+    if threshold != defined_threshold {
+        return Err(format!(
+            "the threshold is {} instead of {}",
+            threshold, defined_threshold
+        ));
+    }
+    if keys.len() != defined.keys.len() {
+        return Err(format!(
+            "it has {} keys instead of {}",
+            keys.len(),
+            defined.keys.len()
+        ));
+    }
+    let mut remaining = keys;
+    for defined_key in &defined.keys {
+        match remaining
+            .iter()
+            .position(|key| is_defined_key(key, defined_key))
+        {
+            Some(i) => {
+                remaining.remove(i);
+            }
+            None => return Err(format!("the key {} is missing", defined_key.xkey)),
+        }
+    }
+    Ok(())
+}
+
+/// Check the descriptor has exactly the spending paths defined, recovery paths by timelock.
+pub fn second_opinion(
+    descriptor: &LianaDescriptor,
+    primary: &DefinedPath,
+    recovery: &BTreeMap<u16, DefinedPath>,
+) -> Result<(), String> {
+    let descriptor = LianaDescriptor::from_str(&descriptor.to_string())
+        .map_err(|e| format!("The descriptor cannot be parsed back: {}", e))?;
+    let policy = descriptor.policy();
+    check_path(policy.primary_path(), primary)
+        .map_err(|e| format!("The primary path does not match its definition: {}", e))?;
+
+    let timelocks: Vec<u16> = policy.recovery_paths().keys().copied().collect();
+    let defined_timelocks: Vec<u16> = recovery.keys().copied().collect();
+    if timelocks != defined_timelocks {
+        return Err(format!(
+            "The recovery paths have the timelocks {:?} instead of {:?}",
+            timelocks, defined_timelocks
+        ));
+    }
+    for (sequence, path) in policy.recovery_paths() {
+        check_path(path, &recovery[sequence]).map_err(|e| {
+            format!(
+                "The recovery path after {} blocks does not match its definition: {}",
+                sequence, e
+            )
+        })?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use liana::{
+        descriptors::LianaPolicy,
+        miniscript::{
+            bitcoin::{
+                secp256k1,
+                util::bip32::{DerivationPath, ExtendedPrivKey},
+                Network,
+            },
+            descriptor::{DerivPaths, DescriptorMultiXKey},
+        },
+    };
+
+    fn xkey(seed: u8) -> DescriptorXKey<ExtendedPubKey> {
+        let secp = secp256k1::Secp256k1::signing_only();
+        let master = ExtendedPrivKey::new_master(Network::Testnet, &[seed; 32]).unwrap();
+        let path = DerivationPath::from_str("m/48'/1'/0'/2'").unwrap();
+        DescriptorXKey {
+            origin: Some((master.fingerprint(&secp), path.clone())),
+            xkey: ExtendedPubKey::from_priv(&secp, &master.derive_priv(&secp, &path).unwrap()),
+            derivation_path: DerivationPath::master(),
+            wildcard: Wildcard::None,
+        }
+    }
+
+    fn multipath(xkey: &DescriptorXKey<ExtendedPubKey>) -> DescriptorPublicKey {
+        DescriptorPublicKey::MultiXPub(DescriptorMultiXKey {
+            origin: xkey.origin.clone(),
+            xkey: xkey.xkey,
+            derivation_paths: DerivPaths::new(vec![
+                DerivationPath::from_str("m/0").unwrap(),
+                DerivationPath::from_str("m/1").unwrap(),
+            ])
+            .unwrap(),
+            wildcard: Wildcard::Unhardened,
+        })
+    }
+
+    fn path_info(path: &DefinedPath) -> PathInfo {
+        let keys: Vec<_> = path.keys.iter().map(multipath).collect();
+        if keys.len() == 1 {
+            PathInfo::Single(keys[0].clone())
+        } else {
+            PathInfo::Multi(path.threshold, keys)
+        }
+    }
+
+    fn descriptor(primary: &DefinedPath, recovery: &BTreeMap<u16, DefinedPath>) -> LianaDescriptor {
+        LianaDescriptor::new(
+            LianaPolicy::new(
+                path_info(primary),
+                recovery
+                    .iter()
+                    .map(|(sequence, path)| (*sequence, path_info(path)))
+                    .collect(),
+            )
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn second_opinion_on_descriptor() {
+        let primary = DefinedPath {
+            threshold: 2,
+            keys: vec![xkey(1), xkey(2), xkey(3)],
+        };
+        let recovery: BTreeMap<u16, DefinedPath> = vec![
+            (
+                52560,
+                DefinedPath {
+                    threshold: 1,
+                    keys: vec![xkey(4)],
+                },
+            ),
+            (
+                26352,
+                DefinedPath {
+                    threshold: 2,
+                    keys: vec![xkey(5), xkey(6)],
+                },
+            ),
+        ]
+        .into_iter()
+        .collect();
+        let desc = descriptor(&primary, &recovery);
+        assert_eq!(second_opinion(&desc, &primary, &recovery), Ok(()));
+
+        // The order of the keys does not matter.
+        let reordered = DefinedPath {
+            threshold: 2,
+            keys: vec![xkey(3), xkey(1), xkey(2)],
+        };
+        assert_eq!(second_opinion(&desc, &reordered, &recovery), Ok(()));
+
+        // A descriptor built from a perturbed path is caught.
+        let mut perturbed = recovery.clone();
+        perturbed.get_mut(&26352).unwrap().threshold = 1;
+        let err =
+            second_opinion(&descriptor(&primary, &perturbed), &primary, &recovery).unwrap_err();
+        assert!(err.contains("after 26352 blocks") && err.contains("threshold"));
+
+        let mut perturbed = recovery.clone();
+        perturbed.get_mut(&52560).unwrap().keys = vec![xkey(7)];
+        let err =
+            second_opinion(&descriptor(&primary, &perturbed), &primary, &recovery).unwrap_err();
+        assert!(err.contains("after 52560 blocks") && err.contains("missing"));
+
+        let mut perturbed = recovery.clone();
+        let path = perturbed.remove(&52560).unwrap();
+        perturbed.insert(52561, path);
+        assert!(
+            second_opinion(&descriptor(&primary, &perturbed), &primary, &recovery)
+                .unwrap_err()
+                .contains("timelocks")
+        );
+
+        let swapped = DefinedPath {
+            threshold: 2,
+            keys: vec![xkey(1), xkey(2), xkey(4)],
+        };
+        assert!(
+            second_opinion(&descriptor(&swapped, &recovery), &primary, &recovery)
+                .unwrap_err()
+                .contains("primary path")
+        );
+
+        // A key of another branch is not the one defined.
+        let mut other_branch = xkey(1);
+        other_branch.derivation_path = DerivationPath::from_str("m/2").unwrap();
+        let defined = DefinedPath {
+            threshold: 2,
+            keys: vec![other_branch, xkey(2), xkey(3)],
+        };
+        assert!(second_opinion(&desc, &defined, &recovery).is_err());
+    }
+}
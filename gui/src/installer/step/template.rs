@@ -0,0 +1,140 @@
+//! The library of well known policies a descriptor is compared to, for the user to tell at a
+//! glance whether the wallet follows a standard setup or a bespoke one. Only the structure of
+//! the policy is compared: the thresholds, the number of keys and the timelocks, not the keys.
+
+use liana::descriptors::LianaDescriptor;
+
+/// The structure of a policy, with its recovery paths sorted by timelock.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyShape {
+    /// The threshold and the number of keys of the primary path.
+    pub primary: (usize, usize),
+    /// The timelock, the threshold and the number of keys of each recovery path.
+    pub recovery: Vec<(u16, usize, usize)>,
+}
+
+impl PolicyShape {
+    pub fn new(primary: (usize, usize), mut recovery: Vec<(u16, usize, usize)>) -> Self {
+        recovery.sort_unstable();
+        Self { primary, recovery }
+    }
+
+    pub fn from_descriptor(descriptor: &LianaDescriptor) -> Self {
+        let policy = descriptor.policy();
+        let (threshold, origins) = policy.primary_path().thresh_origins();
+        Self::new(
+            (threshold, origins.len()),
+            policy
+                .recovery_paths()
+                .iter()
+                .map(|(sequence, path)| {
+                    let (threshold, origins) = path.thresh_origins();
+                    (*sequence, threshold, origins.len())
+                })
+                .collect(),
+        )
+    }
+}
+
+pub struct PolicyTemplate {
+    pub name: &'static str,
+    primary: (usize, usize),
+    recovery: &'static [(u16, usize, usize)],
+}
+
+impl PolicyTemplate {
+    fn matches(&self, shape: &PolicyShape) -> bool {
+        self.primary == shape.primary && self.recovery == shape.recovery.as_slice()
+    }
+}
+
+/// The known templates, with their timelocks in mainnet blocks.
+pub const POLICY_TEMPLATES: &[PolicyTemplate] = &[
+    PolicyTemplate {
+        name: "single key + 6-month recovery",
+        primary: (1, 1),
+        recovery: &[(26352, 1, 1)],
+    },
+    PolicyTemplate {
+        name: "simple inheritance: single key + 1-year recovery",
+        primary: (1, 1),
+        recovery: &[(52560, 1, 1)],
+    },
+    PolicyTemplate {
+        name: "2-of-3 + 90-day recovery",
+        primary: (2, 3),
+        recovery: &[(12960, 1, 1)],
+    },
+    PolicyTemplate {
+        name: "expanding multisig: 2-of-2 + 2-of-3 after 1 year",
+        primary: (2, 2),
+        recovery: &[(52560, 2, 3)],
+    },
+    PolicyTemplate {
+        name: "decaying multisig: 2-of-3 + 1-of-3 after 1 year",
+        primary: (2, 3),
+        recovery: &[(52560, 1, 3)],
+    },
+];
+
+pub fn matching_template(shape: &PolicyShape) -> Option<&'static PolicyTemplate> {
+    POLICY_TEMPLATES
+        .iter()
+        .find(|template| template.matches(shape))
+}
+
+/// The label of the policy given after its summary.
+pub fn policy_template_label(shape: &PolicyShape) -> String {
+    match matching_template(shape) {
+        Some(template) => format!("This is the standard {} template", template.name),
+        None => "This is a custom policy, it matches none of the standard templates".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn template_descriptors() {
+        let inheritance = LianaDescriptor::from_str("wsh(or_d(pk([abcdef01]xpub6Eze7yAT3Y1wGrnzedCNVYDXUqa9NmHVWck5emBaTbXtURbe1NWZbK9bsz1TiVE7Cz341PMTfYgFw1KdLWdzcM1UMFTcdQfCYhhXZ2HJvTW/<0;1>/*),and_v(v:pkh([abcdef01]xpub688Hn4wScQAAiYJLPg9yH27hUpfZAUnmJejRQBCiwfP5PEDzjWMNW1wChcninxr5gyavFqbbDjdV1aK5USJz8NDVjUy7FRQaaqqXHh5SbXe/<0;1>/*),older(52560))))").unwrap();
+        let shape = PolicyShape::from_descriptor(&inheritance);
+        assert_eq!(shape, PolicyShape::new((1, 1), vec![(52560, 1, 1)]));
+        assert_eq!(
+            policy_template_label(&shape),
+            "This is the standard simple inheritance: single key + 1-year recovery template"
+        );
+
+        let custom = LianaDescriptor::from_str("wsh(or_d(pk([9e1c1983/48'/1'/0'/2']tpubDEWCLCRcyEWgDA6kgcFvq3xbn5EjMwr3ZF3LQyJEE5pvbsLNKnLBNSsGreZm8NRkGoqA3mTjBR7RxuJ6vKQzhCTCq1GXZjfPvAXXzrmuoWj/<0;1>/*),and_v(v:pkh([f5acc2fd/48'/1'/0'/2']tpubDFAqEGNyad35aBCKUAXbQGDjdVhNueno5ZZVEn3sQbW5ci457gLR7HyTmHBg93oourBssgUxuWz1jX5uhc1qaqFo9VsybY1J5FuedLfm4dK/<0;1>/*),older(20))))").unwrap();
+        let shape = PolicyShape::from_descriptor(&custom);
+        assert!(matching_template(&shape).is_none());
+        assert_eq!(
+            policy_template_label(&shape),
+            "This is a custom policy, it matches none of the standard templates"
+        );
+    }
+
+    #[test]
+    fn template_shapes() {
+        assert_eq!(
+            matching_template(&PolicyShape::new((2, 3), vec![(12960, 1, 1)]))
+                .unwrap()
+                .name,
+            "2-of-3 + 90-day recovery"
+        );
+        // The recovery paths are compared whatever their order.
+        assert_eq!(
+            PolicyShape::new((2, 3), vec![(52560, 1, 3), (12960, 1, 1)]),
+            PolicyShape::new((2, 3), vec![(12960, 1, 1), (52560, 1, 3)])
+        );
+        // Close to a template is still custom.
+        assert!(matching_template(&PolicyShape::new((2, 3), vec![(12961, 1, 1)])).is_none());
+        assert!(matching_template(&PolicyShape::new((1, 3), vec![(12960, 1, 1)])).is_none());
+        assert!(matching_template(&PolicyShape::new(
+            (2, 3),
+            vec![(12960, 1, 1), (52560, 1, 3)]
+        ))
+        .is_none());
+    }
+}
@@ -0,0 +1,605 @@
+//! Publication of the RPC interface of the daemon as an onion service, for the wallet to be
+//! reached remotely through Tor. The service is requested with `ADD_ONION` on the control port of
+//! a Tor daemon already running, with the `Detach` flag for it to outlive the control connection.
+//! Tor forgets it when it restarts: the GUI publishes it again with the same key when it starts.
+//! The RPC socket is a Unix socket: the Tor version must accept the `unix:` targets. The RPC
+//! interface has no authentication, so the service requires the v3 client authorization: only
+//! the client with the key generated here can reach it.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+use iced::Command;
+use liana::miniscript::bitcoin::hashes::hex::ToHex;
+use liana_ui::{component::form, widget::Element};
+
+use crate::{
+    app::config::TorConfig,
+    installer::{
+        context::Context,
+        message::{self, Message},
+        step::{Step, SummarySection},
+        view, Error,
+    },
+};
+
+/// The default address of the control port of Tor, when enabled with `ControlPort 9051`.
+pub const DEFAULT_TOR_CONTROL_ADDRESS: &str = "127.0.0.1:9051";
+pub const DEFAULT_ONION_PORT: u16 = 8080;
+
+/// Timeout of the connection to the control port and of its replies.
+const TOR_CONTROL_TIMEOUT_SECS: u64 = 5;
+
+/// Parse the control port settings entered by the user. The control port must not be given
+/// both a cookie file and a password.
+pub fn parse_tor_control(
+    address: &str,
+    cookie_path: &str,
+    password: &str,
+    onion_port: &str,
+) -> Result<TorConfig, String> {
+    let address = address.trim();
+    SocketAddr::from_str(address).map_err(|_| {
+        format!(
+            "'{}' is not a valid address, such as 127.0.0.1:9051",
+            address
+        )
+    })?;
+    let onion_port = u16::from_str(onion_port.trim())
+        .ok()
+        .filter(|port| *port != 0)
+        .ok_or_else(|| format!("'{}' is not a valid port", onion_port.trim()))?;
+    let (cookie_path, password) = (cookie_path.trim(), password.trim());
+    if !cookie_path.is_empty() && !password.is_empty() {
+        return Err("Please enter either the cookie file or the password, not both".to_string());
+    }
+    Ok(TorConfig {
+        control_address: address.to_string(),
+        cookie_path: if cookie_path.is_empty() {
+            None
+        } else {
+            Some(PathBuf::from(cookie_path))
+        },
+        password: if password.is_empty() {
+            None
+        } else {
+            Some(password.to_string())
+        },
+        onion_port,
+        onion_address: None,
+        service_key: None,
+        client_auth_key: None,
+    })
+}
+
+/// Encode in base32 as Tor does: the lowercase alphabet of RFC 4648, without padding.
+fn base32(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+    let mut encoded = String::with_capacity((data.len() * 8 + 4) / 5);
+    let (mut buffer, mut bits) = (0u32, 0);
+    for byte in data {
+        buffer = (buffer << 8) | u32::from(*byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            encoded.push(ALPHABET[((buffer >> bits) & 31) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        encoded.push(ALPHABET[((buffer << (5 - bits)) & 31) as usize] as char);
+    }
+    encoded
+}
+
+/// Generate the x25519 key pair of the client authorized to the onion service. Returns the
+/// public key, given to Tor with the service, and the private key, given to the client.
+pub fn generate_client_auth() -> (String, String) {
+    let secret = x25519_dalek::StaticSecret::random();
+    let public = x25519_dalek::PublicKey::from(&secret);
+    (base32(public.as_bytes()), base32(&secret.to_bytes()))
+}
+
+/// The line of the `.auth_private` file of the client in the `ClientOnionAuthDir` of its Tor.
+pub fn client_auth_line(onion_address: &str, private_key: &str) -> String {
+    format!(
+        "{}:descriptor:x25519:{}",
+        onion_address.trim_end_matches(".onion"),
+        private_key
+    )
+}
+
+/// Read a reply of the control port, returning its lines without their status code. A reply
+/// with an error status is returned as an error.
+fn read_reply(reader: &mut impl BufRead) -> Result<Vec<String>, Error> {
+    let mut lines = Vec::new();
+    loop {
+        let mut line = String::new();
+        if reader
+            .read_line(&mut line)
+            .map_err(|e| Error::Tor(format!("failed to read the reply of Tor: {}", e)))?
+            == 0
+        {
+            return Err(Error::Tor("the control connection was closed".to_string()));
+        }
+        let line = line.trim_end_matches(|c| c == '\r' || c == '\n');
+        let (code, separator, content) = match (line.get(..3), line.get(3..4), line.get(4..)) {
+            (Some(code), Some(separator), Some(content)) => (code, separator, content),
+            _ => return Err(Error::Tor(format!("unexpected reply '{}'", line))),
+        };
+        if code != "250" {
+            return Err(Error::Tor(content.to_string()));
+        }
+        match separator {
+            "-" => lines.push(content.to_string()),
+            // The data of the reply, until a line with a single dot.
+            "+" => {
+                let mut data = content.to_string();
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).map_err(|e| {
+                        Error::Tor(format!("failed to read the reply of Tor: {}", e))
+                    })?;
+                    let line = line.trim_end_matches(|c| c == '\r' || c == '\n');
+                    if line == "." || line.is_empty() {
+                        break;
+                    }
+                    data.push('\n');
+                    data.push_str(line);
+                }
+                lines.push(data);
+            }
+            _ => {
+                lines.push(content.to_string());
+                return Ok(lines);
+            }
+        }
+    }
+}
+
+/// An authenticated connection to the control port of Tor.
+struct TorControl {
+    reader: BufReader<TcpStream>,
+    stream: TcpStream,
+}
+
+impl TorControl {
+    fn connect(config: &TorConfig) -> Result<Self, Error> {
+        let address = SocketAddr::from_str(&config.control_address)
+            .map_err(|e| Error::Tor(format!("invalid control port address: {}", e)))?;
+        let timeout = Duration::from_secs(TOR_CONTROL_TIMEOUT_SECS);
+        let stream = TcpStream::connect_timeout(&address, timeout).map_err(|e| {
+            Error::Tor(format!(
+                "the control port at {} is not reachable: {}",
+                address, e
+            ))
+        })?;
+        stream
+            .set_read_timeout(Some(timeout))
+            .map_err(|e| Error::Tor(e.to_string()))?;
+        let reader = BufReader::new(stream.try_clone().map_err(|e| Error::Tor(e.to_string()))?);
+        let mut control = Self { reader, stream };
+
+        let authenticate = if let Some(path) = &config.cookie_path {
+            let cookie = std::fs::read(path).map_err(|e| {
+                Error::Tor(format!(
+                    "failed to read the cookie file {}: {}",
+                    path.to_string_lossy(),
+                    e
+                ))
+            })?;
+            format!("AUTHENTICATE {}", cookie.to_hex())
+        } else if let Some(password) = &config.password {
+            format!("AUTHENTICATE {}", quote(password))
+        } else {
+            "AUTHENTICATE".to_string()
+        };
+        control.command(&authenticate).map_err(|e| match e {
+            Error::Tor(e) => Error::Tor(format!("the authentication failed: {}", e)),
+            e => e,
+        })?;
+        Ok(control)
+    }
+
+    fn command(&mut self, command: &str) -> Result<Vec<String>, Error> {
+        self.stream
+            .write_all(format!("{}\r\n", command).as_bytes())
+            .map_err(|e| Error::Tor(format!("failed to send the command to Tor: {}", e)))?;
+        read_reply(&mut self.reader)
+    }
+}
+
+/// A quoted string of the control protocol.
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Check the control port is reachable and accepts the authentication, returning the version of
+/// Tor.
+pub async fn check_tor_control(config: TorConfig) -> Result<String, Error> {
+    let mut control = TorControl::connect(&config)?;
+    let lines = control.command("GETINFO version")?;
+    Ok(lines
+        .iter()
+        .find_map(|line| line.strip_prefix("version="))
+        .unwrap_or("unknown")
+        .to_string())
+}
+
+/// Publish the onion service forwarding its port to the given RPC socket, only reachable by the
+/// authorized client. The key of the service is reused if already set, otherwise a new one is
+/// generated by Tor. Returns the config with the onion address and the key of the service.
+pub fn publish_onion_service(mut config: TorConfig, rpc_socket: &Path) -> Result<TorConfig, Error> {
+    let client_auth_key = config.client_auth_key.clone().ok_or_else(|| {
+        Error::Tor("the RPC interface is not published without client authorization".to_string())
+    })?;
+    let target = rpc_socket.to_string_lossy();
+    if target.contains(char::is_whitespace) {
+        return Err(Error::Tor(format!(
+            "the path of the RPC socket {} cannot contain a space",
+            target
+        )));
+    }
+    let mut control = TorControl::connect(&config)?;
+    let key = config
+        .service_key
+        .clone()
+        .unwrap_or_else(|| "NEW:ED25519-V3".to_string());
+    let lines = control.command(&format!(
+        "ADD_ONION {} Flags=Detach Port={},unix:{} ClientAuthV3={}",
+        key, config.onion_port, target, client_auth_key
+    ))?;
+    let service_id = lines
+        .iter()
+        .find_map(|line| line.strip_prefix("ServiceID="))
+        .ok_or_else(|| Error::Tor("Tor did not return the onion service".to_string()))?;
+    config.onion_address = Some(format!("{}.onion", service_id));
+    if let Some(key) = lines
+        .iter()
+        .find_map(|line| line.strip_prefix("PrivateKey="))
+    {
+        config.service_key = Some(key.to_string());
+    }
+    Ok(config)
+}
+
+/// Publish again the onion service of the GUI configuration, if Tor does not have it anymore.
+/// Nothing is done if the service was not published at install.
+pub async fn republish_onion_service(config: TorConfig, rpc_socket: PathBuf) -> Result<(), Error> {
+    let service_id = match (&config.onion_address, &config.service_key) {
+        (Some(address), Some(_)) => address.trim_end_matches(".onion").to_string(),
+        _ => return Ok(()),
+    };
+    let lines = TorControl::connect(&config)?.command("GETINFO onions/detached")?;
+    let is_published = lines
+        .iter()
+        .filter_map(|line| line.strip_prefix("onions/detached="))
+        .flat_map(|ids| ids.split_whitespace())
+        .any(|id| id == service_id);
+    if !is_published {
+        publish_onion_service(config, &rpc_socket)?;
+    }
+    Ok(())
+}
+
+/// Remove the onion service published with the given config.
+pub fn remove_onion_service(config: &TorConfig) -> Result<(), Error> {
+    if let Some(address) = &config.onion_address {
+        let mut control = TorControl::connect(config)?;
+        control.command(&format!("DEL_ONION {}", address.trim_end_matches(".onion")))?;
+    }
+    Ok(())
+}
+
+/// The Tor control port the onion service of the daemon is requested to. It is an advanced
+/// option: the step is skipped unless the user enables it.
+pub struct DefineTor {
+    enabled: bool,
+    control_address: form::Value<String>,
+    cookie_path: form::Value<String>,
+    password: form::Value<String>,
+    onion_port: form::Value<String>,
+    /// The version of Tor, once the control port was checked.
+    is_reachable: Option<Result<String, Error>>,
+}
+
+impl DefineTor {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            control_address: form::Value {
+                value: DEFAULT_TOR_CONTROL_ADDRESS.to_string(),
+                valid: true,
+            },
+            cookie_path: form::Value::default(),
+            password: form::Value::default(),
+            onion_port: form::Value {
+                value: DEFAULT_ONION_PORT.to_string(),
+                valid: true,
+            },
+            is_reachable: None,
+        }
+    }
+
+    fn tor_config(&mut self) -> Option<TorConfig> {
+        match parse_tor_control(
+            &self.control_address.value,
+            &self.cookie_path.value,
+            &self.password.value,
+            &self.onion_port.value,
+        ) {
+            Ok(config) => Some(config),
+            Err(e) => {
+                self.is_reachable = Some(Err(Error::Tor(e)));
+                None
+            }
+        }
+    }
+}
+
+impl Default for DefineTor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Step for DefineTor {
+    fn update(&mut self, message: Message) -> Command<Message> {
+        if let Message::DefineTor(msg) = message {
+            match msg {
+                message::DefineTor::Enabled(enabled) => {
+                    self.enabled = enabled;
+                }
+                message::DefineTor::AddressEdited(address) => {
+                    self.is_reachable = None;
+                    self.control_address.valid = true;
+                    self.control_address.value = address;
+                }
+                message::DefineTor::CookiePathEdited(path) => {
+                    self.is_reachable = None;
+                    self.cookie_path.value = path;
+                }
+                message::DefineTor::PasswordEdited(password) => {
+                    self.is_reachable = None;
+                    self.password.value = password;
+                }
+                message::DefineTor::OnionPortEdited(port) => {
+                    self.is_reachable = None;
+                    self.onion_port.valid = true;
+                    self.onion_port.value = port;
+                }
+                message::DefineTor::CheckControlPort => {
+                    if let Some(config) = self.tor_config() {
+                        self.is_reachable = None;
+                        return Command::perform(check_tor_control(config), |res| {
+                            Message::DefineTor(message::DefineTor::Checked(res))
+                        });
+                    }
+                }
+                message::DefineTor::Checked(res) => {
+                    self.is_reachable = Some(res);
+                }
+            }
+        }
+        Command::none()
+    }
+
+    fn summary_section(&self) -> Option<SummarySection> {
+        Some(SummarySection::Tor)
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    fn apply(&mut self, ctx: &mut Context) -> bool {
+        if !self.enabled {
+            ctx.tor = None;
+            ctx.tor_client_key = None;
+            return true;
+        }
+        // The control port must have been reached with the current settings.
+        if !matches!(self.is_reachable, Some(Ok(_))) {
+            return false;
+        }
+        match self.tor_config() {
+            Some(mut config) => {
+                let (public_key, private_key) = generate_client_auth();
+                config.client_auth_key = Some(public_key);
+                ctx.tor = Some(config);
+                ctx.tor_client_key = Some(private_key);
+                true
+            }
+            None => {
+                self.control_address.valid = false;
+                false
+            }
+        }
+    }
+
+    fn view(&self, progress: (usize, usize)) -> Element<Message> {
+        view::define_tor(
+            progress,
+            self.enabled,
+            &self.control_address,
+            &self.cookie_path,
+            &self.password,
+            &self.onion_port,
+            self.is_reachable.as_ref(),
+        )
+    }
+}
+
+impl From<DefineTor> for Box<dyn Step> {
+    fn from(s: DefineTor) -> Box<dyn Step> {
+        Box::new(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    #[test]
+    fn tor_control_settings() {
+        let config = parse_tor_control(" 127.0.0.1:9051 ", "", "secret", "8080").unwrap();
+        assert_eq!(config.control_address, "127.0.0.1:9051");
+        assert_eq!(config.cookie_path, None);
+        assert_eq!(config.password, Some("secret".to_string()));
+        assert_eq!(config.onion_port, 8080);
+        assert_eq!(
+            parse_tor_control("127.0.0.1:9051", "/run/tor/control.authcookie", "", "80")
+                .unwrap()
+                .cookie_path,
+            Some(PathBuf::from("/run/tor/control.authcookie"))
+        );
+        assert!(parse_tor_control("localhost", "", "", "80").is_err());
+        assert!(parse_tor_control("127.0.0.1:9051", "", "", "0").is_err());
+        assert!(parse_tor_control("127.0.0.1:9051", "", "", "65536").is_err());
+        assert!(parse_tor_control("127.0.0.1:9051", "/cookie", "secret", "80").is_err());
+    }
+
+    #[test]
+    fn tor_control_replies() {
+        let mut reply =
+            "250-ServiceID=abc\r\n250-PrivateKey=ED25519-V3:key\r\n250 OK\r\n".as_bytes();
+        assert_eq!(
+            read_reply(&mut reply).unwrap(),
+            vec!["ServiceID=abc", "PrivateKey=ED25519-V3:key", "OK"]
+        );
+        let mut reply = "250+config-text=\r\nControlPort 9051\r\n.\r\n250 OK\r\n".as_bytes();
+        assert_eq!(
+            read_reply(&mut reply).unwrap(),
+            vec!["config-text=\nControlPort 9051", "OK"]
+        );
+        let mut reply = "515 Authentication failed: Password did not match\r\n".as_bytes();
+        assert!(matches!(
+            read_reply(&mut reply),
+            Err(Error::Tor(e)) if e == "Authentication failed: Password did not match"
+        ));
+        assert!(read_reply(&mut "".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn tor_client_auth() {
+        assert_eq!(base32(b""), "");
+        assert_eq!(base32(b"f"), "my");
+        assert_eq!(base32(b"foobar"), "mzxw6ytboi");
+
+        let (public_key, private_key) = generate_client_auth();
+        assert_eq!(public_key.len(), 52);
+        assert_eq!(private_key.len(), 52);
+        assert_ne!(public_key, private_key);
+        assert_ne!(generate_client_auth().0, public_key);
+        assert_eq!(
+            client_auth_line("abc.onion", &private_key),
+            format!("abc:descriptor:x25519:{}", private_key)
+        );
+
+        // The RPC interface is never published without client authorization.
+        let config = parse_tor_control("127.0.0.1:9051", "", "", "8080").unwrap();
+        assert!(matches!(
+            publish_onion_service(config, Path::new("/home/alice/.liana/bitcoin/lianad_rpc")),
+            Err(Error::Tor(_))
+        ));
+    }
+
+    #[test]
+    fn publish_and_remove_onion_service() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        // A control port accepting the password, answering each command in turn.
+        let server = std::thread::spawn(move || {
+            let mut commands = Vec::new();
+            for replies in [
+                "250-ServiceID=abc\r\n250-PrivateKey=ED25519-V3:key\r\n250 OK\r\n",
+                "250 OK\r\n",
+            ] {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut stream = stream;
+                for reply in ["250 OK\r\n", replies] {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    commands.push(line.trim_end().to_string());
+                    stream.write_all(reply.as_bytes()).unwrap();
+                }
+            }
+            commands
+        });
+
+        let mut config = parse_tor_control(&address.to_string(), "", "se\"cret", "8080").unwrap();
+        config.client_auth_key = Some("client".to_string());
+        let config =
+            publish_onion_service(config, Path::new("/home/alice/.liana/bitcoin/lianad_rpc"))
+                .unwrap();
+        assert_eq!(config.onion_address, Some("abc.onion".to_string()));
+        assert_eq!(config.service_key, Some("ED25519-V3:key".to_string()));
+        remove_onion_service(&config).unwrap();
+
+        assert_eq!(
+            server.join().unwrap(),
+            vec![
+                "AUTHENTICATE \"se\\\"cret\"",
+                "ADD_ONION NEW:ED25519-V3 Flags=Detach Port=8080,unix:/home/alice/.liana/bitcoin/lianad_rpc ClientAuthV3=client",
+                "AUTHENTICATE \"se\\\"cret\"",
+                "DEL_ONION abc",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn republish_onion_service_after_restart() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        // Tor restarted and forgot the service, then has it again.
+        let server = std::thread::spawn(move || {
+            let mut commands = Vec::new();
+            for replies in [
+                vec!["250-onions/detached=other\r\n250 OK\r\n"],
+                vec!["250-ServiceID=abc\r\n250 OK\r\n"],
+                vec!["250+onions/detached=\r\nother\r\nabc\r\n.\r\n250 OK\r\n"],
+            ] {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut stream = stream;
+                for reply in std::iter::once("250 OK\r\n").chain(replies) {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    commands.push(line.trim_end().to_string());
+                    stream.write_all(reply.as_bytes()).unwrap();
+                }
+            }
+            commands
+        });
+
+        let rpc_socket = PathBuf::from("/home/alice/.liana/bitcoin/lianad_rpc");
+        let mut config = parse_tor_control(&address.to_string(), "", "", "8080").unwrap();
+        config.client_auth_key = Some("client".to_string());
+        // Never published, nothing to do.
+        republish_onion_service(config.clone(), rpc_socket.clone())
+            .await
+            .unwrap();
+        config.onion_address = Some("abc.onion".to_string());
+        config.service_key = Some("ED25519-V3:key".to_string());
+        republish_onion_service(config.clone(), rpc_socket.clone())
+            .await
+            .unwrap();
+        republish_onion_service(config, rpc_socket).await.unwrap();
+
+        assert_eq!(
+            server.join().unwrap(),
+            vec![
+                "AUTHENTICATE",
+                "GETINFO onions/detached",
+                "AUTHENTICATE",
+                "ADD_ONION ED25519-V3:key Flags=Detach Port=8080,unix:/home/alice/.liana/bitcoin/lianad_rpc ClientAuthV3=client",
+                "AUTHENTICATE",
+                "GETINFO onions/detached",
+            ]
+        );
+    }
+}
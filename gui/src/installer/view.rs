@@ -1,5 +1,6 @@
 use iced::widget::{
-    checkbox, container, pick_list, scrollable, scrollable::Properties, slider, Space, TextInput,
+    checkbox, container, pick_list, qr_code, scrollable, scrollable::Properties, slider, Space,
+    TextInput,
 };
 use iced::{alignment, Alignment, Length};
 
@@ -21,9 +22,14 @@ use liana_ui::{
 use crate::{
     hw::HardwareWallet,
     installer::{
-        context::Context,
+        context::{Context, SecurityWarning},
         message::{self, Message},
-        prompt, Error,
+        prompt,
+        step::{
+            AddressBranch, ChecklistItem, DescriptorAnalysis, HotSignerPlacement,
+            NodeDescriptorInfo, RegistrationFormat, RescanStart, SummarySection, MNEMONIC_LENGTHS,
+        },
+        Error,
     },
 };
 
@@ -152,6 +158,10 @@ pub fn welcome<'a>() -> Element<'a, Message> {
                                     .padding(20),
                                 ),
                         )
+                        .push(
+                            button::transparent(None, "Verify a descriptor offline")
+                                .on_press(Message::VerifyDescriptor),
+                        )
                         .push(Space::with_height(Length::Units(100)))
                         .spacing(50)
                         .align_items(Alignment::Center),
@@ -173,6 +183,15 @@ pub fn define_descriptor<'a>(
     spending_keys: Vec<Element<'a, Message>>,
     spending_threshold: usize,
     recovery_paths: Vec<Element<'a, Message>>,
+    warnings: Vec<(SecurityWarning, String, bool)>,
+    preview: Result<String, String>,
+    policy_summary: Vec<String>,
+    witness_sizes: Vec<(String, bool)>,
+    address_change: Option<&(String, String)>,
+    aliases_input: &form::Value<String>,
+    can_compare_hot_primary_key: bool,
+    can_add_hot_recovery_path: bool,
+    primary_key_variants: Option<(Result<String, String>, Result<String, String>, bool)>,
     valid: bool,
     error: Option<&String>,
 ) -> Element<'a, Message> {
@@ -273,6 +292,44 @@ pub fn define_descriptor<'a>(
                     )
                     .spacing(25),
             )
+            .push(descriptor_preview(preview, policy_summary, witness_sizes))
+            .push_maybe(if can_compare_hot_primary_key {
+                Some(
+                    button::border(None, "Compare with a primary key on this computer").on_press(
+                        Message::DefineDescriptor(message::DefineDescriptor::CompareHotPrimaryKey),
+                    ),
+                )
+            } else {
+                None
+            })
+            .push_maybe(
+                primary_key_variants.map(|(hardware, hot, use_hot)| {
+                    primary_key_variants_view(hardware, hot, use_hot)
+                }),
+            )
+            .push(key_aliases_input(aliases_input))
+            .push_maybe(address_change.map(|(before, after)| {
+                card::simple(
+                    Column::new()
+                        .spacing(5)
+                        .push(text(prompt::ADDRESS_CHANGE_WARNING).small())
+                        .push(text(format!("First address before the edit: {}", before)).small())
+                        .push(text(format!("First address after the edit: {}", after)).small()),
+                )
+                .width(Length::Fill)
+            }))
+            .push_maybe(if warnings.is_empty() {
+                None
+            } else {
+                Some(
+                    warnings.into_iter().fold(
+                        Column::new().spacing(10),
+                        |col, (warning, input, ack)| {
+                            col.push(security_warning(warning, input, ack))
+                        },
+                    ),
+                )
+            })
             .push(
                 Row::new()
                     .spacing(10)
@@ -283,12 +340,29 @@ pub fn define_descriptor<'a>(
                             ))
                             .width(Length::Units(200)),
                     )
-                    .push(if !valid {
-                        button::primary(None, "Next").width(Length::Units(200))
+                    .push_maybe(if can_add_hot_recovery_path {
+                        Some(
+                            button::secondary(
+                                Some(icon::plus_icon()),
+                                "Add a recovery key on this computer",
+                            )
+                            .on_press(Message::DefineDescriptor(
+                                message::DefineDescriptor::AddHotRecoveryPath,
+                            )),
+                        )
                     } else {
+                        None
+                    })
+                    .push(reset_button())
+                    .push(if !valid {
                         button::primary(None, "Next")
                             .width(Length::Units(200))
-                            .on_press(Message::Next)
+                            .into()
+                    } else {
+                        button::debounced(
+                            || button::primary(None, "Next").width(Length::Units(200)),
+                            Message::Next,
+                        )
                     }),
             )
             .push_maybe(error.map(|e| card::error("Failed to create descriptor", e.to_string())))
@@ -298,6 +372,179 @@ pub fn define_descriptor<'a>(
     )
 }
 
+/// The descriptor and a summary of its policy, as the user defines them.
+/// The descriptors using a signing device or the hot signer for the primary key, side by side.
+fn primary_key_variants_view<'a>(
+    hardware: Result<String, String>,
+    hot: Result<String, String>,
+    use_hot: bool,
+) -> Element<'a, Message> {
+    let variant = |title: &'static str,
+                   help: Element<'a, Message>,
+                   preview: Result<String, String>,
+                   selected: bool,
+                   hot: bool| {
+        let valid = preview.is_ok();
+        card::simple(
+            Column::new()
+                .spacing(10)
+                .push(text(title).bold())
+                .push(help)
+                .push::<Element<'a, Message>>(match preview {
+                    Ok(desc) => scrollable(Container::new(text(desc).small()).padding(10))
+                        .horizontal_scroll(Properties::new().width(3).scroller_width(3))
+                        .into(),
+                    Err(e) => text(format!("Invalid policy: {}", e))
+                        .small()
+                        .style(color::RED)
+                        .into(),
+                })
+                .push::<Element<'a, Message>>(if selected {
+                    text("Selected").small().bold().into()
+                } else if valid {
+                    button::secondary(None, "Use this descriptor")
+                        .on_press(Message::DefineDescriptor(
+                            message::DefineDescriptor::UseHotPrimaryKey(hot),
+                        ))
+                        .into()
+                } else {
+                    button::secondary(None, "Use this descriptor").into()
+                }),
+        )
+        .width(Length::FillPortion(1))
+    };
+    Row::new()
+        .spacing(10)
+        .push(variant(
+            "Primary key on the signing device",
+            text(prompt::HARDWARE_PRIMARY_KEY_HELP).small().into(),
+            hardware,
+            !use_hot,
+            false,
+        ))
+        .push(variant(
+            "Primary key on this computer",
+            text(prompt::HOT_PRIMARY_KEY_WARNING)
+                .small()
+                .style(color::ORANGE)
+                .into(),
+            hot,
+            use_hot,
+            true,
+        ))
+        .into()
+}
+
+/// The input of the alias map of the keys, pasted or loaded from a file.
+fn key_aliases_input<'a>(aliases_input: &form::Value<String>) -> Element<'a, Message> {
+    Column::new()
+        .spacing(10)
+        .push(text("Key aliases:").bold())
+        .push(
+            form::Form::new("Alias map or path of its file", aliases_input, |msg| {
+                Message::DefineDescriptor(message::DefineDescriptor::KeyAliasesEdited(msg))
+            })
+            .warning("Please enter a valid alias map or the path of an alias map file")
+            .size(20)
+            .padding(10),
+        )
+        .push(text(prompt::KEY_ALIASES_HELP).small())
+        .into()
+}
+
+fn descriptor_preview<'a>(
+    preview: Result<String, String>,
+    policy_summary: Vec<String>,
+    witness_sizes: Vec<(String, bool)>,
+) -> Element<'a, Message> {
+    card::simple(
+        Column::new()
+            .spacing(10)
+            .push(text("Preview:").bold())
+            .push(
+                policy_summary
+                    .into_iter()
+                    .fold(Column::new().spacing(5), |col, line| {
+                        col.push(text(line).small())
+                    }),
+            )
+            .push::<Element<'a, Message>>(match preview {
+                Ok(desc) => scrollable(Container::new(text(desc).small()).padding(10))
+                    .horizontal_scroll(Properties::new().width(3).scroller_width(3))
+                    .into(),
+                Err(e) => text(format!("Invalid policy: {}", e))
+                    .small()
+                    .style(color::RED)
+                    .into(),
+            })
+            .push_maybe(if witness_sizes.is_empty() {
+                None
+            } else {
+                Some(witness_sizes_view(witness_sizes))
+            }),
+    )
+    .width(Length::Fill)
+    .into()
+}
+
+/// The estimated witness size of each spending path, flagging the ones whose spends would not
+/// be relayed by standard nodes.
+fn witness_sizes_view<'a>(witness_sizes: Vec<(String, bool)>) -> Element<'a, Message> {
+    let non_standard = witness_sizes.iter().any(|(_, standard)| !standard);
+    witness_sizes
+        .into_iter()
+        .fold(
+            Column::new()
+                .spacing(5)
+                .push(text("Estimated spending sizes:").small().bold()),
+            |col, (line, standard)| {
+                col.push(if standard {
+                    text(line).small()
+                } else {
+                    text(line).small().style(color::RED)
+                })
+            },
+        )
+        .push_maybe(if non_standard {
+            Some(
+                text(prompt::NON_STANDARD_WITNESS_WARNING)
+                    .small()
+                    .style(color::RED),
+            )
+        } else {
+            None
+        })
+        .into()
+}
+
+/// A security warning with the checkbox to acknowledge it.
+pub fn security_warning<'a>(
+    warning: SecurityWarning,
+    input: String,
+    acknowledged: bool,
+) -> Element<'a, Message> {
+    card::simple(
+        Column::new()
+            .spacing(10)
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .push(icon::warning_icon().style(color::ORANGE))
+                    .push(text(warning.message()).small()),
+            )
+            .push(checkbox(
+                "I understand the risk",
+                acknowledged,
+                move |acknowledged| {
+                    Message::AcknowledgeWarning(warning, input.clone(), acknowledged)
+                },
+            )),
+    )
+    .width(Length::Fill)
+    .into()
+}
+
 pub fn recovery_path_view(
     sequence: u16,
     duplicate_sequence: bool,
@@ -349,12 +596,102 @@ pub fn recovery_path_view(
     .into()
 }
 
+pub fn verify_descriptor<'a>(
+    progress: (usize, usize),
+    descriptor: &form::Value<String>,
+    analysis: Option<&Result<DescriptorAnalysis, String>>,
+) -> Element<'a, Message> {
+    let lines = |title: &'static str, lines: Vec<String>| {
+        lines.into_iter().fold(
+            Column::new().spacing(5).push(text(title).small().bold()),
+            |col, line| col.push(text(line).small()),
+        )
+    };
+    layout(
+        progress,
+        "Verify a descriptor",
+        Column::new()
+            .push(text(prompt::VERIFY_DESCRIPTOR_HELP))
+            .push(
+                form::Form::new("Descriptor", descriptor, |msg| {
+                    Message::DefineDescriptor(message::DefineDescriptor::ImportDescriptor(msg))
+                })
+                .warning("Invalid descriptor")
+                .size(20)
+                .padding(10),
+            )
+            .push_maybe(analysis.map(|analysis| {
+                match analysis {
+                    Err(e) => card::invalid(text(e.to_string()).small()).width(Length::Fill),
+                    Ok(analysis) => card::simple(
+                        Column::new()
+                            .spacing(20)
+                            .push(
+                                text(if analysis.checksum_provided {
+                                    format!("Valid checksum: {}", analysis.checksum)
+                                } else {
+                                    format!(
+                                        "No checksum provided, computed checksum: {}",
+                                        analysis.checksum
+                                    )
+                                })
+                                .small()
+                                .bold(),
+                            )
+                            .push(lines("Policy:", analysis.policy_summary.clone()))
+                            .push(
+                                scrollable(analysis.paths_policies.iter().fold(
+                                    Column::new().spacing(5).padding(10),
+                                    |col, (path, policy)| {
+                                        col.push(text(format!("{}: {}", path, policy)).small())
+                                    },
+                                ))
+                                .horizontal_scroll(Properties::new().width(3).scroller_width(3)),
+                            )
+                            .push(lines("Keys:", analysis.key_origins.clone()))
+                            .push(lines(
+                                if analysis.network == bitcoin::Network::Bitcoin {
+                                    "First receive addresses:"
+                                } else {
+                                    "First receive addresses (testnet and signet):"
+                                },
+                                analysis.receive_addresses.clone(),
+                            ))
+                            .push(lines(
+                                "First change addresses:",
+                                analysis.change_addresses.clone(),
+                            ))
+                            .push(witness_sizes_view(analysis.witness_sizes.clone())),
+                    )
+                    .width(Length::Fill),
+                }
+            }))
+            .spacing(20),
+        true,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn import_descriptor<'a>(
     progress: (usize, usize),
     change_network: bool,
     network: bitcoin::Network,
     network_valid: bool,
     imported_descriptor: &form::Value<String>,
+    imported_psbt: &form::Value<String>,
+    psbt_xpubs: &form::Value<String>,
+    psbt_error: Option<&String>,
+    witness_sizes: Vec<(String, bool)>,
+    network_mismatch: Option<Result<Option<bitcoin::Network>, String>>,
+    policy_summary: Vec<String>,
+    aliases_input: &form::Value<String>,
+    core_wallet: (
+        &form::Value<String>,
+        &form::Value<String>,
+        &form::Value<String>,
+        bool,
+        Option<&'a Result<Vec<String>, Error>>,
+    ),
     error: Option<&String>,
 ) -> Element<'a, Message> {
     let row_network = Row::new()
@@ -387,6 +724,61 @@ pub fn import_descriptor<'a>(
             .size(20)
             .padding(10),
         )
+        .push(text(prompt::DESCRIPTOR_PAYLOAD_HELP).small())
+        .push_maybe(match network_mismatch {
+            Some(Err(e)) => Some(Container::new(text(e).small().style(color::RED))),
+            Some(Ok(Some(expected))) => Some(Container::new(
+                Row::new()
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .push(
+                        text(if expected == bitcoin::Network::Bitcoin {
+                            "The keys of this descriptor are for the main Bitcoin network."
+                        } else {
+                            "The keys of this descriptor are for a test network."
+                        })
+                        .small()
+                        .style(color::ORANGE),
+                    )
+                    .push_maybe(if change_network {
+                        Some(
+                            button::border(None, "Switch network")
+                                .on_press(Message::Network(expected)),
+                        )
+                    } else {
+                        None
+                    }),
+            )),
+            _ => None,
+        })
+        .spacing(10);
+    let col_psbt = Column::new()
+        .push(text("Or reconstruct it from a PSBT spending from the wallet:").bold())
+        .push(
+            form::Form::new("Base64 encoded PSBT", imported_psbt, |msg| {
+                Message::DefineDescriptor(message::DefineDescriptor::PsbtEdited(msg))
+            })
+            .warning("Please enter a base64 encoded PSBT")
+            .size(20)
+            .padding(10),
+        )
+        .push(text("Extended keys with origin missing from the PSBT, if any:").small())
+        .push(
+            form::Form::new("[fingerprint/path]xpub", psbt_xpubs, |msg| {
+                Message::DefineDescriptor(message::DefineDescriptor::PsbtXpubsEdited(msg))
+            })
+            .warning("Please enter extended keys with their origin")
+            .size(20)
+            .padding(10),
+        )
+        .push(
+            button::secondary(None, "Reconstruct the descriptor")
+                .on_press(Message::DefineDescriptor(
+                    message::DefineDescriptor::ImportFromPsbt,
+                ))
+                .width(Length::Units(250)),
+        )
+        .push_maybe(psbt_error.map(|e| text(e.to_string()).small().style(color::RED)))
         .spacing(10);
     layout(
         progress,
@@ -400,21 +792,130 @@ pub fn import_descriptor<'a>(
                     } else {
                         None
                     })
-                    .push(col_descriptor),
+                    .push(col_descriptor)
+                    .push_maybe(if policy_summary.is_empty() {
+                        None
+                    } else {
+                        Some(
+                            policy_summary
+                                .into_iter()
+                                .fold(Column::new().spacing(5), |col, line| {
+                                    col.push(text(line).small())
+                                }),
+                        )
+                    })
+                    .push_maybe(if witness_sizes.is_empty() {
+                        None
+                    } else {
+                        Some(witness_sizes_view(witness_sizes))
+                    })
+                    .push(key_aliases_input(aliases_input))
+                    .push(col_psbt)
+                    .push(core_wallet_import(core_wallet)),
             )
-            .push(if imported_descriptor.value.is_empty() {
-                button::primary(None, "Next").width(Length::Units(200))
-            } else {
-                button::primary(None, "Next")
-                    .width(Length::Units(200))
-                    .on_press(Message::Next)
-            })
+            .push(Row::new().spacing(10).push(reset_button()).push(
+                if imported_descriptor.value.is_empty() {
+                    button::primary(None, "Next")
+                        .width(Length::Units(200))
+                        .into()
+                } else {
+                    button::debounced(
+                        || button::primary(None, "Next").width(Length::Units(200)),
+                        Message::Next,
+                    )
+                },
+            ))
             .push_maybe(error.map(|e| card::error("Invalid descriptor", e.to_string())))
             .spacing(50),
         true,
     )
 }
 
+/// The import of the descriptor from a wallet of bitcoind already watching it.
+fn core_wallet_import<'a>(
+    (address, cookie_path, wallet, fetching, descriptors): (
+        &form::Value<String>,
+        &form::Value<String>,
+        &form::Value<String>,
+        bool,
+        Option<&'a Result<Vec<String>, Error>>,
+    ),
+) -> Element<'a, Message> {
+    let edit = |msg| Message::DefineDescriptor(message::DefineDescriptor::CoreWallet(msg));
+    Column::new()
+        .spacing(10)
+        .push(text("Or import it from a Bitcoin Core wallet watching it:").bold())
+        .push(
+            Row::new()
+                .spacing(10)
+                .push(
+                    form::Form::new("Address", address, move |value| {
+                        edit(message::CoreWallet::AddressEdited(value))
+                    })
+                    .warning("Please enter a correct address")
+                    .size(20)
+                    .padding(10),
+                )
+                .push(
+                    form::Form::new("Cookie path", cookie_path, move |value| {
+                        edit(message::CoreWallet::CookiePathEdited(value))
+                    })
+                    .warning("Please enter a correct path")
+                    .size(20)
+                    .padding(10),
+                ),
+        )
+        .push(
+            form::Form::new("Wallet name, if several are loaded", wallet, move |value| {
+                edit(message::CoreWallet::WalletEdited(value))
+            })
+            .size(20)
+            .padding(10),
+        )
+        .push(if fetching {
+            button::secondary(None, "Listing the descriptors ...").width(Length::Units(250))
+        } else {
+            button::secondary(None, "List the descriptors")
+                .on_press(edit(message::CoreWallet::ListDescriptors))
+                .width(Length::Units(250))
+        })
+        .push_maybe(descriptors.map(|res| {
+            match res {
+                Err(e) => Element::from(text(e.to_string()).small().style(color::RED)),
+                Ok(descs) if descs.is_empty() => {
+                    text("No Liana descriptor was found in this wallet")
+                        .small()
+                        .style(color::RED)
+                        .into()
+                }
+                Ok(descs) => descs
+                    .iter()
+                    .fold(
+                        Column::new()
+                            .spacing(5)
+                            .push(text("Liana descriptors of the wallet:").small()),
+                        |col, desc| {
+                            col.push(
+                                Row::new()
+                                    .spacing(10)
+                                    .align_items(Alignment::Center)
+                                    .push(Container::new(text(desc).small()).width(Length::Fill))
+                                    .push(button::border(None, "Import").on_press(
+                                        Message::DefineDescriptor(
+                                            message::DefineDescriptor::ImportDescriptor(
+                                                desc.clone(),
+                                            ),
+                                        ),
+                                    )),
+                            )
+                        },
+                    )
+                    .into(),
+            }
+        }))
+        .into()
+}
+
 pub fn signer_xpubs(xpubs: &Vec<String>) -> Element<Message> {
     Container::new(
         Column::new()
@@ -635,11 +1136,12 @@ pub fn participate_xpub<'a>(
                 Message::UserActionDone,
             ))
             .push(if shared {
-                button::primary(None, "Next")
-                    .width(Length::Units(200))
-                    .on_press(Message::Next)
+                button::debounced(
+                        || button::primary(None, "Next").width(Length::Units(200)),
+                        Message::Next,
+                    )
             } else {
-                button::primary(None, "Next").width(Length::Units(200))
+                button::primary(None, "Next").width(Length::Units(200)).into()
             })
             .spacing(50),
         true
@@ -650,9 +1152,22 @@ pub fn participate_xpub<'a>(
 pub fn register_descriptor<'a>(
     progress: (usize, usize),
     descriptor: String,
+    first_address: Option<&String>,
+    cosigners: Vec<(Fingerprint, Option<&String>, RegistrationFormat, bool, bool)>,
     hws: &'a [HardwareWallet],
     registered: &HashSet<bitcoin::util::bip32::Fingerprint>,
-    error: Option<&Error>,
+    failed: Vec<(Fingerprint, Option<&String>, &Error, Option<usize>)>,
+    enumeration_errors: Option<&Vec<String>>,
+    manual_entry: Option<(
+        &'a form::Value<String>,
+        Option<Result<(Fingerprint, Option<&String>), &'a str>>,
+    )>,
+    attestations: Option<(
+        &String,
+        Vec<(Fingerprint, Option<&String>, String, bool)>,
+        &'a form::Value<String>,
+        &[String],
+    )>,
     processing: bool,
     chosen_hw: Option<usize>,
     done: bool,
@@ -665,6 +1180,11 @@ pub fn register_descriptor<'a>(
                 Column::new()
                     .push(text("The descriptor:").small().bold())
                     .push(text(descriptor.clone()).small())
+                    .push_maybe(
+                        first_address.map(|address| {
+                            text(format!("First receive address: {}", address)).small()
+                        }),
+                    )
                     .push(
                         Row::new().push(Column::new().width(Length::Fill)).push(
                             button::secondary(Some(icon::clipboard_icon()), "Copy")
@@ -674,7 +1194,32 @@ pub fn register_descriptor<'a>(
                     .spacing(10),
             ))
             .push(text(prompt::REGISTER_DESCRIPTOR_HELP))
-            .push_maybe(error.map(|e| card::error("Failed to register descriptor", e.to_string())))
+            .push(failed.into_iter().fold(
+                Column::new().spacing(10),
+                |col, (fingerprint, alias, error, connected)| {
+                    col.push(failed_registration(
+                        fingerprint,
+                        alias,
+                        error,
+                        connected,
+                        processing,
+                    ))
+                },
+            ))
+            .push_maybe(if cosigners.is_empty() {
+                None
+            } else {
+                Some(cosigners_registration(cosigners))
+            })
+            .push_maybe(
+                attestations.and_then(|(message, cosigners, input, errors)| {
+                    if cosigners.is_empty() {
+                        None
+                    } else {
+                        Some(cosigners_attestations(message, cosigners, input, errors))
+                    }
+                }),
+            )
             .push(
                 Column::new()
                     .push(
@@ -709,6 +1254,10 @@ pub fn register_descriptor<'a>(
                                 ))
                             }),
                     )
+                    .push_maybe(enumeration_errors.map(|errors| {
+                        undetected_devices(errors, manual_entry.is_some(), processing)
+                    }))
+                    .push_maybe(manual_entry.map(|(key, device)| manual_registration(key, device)))
                     .width(Length::Fill),
             )
             .push(checkbox(
@@ -717,124 +1266,696 @@ pub fn register_descriptor<'a>(
                 Message::UserActionDone,
             ))
             .push(if done && !processing {
+                button::debounced(
+                    || button::primary(None, "Next").width(Length::Units(200)),
+                    Message::Next,
+                )
+            } else {
                 button::primary(None, "Next")
-                    .on_press(Message::Next)
                     .width(Length::Units(200))
-            } else {
-                button::primary(None, "Next").width(Length::Units(200))
+                    .into()
             })
             .spacing(50),
         true,
     )
 }
 
-pub fn backup_descriptor<'a>(
-    progress: (usize, usize),
-    descriptor: String,
-    done: bool,
+/// The checklist of the cosigners who confirmed they registered the descriptor, with the input
+/// of the signatures of their confirmations.
+fn cosigners_attestations<'a>(
+    message: &String,
+    cosigners: Vec<(Fingerprint, Option<&String>, String, bool)>,
+    input: &'a form::Value<String>,
+    errors: &[String],
 ) -> Element<'a, Message> {
-    layout(
-        progress,
-        "Backup your wallet descriptor",
+    let remaining = cosigners
+        .iter()
+        .filter(|(_, _, _, confirmed)| !confirmed)
+        .count();
+    card::simple(
         Column::new()
+            .spacing(10)
+            .push(text("Confirmations of the cosigners:").small().bold())
+            .push(text(prompt::ATTESTATION_HELP).small())
             .push(
-                Column::new()
-                    .push(text(prompt::BACKUP_DESCRIPTOR_MESSAGE))
-                    .push(collapse::Collapse::new(
-                        || {
-                            Button::new(
-                                Row::new()
-                                    .align_items(Alignment::Center)
-                                    .spacing(10)
-                                    .push(text("Learn more").small().bold())
-                                    .push(icon::collapse_icon()),
-                            )
-                            .style(theme::Button::Transparent)
-                        },
-                        || {
-                            Button::new(
-                                Row::new()
-                                    .align_items(Alignment::Center)
-                                    .spacing(10)
-                                    .push(text("Learn more").small().bold())
-                                    .push(icon::collapsed_icon()),
-                            )
-                            .style(theme::Button::Transparent)
-                        },
-                        help_backup,
-                    ))
-                    .max_width(1000),
-            )
-            .push(card::simple(
-                Column::new()
-                    .push(text("The descriptor:").small().bold())
-                    .push(text(descriptor.clone()).small())
+                Row::new()
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .push(Container::new(text(message.clone()).small().bold()).width(Length::Fill))
                     .push(
-                        Row::new().push(Column::new().width(Length::Fill)).push(
-                            button::secondary(Some(icon::clipboard_icon()), "Copy")
-                                .on_press(Message::Clibpboard(descriptor)),
+                        button::secondary(Some(icon::clipboard_icon()), "Copy message")
+                            .on_press(Message::Clibpboard(message.clone())),
+                    ),
+            )
+            .push(cosigners.into_iter().fold(
+                Column::new().spacing(5),
+                |col, (fingerprint, alias, path, confirmed)| {
+                    col.push(
+                        Row::new()
+                            .spacing(10)
+                            .align_items(Alignment::Center)
+                            .push(if confirmed {
+                                icon::circle_check_icon().style(color::GREEN)
+                            } else {
+                                icon::circle_cross_icon().style(color::ORANGE)
+                            })
+                            .push(
+                                text(format!(
+                                    "{}: {}, signing with the key at {}",
+                                    alias
+                                        .map(|a| a.to_string())
+                                        .unwrap_or_else(|| format!("#{}", fingerprint)),
+                                    if confirmed {
+                                        "confirmed"
+                                    } else {
+                                        "not confirmed yet"
+                                    },
+                                    path
+                                ))
+                                .small(),
+                            ),
+                    )
+                },
+            ))
+            .push(
+                text(if remaining == 0 {
+                    "Every cosigner confirmed the registration.".to_string()
+                } else {
+                    format!(
+                        "{} cosigner(s) did not confirm the registration.",
+                        remaining
+                    )
+                })
+                .small()
+                .style(if remaining == 0 {
+                    color::GREEN
+                } else {
+                    color::ORANGE
+                }),
+            )
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .push(
+                        form::Form::new("Signatures, one per line", input, |msg| {
+                            Message::RegisterDescriptor(
+                                message::RegisterDescriptor::AttestationsEdited(msg),
+                            )
+                        })
+                        .size(15)
+                        .padding(10),
+                    )
+                    .push(
+                        button::secondary(None, "Import").on_press(Message::RegisterDescriptor(
+                            message::RegisterDescriptor::ImportAttestations,
+                        )),
+                    ),
+            )
+            .push(errors.iter().fold(Column::new().spacing(5), |col, e| {
+                col.push(text(e.clone()).small().style(color::RED))
+            })),
+    )
+    .into()
+}
+
+/// No device could be listed. If errors were met the enumeration itself failed, the user is
+/// given some troubleshooting tips and the option to go on without the device being detected.
+fn undetected_devices<'a>(
+    errors: &[String],
+    manual_entry: bool,
+    processing: bool,
+) -> Element<'a, Message> {
+    let mut col = Column::new().spacing(10);
+    if !errors.is_empty() {
+        col = col
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .push(icon::warning_icon().style(color::ORANGE))
+                    .push(text("Failed to list the signing devices").bold()),
+            )
+            .push(errors.iter().fold(Column::new().spacing(5), |col, e| {
+                col.push(text(e.to_string()).small().style(color::ORANGE))
+            }))
+            .push(text(prompt::DEVICE_ENUMERATION_FAILED_HELP).small());
+    }
+    col.push(
+        Row::new()
+            .spacing(10)
+            .align_items(Alignment::Center)
+            .push(Container::new(text("Your device is not detected?").small()).width(Length::Fill))
+            .push(if manual_entry {
+                button::secondary(None, "Cancel").on_press(Message::RegisterDescriptor(
+                    message::RegisterDescriptor::ManualEntry(false),
+                ))
+            } else if processing {
+                button::secondary(None, "Enter it by hand")
+            } else {
+                button::secondary(None, "Enter it by hand").on_press(Message::RegisterDescriptor(
+                    message::RegisterDescriptor::ManualEntry(true),
+                ))
+            }),
+    )
+    .into()
+}
+
+/// The key reported by a device entered by hand, matched against the keys of the descriptor
+/// for the user to confirm the registration made with a file.
+fn manual_registration<'a>(
+    key: &'a form::Value<String>,
+    device: Option<Result<(Fingerprint, Option<&String>), &'a str>>,
+) -> Element<'a, Message> {
+    let warning = match device {
+        Some(Err(e)) => e,
+        _ => "Invalid key",
+    };
+    card::simple(
+        Column::new()
+            .spacing(10)
+            .push(text(prompt::MANUAL_REGISTRATION_HELP).small())
+            .push(
+                form::Form::new("Fingerprint or extended public key", key, |msg| {
+                    Message::RegisterDescriptor(message::RegisterDescriptor::ManualKeyEdited(msg))
+                })
+                .warning(warning)
+                .size(20)
+                .padding(10),
+            )
+            .push_maybe(if let Some(Ok((fingerprint, alias))) = device {
+                Some(
+                    Row::new()
+                        .spacing(10)
+                        .align_items(Alignment::Center)
+                        .push(
+                            Container::new(
+                                text(format!(
+                                    "Matches the key of {}",
+                                    alias
+                                        .map(|a| format!("{} ({})", a, fingerprint))
+                                        .unwrap_or_else(|| fingerprint.to_string())
+                                ))
+                                .small()
+                                .style(color::GREEN),
+                            )
+                            .width(Length::Fill),
+                        )
+                        .push(
+                            button::primary(None, "I have registered the descriptor").on_press(
+                                Message::RegisterDescriptor(
+                                    message::RegisterDescriptor::ManuallyRegistered(fingerprint),
+                                ),
+                            ),
                         ),
+                )
+            } else {
+                None
+            }),
+    )
+    .into()
+}
+
+/// The registration interrupted on a device, with the option to retry once the device is
+/// connected again.
+fn failed_registration<'a>(
+    fingerprint: Fingerprint,
+    alias: Option<&String>,
+    error: &Error,
+    connected: Option<usize>,
+    processing: bool,
+) -> Element<'a, Message> {
+    card::invalid(
+        Column::new()
+            .spacing(10)
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .push(icon::warning_icon().style(color::RED))
+                    .push(
+                        text(format!(
+                            "Failed to register the descriptor on {}",
+                            alias
+                                .map(|a| a.to_string())
+                                .unwrap_or_else(|| fingerprint.to_string())
+                        ))
+                        .bold(),
+                    ),
+            )
+            .push(text(error.to_string()).small())
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .push(
+                        Container::new(
+                            text(if connected.is_some() {
+                                "The device is connected, the registration can be retried."
+                            } else {
+                                "Reconnect the device and refresh the list to retry."
+                            })
+                            .small(),
+                        )
+                        .width(Length::Fill),
                     )
+                    .push(match connected {
+                        Some(i) if !processing => {
+                            button::secondary(Some(icon::reload_icon()), "Retry")
+                                .on_press(Message::Select(i))
+                        }
+                        _ => button::secondary(Some(icon::reload_icon()), "Retry"),
+                    }),
+            ),
+    )
+    .into()
+}
+
+/// The registration status of every cosigner device, with the export of the file to register
+/// the descriptor on the devices not present.
+fn cosigners_registration<'a>(
+    cosigners: Vec<(Fingerprint, Option<&String>, RegistrationFormat, bool, bool)>,
+) -> Element<'a, Message> {
+    let remaining = cosigners
+        .iter()
+        .filter(|(_, _, _, registered, exported)| !registered && !exported)
+        .count();
+    card::simple(
+        cosigners.into_iter().fold(
+            Column::new()
+                .spacing(10)
+                .push(text("Cosigners devices:").small().bold())
+                .push(
+                    text(if remaining == 0 {
+                        "The descriptor was registered or exported for every device.".to_string()
+                    } else {
+                        format!(
+                            "{} device(s) still need the descriptor registered.",
+                            remaining
+                        )
+                    })
+                    .small(),
+                ),
+            |col, (fingerprint, alias, format, registered, exported)| {
+                col.push(
+                    Row::new()
+                        .spacing(10)
+                        .align_items(Alignment::Center)
+                        .push(
+                            Column::new()
+                                .width(Length::Fill)
+                                .push(
+                                    text(
+                                        alias
+                                            .map(|a| a.to_string())
+                                            .unwrap_or_else(|| fingerprint.to_string()),
+                                    )
+                                    .bold(),
+                                )
+                                .push(
+                                    text(if registered {
+                                        "Registered"
+                                    } else if exported {
+                                        "Registration file exported"
+                                    } else {
+                                        "Not registered yet"
+                                    })
+                                    .small()
+                                    .style(
+                                        if registered || exported {
+                                            color::GREEN
+                                        } else {
+                                            color::ORANGE
+                                        },
+                                    ),
+                                ),
+                        )
+                        .push_maybe(if registered {
+                            None
+                        } else {
+                            Some(
+                                Row::new()
+                                    .spacing(10)
+                                    .align_items(Alignment::Center)
+                                    .push(
+                                        pick_list(
+                                            &RegistrationFormat::ALL[..],
+                                            Some(format),
+                                            move |f| {
+                                                Message::RegisterDescriptor(
+                                                    message::RegisterDescriptor::FormatSelected(
+                                                        fingerprint,
+                                                        f,
+                                                    ),
+                                                )
+                                            },
+                                        )
+                                        .padding(10),
+                                    )
+                                    .push(
+                                        button::secondary(
+                                            Some(icon::clipboard_icon()),
+                                            "Copy registration file",
+                                        )
+                                        .on_press(
+                                            Message::RegisterDescriptor(
+                                                message::RegisterDescriptor::ExportArtifact(
+                                                    fingerprint,
+                                                ),
+                                            ),
+                                        ),
+                                    ),
+                            )
+                        }),
+                )
+            },
+        ),
+    )
+    .into()
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn backup_descriptor<'a>(
+    progress: (usize, usize),
+    split: bool,
+    with_checksum: bool,
+    exported: Result<&'a Vec<(&'static str, String)>, &'a String>,
+    qr_codes: &'a [Option<qr_code::State>],
+    aliases: Option<&String>,
+    copy_acknowledged: bool,
+    backup_file: (
+        &form::Value<String>,
+        Option<&'a Result<std::path::PathBuf, Error>>,
+        Option<&'a Result<(), String>>,
+    ),
+    done: bool,
+) -> Element<'a, Message> {
+    layout(
+        progress,
+        "Backup your wallet descriptor",
+        Column::new()
+            .push(
+                Column::new()
+                    .push(text(prompt::BACKUP_DESCRIPTOR_MESSAGE))
+                    .push(collapse::Collapse::new(
+                        || {
+                            Button::new(
+                                Row::new()
+                                    .align_items(Alignment::Center)
+                                    .spacing(10)
+                                    .push(text("Learn more").small().bold())
+                                    .push(icon::collapse_icon()),
+                            )
+                            .style(theme::Button::Transparent)
+                        },
+                        || {
+                            Button::new(
+                                Row::new()
+                                    .align_items(Alignment::Center)
+                                    .spacing(10)
+                                    .push(text("Learn more").small().bold())
+                                    .push(icon::collapsed_icon()),
+                            )
+                            .style(theme::Button::Transparent)
+                        },
+                        help_backup,
+                    ))
+                    .max_width(1000),
+            )
+            .push(
+                Column::new()
                     .spacing(10)
+                    .push(text("Export format:").small().bold())
+                    .push(
+                        Row::new()
+                            .spacing(20)
+                            .push(checkbox(
+                                "Split receive and change descriptors",
+                                split,
+                                |split| {
+                                    Message::BackupDescriptor(
+                                        message::BackupDescriptor::SplitToggled(split),
+                                    )
+                                },
+                            ))
+                            .push(checkbox("Include checksum", with_checksum, |checksum| {
+                                Message::BackupDescriptor(
+                                    message::BackupDescriptor::ChecksumToggled(checksum),
+                                )
+                            })),
+                    )
                     .max_width(1000),
+            )
+            .push(security_warning(
+                SecurityWarning::DescriptorCopy,
+                String::new(),
+                copy_acknowledged,
             ))
+            .push(match exported {
+                Err(e) => Element::<Message>::from(card::invalid(text(e).small())),
+                Ok(exported) => exported
+                    .iter()
+                    .enumerate()
+                    .fold(
+                        Column::new().spacing(10),
+                        |col, (i, (label, descriptor))| {
+                            col.push(
+                                card::simple(
+                                    Row::new()
+                                        .spacing(10)
+                                        .push(
+                                            Column::new()
+                                                .width(Length::Fill)
+                                                .push(text(format!("{}:", label)).small().bold())
+                                                .push(text(descriptor).small())
+                                                .push(
+                                                    Row::new()
+                                                        .push(Column::new().width(Length::Fill))
+                                                        .push(if copy_acknowledged {
+                                                            button::secondary(
+                                                                Some(icon::clipboard_icon()),
+                                                                "Copy",
+                                                            )
+                                                            .on_press(Message::Clibpboard(
+                                                                descriptor.clone(),
+                                                            ))
+                                                        } else {
+                                                            button::secondary(
+                                                                Some(icon::clipboard_icon()),
+                                                                "Copy",
+                                                            )
+                                                        }),
+                                                )
+                                                .spacing(10),
+                                        )
+                                        .push(if let Some(Some(qr)) = qr_codes.get(i) {
+                                            Container::new(
+                                                Container::new(
+                                                    qr_code::QRCode::new(qr).cell_size(3),
+                                                )
+                                                .padding(10)
+                                                .style(theme::Container::QrCode),
+                                            )
+                                        } else {
+                                            Container::new(
+                                                text("Too long to be displayed as a QR code")
+                                                    .small(),
+                                            )
+                                            .width(Length::Units(200))
+                                        }),
+                                )
+                                .max_width(1000),
+                            )
+                        },
+                    )
+                    .into(),
+            })
+            .push_maybe(aliases.map(|aliases| {
+                card::simple(
+                    Column::new()
+                        .spacing(10)
+                        .push(text("Key aliases:").small().bold())
+                        .push(text(prompt::KEY_ALIASES_BACKUP_HELP).small())
+                        .push(text(aliases.clone()).small())
+                        .push(
+                            Row::new().push(Column::new().width(Length::Fill)).push(
+                                button::secondary(Some(icon::clipboard_icon()), "Copy")
+                                    .on_press(Message::Clibpboard(aliases.clone())),
+                            ),
+                        ),
+                )
+                .max_width(1000)
+            }))
+            .push_maybe(if exported.is_ok() {
+                Some(backup_file_options(backup_file))
+            } else {
+                None
+            })
             .push(checkbox(
                 "I have backed up my descriptor",
                 done,
                 Message::UserActionDone,
             ))
             .push(if done {
+                button::debounced(
+                    || button::primary(None, "Next").width(Length::Units(200)),
+                    Message::Next,
+                )
+            } else {
                 button::primary(None, "Next")
-                    .on_press(Message::Next)
                     .width(Length::Units(200))
-            } else {
-                button::primary(None, "Next").width(Length::Units(200))
+                    .into()
             })
             .spacing(50),
         true,
     )
 }
 
-pub fn help_backup<'a>() -> Element<'a, Message> {
-    text(prompt::BACKUP_DESCRIPTOR_HELP).small().into()
-}
-
-pub fn define_bitcoin<'a>(
-    progress: (usize, usize),
-    address: &form::Value<String>,
-    cookie_path: &form::Value<String>,
-    is_running: Option<&Result<(), Error>>,
+/// Save the exported descriptor to a file, and read it back to check it holds the descriptor.
+fn backup_file_options<'a>(
+    (path, saved, verification): (
+        &form::Value<String>,
+        Option<&'a Result<std::path::PathBuf, Error>>,
+        Option<&'a Result<(), String>>,
+    ),
 ) -> Element<'a, Message> {
-    let col_address = Column::new()
-        .push(text("Address:").bold())
-        .push(
-            form::Form::new("Address", address, |msg| {
-                Message::DefineBitcoind(message::DefineBitcoind::AddressEdited(msg))
-            })
-            .warning("Please enter correct address")
-            .size(20)
-            .padding(10),
-        )
-        .spacing(10);
+    card::simple(
+        Column::new()
+            .spacing(10)
+            .push(text("Backup file:").small().bold())
+            .push(text(prompt::BACKUP_FILE_HELP).small())
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .push(
+                        form::Form::new("/path/to/descriptor.txt", path, |value| {
+                            Message::BackupDescriptor(message::BackupDescriptor::BackupPathEdited(
+                                value,
+                            ))
+                        })
+                        .warning("Please enter an absolute path in an existing directory")
+                        .size(20)
+                        .padding(5),
+                    )
+                    .push(
+                        button::secondary(None, "Save").on_press(Message::BackupDescriptor(
+                            message::BackupDescriptor::SaveBackup,
+                        )),
+                    )
+                    .push(if let Some(Ok(_)) = saved {
+                        button::secondary(None, "Verify backup").on_press(
+                            Message::BackupDescriptor(message::BackupDescriptor::VerifyBackup),
+                        )
+                    } else {
+                        button::secondary(None, "Verify backup")
+                    }),
+            )
+            .push_maybe(saved.map(|res| match res {
+                Ok(path) => text(format!("Descriptor saved to {}", path.to_string_lossy())).small(),
+                Err(e) => text(e.to_string()).small().style(color::RED),
+            }))
+            .push_maybe(verification.map(|res| {
+                match res {
+                    Ok(()) => Row::new()
+                        .spacing(5)
+                        .align_items(Alignment::Center)
+                        .push(icon::circle_check_icon().style(color::GREEN))
+                        .push(
+                            text("Verified: the backup gives back the descriptor of the wallet")
+                                .small()
+                                .style(color::GREEN),
+                        ),
+                    Err(e) => Row::new()
+                        .spacing(5)
+                        .align_items(Alignment::Center)
+                        .push(icon::circle_cross_icon().style(color::RED))
+                        .push(
+                            text(format!("The backup cannot be relied on: {}", e))
+                                .small()
+                                .style(color::RED),
+                        ),
+                }
+            })),
+    )
+    .max_width(1000)
+    .into()
+}
+
+pub fn help_backup<'a>() -> Element<'a, Message> {
+    text(prompt::BACKUP_DESCRIPTOR_HELP).small().into()
+}
 
-    let col_cookie = Column::new()
-        .push(text("Cookie path:").bold())
+pub fn define_bitcoin<'a>(
+    progress: (usize, usize),
+    address: &form::Value<String>,
+    cookie_path: &form::Value<String>,
+    (use_rpc_auth, rpc_user, rpc_password): (bool, &form::Value<String>, &form::Value<String>),
+    is_running: Option<&Result<(), Error>>,
+    warning: Option<&String>,
+    support_summary: String,
+) -> Element<'a, Message> {
+    let col_address = Column::new()
+        .push(text("Address:").bold())
         .push(
-            form::Form::new("Cookie path", cookie_path, |msg| {
-                Message::DefineBitcoind(message::DefineBitcoind::CookiePathEdited(msg))
+            form::Form::new("Address", address, |msg| {
+                Message::DefineBitcoind(message::DefineBitcoind::AddressEdited(msg))
             })
-            .warning("Please enter correct path")
+            .warning("Please enter correct address")
             .size(20)
             .padding(10),
         )
         .spacing(10);
 
+    let col_auth = if use_rpc_auth {
+        Column::new()
+            .push(text("User and password:").bold())
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .push(
+                        form::Form::new("User", rpc_user, |msg| {
+                            Message::DefineBitcoind(message::DefineBitcoind::RpcUserEdited(msg))
+                        })
+                        .warning("Please enter the rpcuser of bitcoind")
+                        .size(20)
+                        .padding(10),
+                    )
+                    .push(
+                        form::Form::new("Password", rpc_password, |msg| {
+                            Message::DefineBitcoind(message::DefineBitcoind::RpcPasswordEdited(msg))
+                        })
+                        .warning("Please enter the rpcpassword of bitcoind")
+                        .size(20)
+                        .padding(10),
+                    ),
+            )
+            .spacing(10)
+    } else {
+        Column::new()
+            .push(text("Cookie path:").bold())
+            .push(
+                form::Form::new("Cookie path", cookie_path, |msg| {
+                    Message::DefineBitcoind(message::DefineBitcoind::CookiePathEdited(msg))
+                })
+                .warning("Please enter correct path")
+                .size(20)
+                .padding(10),
+            )
+            .spacing(10)
+    };
+
     layout(
         progress,
         "Set up connection to the Bitcoin full node",
         Column::new()
             .push(col_address)
-            .push(col_cookie)
+            .push(checkbox(
+                "Authenticate with the rpcuser and rpcpassword of bitcoind",
+                use_rpc_auth,
+                |enabled| Message::DefineBitcoind(message::DefineBitcoind::RpcAuthToggled(enabled)),
+            ))
+            .push(col_auth)
             .push_maybe(if is_running.is_some() {
                 is_running.map(|res| {
                     if res.is_ok() {
@@ -847,196 +1968,1293 @@ pub fn define_bitcoin<'a>(
                         )
                     } else {
                         Container::new(
+                            Column::new()
+                                .spacing(5)
+                                .push(
+                                    Row::new()
+                                        .spacing(10)
+                                        .align_items(Alignment::Center)
+                                        .push(icon::circle_cross_icon().style(color::RED))
+                                        .push(text("Connection failed").style(color::RED)),
+                                )
+                                .push_maybe(
+                                    res.as_ref()
+                                        .err()
+                                        .map(|e| text(e.to_string()).small().style(color::RED)),
+                                )
+                                .push_maybe(match res.as_ref().err() {
+                                    Some(Error::BitcoindRemoteRpcRefused(_)) => {
+                                        Some(text(prompt::REMOTE_RPC_TIP).small())
+                                    }
+                                    Some(Error::BitcoindWorkQueueExceeded) => {
+                                        Some(text(prompt::WORK_QUEUE_TIP).small())
+                                    }
+                                    _ => None,
+                                }),
+                        )
+                    }
+                })
+            } else {
+                Some(Container::new(Space::with_height(Length::Units(25))))
+            })
+            .push_maybe(warning.map(|w| card::warning(w.clone()).width(Length::Fill)))
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .push(
+                        button::border(
+                            Some(icon::clipboard_icon()),
+                            "Copy connection details for support",
+                        )
+                        .on_press(Message::Clibpboard(support_summary)),
+                    )
+                    .push(text(prompt::SUPPORT_SUMMARY_HELP).small()),
+            )
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .push(Container::new(button::debounced(
+                        || button::secondary(None, "Check connection").width(Length::Units(200)),
+                        Message::DefineBitcoind(message::DefineBitcoind::PingBitcoind),
+                    )))
+                    .push(reset_button())
+                    .push(if is_running.map(|res| res.is_ok()).unwrap_or(false) {
+                        button::debounced(
+                            || button::primary(None, "Next").width(Length::Units(200)),
+                            Message::Next,
+                        )
+                    } else {
+                        button::primary(None, "Next")
+                            .width(Length::Units(200))
+                            .into()
+                    }),
+            )
+            .spacing(50),
+        true,
+    )
+}
+
+pub fn define_coordinator<'a>(
+    progress: (usize, usize),
+    url: &form::Value<String>,
+    user: &form::Value<String>,
+    password: &form::Value<String>,
+    is_connected: Option<&Result<(), Error>>,
+) -> Element<'a, Message> {
+    let col_url = Column::new()
+        .push(text("Address:").bold())
+        .push(
+            form::Form::new("http://127.0.0.1:8080", url, |msg| {
+                Message::DefineCoordinator(message::DefineCoordinator::UrlEdited(msg))
+            })
+            .warning("Please enter a correct address")
+            .size(20)
+            .padding(10),
+        )
+        .spacing(10);
+
+    let col_auth = Column::new()
+        .push(text("Authentication (optional):").bold())
+        .push(
+            Row::new()
+                .spacing(10)
+                .push(
+                    form::Form::new("User", user, |msg| {
+                        Message::DefineCoordinator(message::DefineCoordinator::UserEdited(msg))
+                    })
+                    .size(20)
+                    .padding(10),
+                )
+                .push(
+                    form::Form::new("Password", password, |msg| {
+                        Message::DefineCoordinator(message::DefineCoordinator::PasswordEdited(msg))
+                    })
+                    .size(20)
+                    .padding(10),
+                ),
+        )
+        .spacing(10);
+
+    layout(
+        progress,
+        "Set up the coordination server",
+        Column::new()
+            .push(text(prompt::COORDINATOR_HELP))
+            .push(col_url)
+            .push(col_auth)
+            .push(match is_connected {
+                Some(Ok(())) => Container::new(
+                    Row::new()
+                        .spacing(10)
+                        .align_items(Alignment::Center)
+                        .push(icon::circle_check_icon().style(color::GREEN))
+                        .push(text("Connection checked").style(color::GREEN)),
+                ),
+                Some(Err(e)) => Container::new(
+                    Column::new()
+                        .spacing(5)
+                        .push(
                             Row::new()
                                 .spacing(10)
                                 .align_items(Alignment::Center)
                                 .push(icon::circle_cross_icon().style(color::RED))
                                 .push(text("Connection failed").style(color::RED)),
                         )
-                    }
-                })
+                        .push(text(e.to_string()).small().style(color::RED)),
+                ),
+                None => Container::new(Space::with_height(Length::Units(25))),
+            })
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .push(Container::new(button::debounced(
+                        || button::secondary(None, "Check connection").width(Length::Units(200)),
+                        Message::DefineCoordinator(message::DefineCoordinator::CheckConnection),
+                    )))
+                    .push(reset_button())
+                    .push(
+                        button::secondary(None, "Skip")
+                            .width(Length::Units(200))
+                            .on_press(Message::Skip),
+                    )
+                    .push(if is_connected.map(|res| res.is_ok()).unwrap_or(false) {
+                        button::debounced(
+                            || button::primary(None, "Next").width(Length::Units(200)),
+                            Message::Next,
+                        )
+                    } else {
+                        button::primary(None, "Next")
+                            .width(Length::Units(200))
+                            .into()
+                    }),
+            )
+            .spacing(50),
+        true,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn define_tor<'a>(
+    progress: (usize, usize),
+    enabled: bool,
+    control_address: &form::Value<String>,
+    cookie_path: &form::Value<String>,
+    password: &form::Value<String>,
+    onion_port: &form::Value<String>,
+    is_reachable: Option<&Result<String, Error>>,
+) -> Element<'a, Message> {
+    let col_address = Column::new()
+        .push(text("Control port:").bold())
+        .push(
+            form::Form::new("127.0.0.1:9051", control_address, |msg| {
+                Message::DefineTor(message::DefineTor::AddressEdited(msg))
+            })
+            .warning("Please enter a correct address")
+            .size(20)
+            .padding(10),
+        )
+        .spacing(10);
+
+    let col_auth = Column::new()
+        .push(text("Authentication (optional):").bold())
+        .push(
+            Row::new()
+                .spacing(10)
+                .push(
+                    form::Form::new("Cookie file path", cookie_path, |msg| {
+                        Message::DefineTor(message::DefineTor::CookiePathEdited(msg))
+                    })
+                    .size(20)
+                    .padding(10),
+                )
+                .push(
+                    form::Form::new("Password", password, |msg| {
+                        Message::DefineTor(message::DefineTor::PasswordEdited(msg))
+                    })
+                    .size(20)
+                    .padding(10),
+                ),
+        )
+        .spacing(10);
+
+    let col_port = Column::new()
+        .push(text("Port of the onion service:").bold())
+        .push(
+            form::Form::new("8080", onion_port, |msg| {
+                Message::DefineTor(message::DefineTor::OnionPortEdited(msg))
+            })
+            .warning("Please enter a correct port")
+            .size(20)
+            .padding(10),
+        )
+        .spacing(10);
+
+    let can_continue = !enabled || is_reachable.map(|res| res.is_ok()).unwrap_or(false);
+
+    layout(
+        progress,
+        "Publish the daemon over Tor",
+        Column::new()
+            .push(text(prompt::TOR_HELP))
+            .push(checkbox(
+                "Publish an onion service for the daemon",
+                enabled,
+                |enabled| Message::DefineTor(message::DefineTor::Enabled(enabled)),
+            ))
+            .push_maybe(if enabled {
+                Some(
+                    Column::new()
+                        .spacing(50)
+                        .push(col_address)
+                        .push(col_auth)
+                        .push(col_port)
+                        .push(match is_reachable {
+                            Some(Ok(version)) => Container::new(
+                                Row::new()
+                                    .spacing(10)
+                                    .align_items(Alignment::Center)
+                                    .push(icon::circle_check_icon().style(color::GREEN))
+                                    .push(
+                                        text(format!("Connected to Tor {}", version))
+                                            .style(color::GREEN),
+                                    ),
+                            ),
+                            Some(Err(e)) => Container::new(
+                                Column::new()
+                                    .spacing(5)
+                                    .push(
+                                        Row::new()
+                                            .spacing(10)
+                                            .align_items(Alignment::Center)
+                                            .push(icon::circle_cross_icon().style(color::RED))
+                                            .push(text("Connection failed").style(color::RED)),
+                                    )
+                                    .push(text(e.to_string()).small().style(color::RED)),
+                            ),
+                            None => Container::new(Space::with_height(Length::Units(25))),
+                        }),
+                )
             } else {
-                Some(Container::new(Space::with_height(Length::Units(25))))
+                None
             })
             .push(
                 Row::new()
                     .spacing(10)
-                    .push(Container::new(
-                        button::secondary(None, "Check connection")
-                            .on_press(Message::DefineBitcoind(
-                                message::DefineBitcoind::PingBitcoind,
-                            ))
-                            .width(Length::Units(200)),
-                    ))
-                    .push(if is_running.map(|res| res.is_ok()).unwrap_or(false) {
-                        button::primary(None, "Next")
-                            .on_press(Message::Next)
-                            .width(Length::Units(200))
-                    } else {
-                        button::primary(None, "Next").width(Length::Units(200))
-                    }),
+                    .push_maybe(if enabled {
+                        Some(Container::new(button::debounced(
+                            || {
+                                button::secondary(None, "Check connection")
+                                    .width(Length::Units(200))
+                            },
+                            Message::DefineTor(message::DefineTor::CheckControlPort),
+                        )))
+                    } else {
+                        None
+                    })
+                    .push(reset_button())
+                    .push(if can_continue {
+                        button::debounced(
+                            || button::primary(None, "Next").width(Length::Units(200)),
+                            Message::Next,
+                        )
+                    } else {
+                        button::primary(None, "Next")
+                            .width(Length::Units(200))
+                            .into()
+                    }),
+            )
+            .spacing(50),
+        true,
+    )
+}
+
+pub fn install<'a>(
+    progress: (usize, usize),
+    context: &Context,
+    signer: Option<(Fingerprint, &HotSignerPlacement)>,
+    first_addresses: Option<&'a (String, String)>,
+    options: Vec<Element<'a, Message>>,
+    warning: Option<&'a String>,
+    status: Element<'a, Message>,
+) -> Element<'a, Message> {
+    layout(
+        progress,
+        "Final step",
+        Column::new()
+            .push(text(
+                "Check your information before finalizing the install process:",
+            ))
+            .push(
+                Container::new(
+                    Column::new()
+                        .spacing(10)
+                        .push(
+                            card::simple(
+                                Column::new()
+                                    .spacing(5)
+                                    .push(text("Descriptor:").small().bold())
+                                    .push(
+                                        text(context.descriptor.as_ref().unwrap().to_string())
+                                            .small(),
+                                    ),
+                            )
+                            .width(Length::Fill),
+                        )
+                        .push_maybe(first_addresses.map(|(receive, change)| {
+                            card::simple(
+                                Column::new()
+                                    .spacing(5)
+                                    .push(text("First addresses:").small().bold())
+                                    .push(text(prompt::FIRST_ADDRESSES_HELP).small())
+                                    .push(
+                                        Row::new()
+                                            .spacing(5)
+                                            .push(text("Receive:").small().bold())
+                                            .push(text(receive).small()),
+                                    )
+                                    .push(
+                                        Row::new()
+                                            .spacing(5)
+                                            .push(text("Change:").small().bold())
+                                            .push(text(change).small()),
+                                    ),
+                            )
+                            .width(Length::Fill)
+                        }))
+                        .push_maybe(if context.hws.is_empty() && signer.is_none() {
+                            None
+                        } else {
+                            Some(
+                                card::simple(
+                                    Column::new()
+                                        .spacing(5)
+                                        .push(text("Registered signing devices:").small().bold())
+                                        .push_maybe(if context.hws.is_empty() {
+                                            None
+                                        } else {
+                                            Some(context.hws.iter().fold(
+                                                Column::new(),
+                                                |acc, hw| {
+                                                    acc.push(
+                                                        Row::new()
+                                                            .spacing(5)
+                                                            .push_maybe(
+                                                                context.keys.iter().find_map(|k| {
+                                                                    if k.master_fingerprint == hw.1
+                                                                    {
+                                                                        Some(
+                                                                            text(k.name.clone())
+                                                                                .small()
+                                                                                .bold(),
+                                                                        )
+                                                                    } else {
+                                                                        None
+                                                                    }
+                                                                }),
+                                                            )
+                                                            .push(
+                                                                text(format!("#{}", hw.1)).small(),
+                                                            )
+                                                            .push(text(hw.0.to_string()).small()),
+                                                    )
+                                                },
+                                            ))
+                                        })
+                                        .push_maybe(signer.map(|(fingerprint, placement)| {
+                                            Row::new()
+                                                .spacing(5)
+                                                .push_maybe(context.keys.iter().find_map(|k| {
+                                                    if k.master_fingerprint == fingerprint {
+                                                        Some(text(k.name.clone()).small().bold())
+                                                    } else {
+                                                        None
+                                                    }
+                                                }))
+                                                .push(text(format!("#{}", fingerprint)).small())
+                                                .push(text("This computer").small())
+                                                .push(
+                                                    text(format!("({})", placement.description()))
+                                                        .small(),
+                                                )
+                                        })),
+                                )
+                                .width(Length::Fill),
+                            )
+                        })
+                        .push(
+                            card::simple(
+                                Column::new()
+                                    .push(text("Bitcoind:").small().bold())
+                                    .push(
+                                        Row::new()
+                                            .spacing(5)
+                                            .align_items(Alignment::Center)
+                                            .push(text("Authentication:").small())
+                                            .push(
+                                                text(bitcoind_authentication(
+                                                    context.bitcoind_config.as_ref().unwrap(),
+                                                ))
+                                                .small(),
+                                            ),
+                                    )
+                                    .push(
+                                        Row::new()
+                                            .spacing(5)
+                                            .align_items(Alignment::Center)
+                                            .push(text("Address:").small())
+                                            .push(
+                                                text(format!(
+                                                    "{}",
+                                                    context.bitcoind_config.as_ref().unwrap().addr
+                                                ))
+                                                .small(),
+                                            ),
+                                    ),
+                            )
+                            .width(Length::Fill),
+                        )
+                        .push(Column::with_children(options).spacing(10)),
+                )
+                .max_width(1000),
+            )
+            .push(Space::with_height(Length::Units(50)))
+            .push_maybe(warning.map(|e| card::invalid(text(e))))
+            .push(status)
+            .spacing(10)
+            .width(Length::Fill),
+        true,
+    )
+}
+
+/// The button to install the wallet, or the clean up of a failed install.
+pub fn install_button<'a>(generating: bool, cleanup: Option<bool>) -> Element<'a, Message> {
+    if generating {
+        Container::new(button::primary(None, "Installing ...").width(Length::Units(200))).into()
+    } else if let Some(cleaning_up) = cleanup {
+        Container::new(
+            Column::new()
+                .spacing(10)
+                .push(text(prompt::INSTALL_CLEANUP_HELP).small())
+                .push(if cleaning_up {
+                    button::primary(None, "Cleaning up ...").width(Length::Units(200))
+                } else {
+                    button::primary(None, "Clean up")
+                        .on_press(Message::CleanUpInstall)
+                        .width(Length::Units(200))
+                }),
+        )
+        .into()
+    } else {
+        Container::new(button::debounced(
+            || button::primary(None, "Finalize installation").width(Length::Units(200)),
+            Message::Final(message::Final::Review(true)),
+        ))
+        .into()
+    }
+}
+
+/// The installed wallet, with what can be done before starting Liana. It cannot be started while
+/// the install waits for the rescan.
+pub fn installed<'a>(
+    config_path: &std::path::Path,
+    sections: Vec<Element<'a, Message>>,
+    waiting_rescan: bool,
+) -> Element<'a, Message> {
+    Container::new(
+        sections
+            .into_iter()
+            .fold(
+                Column::new().push(Container::new(text("Installed !"))),
+                |col, section| col.push(section),
+            )
+            .push(Container::new(
+                if waiting_rescan {
+                    button::primary(None, "Start")
+                } else {
+                    button::primary(None, "Start")
+                        .on_press(Message::Exit(config_path.to_path_buf(), None))
+                }
+                .width(Length::Units(200)),
+            ))
+            .align_items(Alignment::Center)
+            .spacing(20),
+    )
+    .padding(50)
+    .width(Length::Fill)
+    .center_x()
+    .into()
+}
+
+pub fn rescan_progress<'a>(progress: f64, wait: bool) -> Element<'a, Message> {
+    text(format!(
+        "Rescanning the chain: {:.2}%{}",
+        progress * 100.0,
+        if wait {
+            ""
+        } else {
+            ", it goes on in the background once Liana is started"
+        }
+    ))
+    .small()
+    .into()
+}
+
+pub fn tor_client_auth(auth: &str) -> Element<Message> {
+    card::simple(
+        Column::new()
+            .spacing(5)
+            .push(text(prompt::TOR_CLIENT_AUTH_HELP).small())
+            .push(text(auth).small().bold()),
+    )
+    .width(Length::Fill)
+    .into()
+}
+
+/// The read-only summary of every setting, to be confirmed by the user before the install.
+pub fn install_summary<'a>(
+    progress: (usize, usize),
+    context: &Context,
+    policy: Vec<String>,
+    hot_signer: Option<(Fingerprint, &HotSignerPlacement)>,
+) -> Element<'a, Message> {
+    let network = context.bitcoin_config.network;
+    let mut network_datadir = context.data_dir.clone();
+    network_datadir.push(network.to_string());
+    let key_name = |fingerprint: &Fingerprint| {
+        context
+            .keys
+            .iter()
+            .find(|k| k.master_fingerprint == *fingerprint && !k.name.is_empty())
+            .map(|k| format!("{} (#{})", k.name, fingerprint))
+            .unwrap_or_else(|| format!("#{}", fingerprint))
+    };
+
+    let mut signers: Vec<(&'static str, String)> = context
+        .hws
+        .iter()
+        .map(|(kind, fingerprint, _)| {
+            (
+                "Registered device",
+                format!("{} {}", key_name(fingerprint), kind),
+            )
+        })
+        .collect();
+    for (fingerprint, confirmed) in &context.cosigners {
+        signers.push((
+            "Cosigner device",
+            format!(
+                "{}: registration {}",
+                key_name(fingerprint),
+                if *confirmed {
+                    "confirmed"
+                } else {
+                    "not confirmed"
+                }
+            ),
+        ));
+    }
+    if let Some((fingerprint, placement)) = hot_signer {
+        signers.push((
+            "Hot signer",
+            format!(
+                "{} on this computer: {}",
+                key_name(&fingerprint),
+                placement.description().to_lowercase()
+            ),
+        ));
+    }
+    if signers.is_empty() {
+        signers.push((
+            "Signers",
+            "No key of this computer or registered device".to_string(),
+        ));
+    }
+
+    let bitcoind = context.bitcoind_config.as_ref().unwrap();
+    let import = &bitcoind.descriptor_import;
+    layout(
+        progress,
+        "Summary",
+        Column::new()
+            .push(text(prompt::INSTALL_SUMMARY_HELP))
+            .push(summary_section(
+                "Wallet",
+                Message::EditStep(SummarySection::Descriptor),
+                vec![
+                    ("Network", Network::from(network).to_string()),
+                    ("Wallet name", context.wallet_name.clone()),
+                    (
+                        "Data directory",
+                        network_datadir.to_string_lossy().to_string(),
+                    ),
+                ],
+            ))
+            .push(summary_section(
+                "Spending policy",
+                Message::EditStep(SummarySection::Descriptor),
+                policy.into_iter().map(|path| ("", path)).collect(),
+            ))
+            .push(summary_section(
+                "Signing setup",
+                if context.hw_is_used {
+                    Message::EditStep(SummarySection::Registration)
+                } else {
+                    Message::EditStep(SummarySection::Descriptor)
+                },
+                signers,
+            ))
+            .push(summary_section(
+                "Bitcoind",
+                Message::EditStep(SummarySection::Bitcoind),
+                vec![
+                    ("Address", bitcoind.addr.to_string()),
+                    ("Authentication", bitcoind_authentication(bitcoind)),
+                ],
+            ))
+            .push_maybe(context.coordinator.as_ref().map(|coordinator| {
+                summary_section(
+                    "Coordinator",
+                    Message::EditStep(SummarySection::Coordinator),
+                    vec![
+                        ("Address", coordinator.url.clone()),
+                        (
+                            "Authentication",
+                            match &coordinator.user {
+                                Some(user) => format!("User {}, password hidden", user),
+                                None => "None".to_string(),
+                            },
+                        ),
+                    ],
+                )
+            }))
+            .push_maybe(context.tor.as_ref().map(|tor| {
+                summary_section(
+                    "Tor",
+                    Message::EditStep(SummarySection::Tor),
+                    vec![
+                        ("Control port", tor.control_address.clone()),
+                        (
+                            "Authentication",
+                            match (&tor.cookie_path, &tor.password) {
+                                (Some(path), _) => {
+                                    format!("Cookie file at {}", path.to_string_lossy())
+                                }
+                                (None, Some(_)) => "Password, hidden".to_string(),
+                                (None, None) => "None".to_string(),
+                            },
+                        ),
+                        ("Onion service port", tor.onion_port.to_string()),
+                    ],
+                )
+            }))
+            .push(summary_section(
+                "Advanced options",
+                Message::Final(message::Final::Review(false)),
+                vec![
+                    (
+                        "Receive addresses",
+                        format!("Imported up to index {}", import.receive_range_end),
+                    ),
+                    (
+                        "Change addresses",
+                        if import.import_change {
+                            format!("Imported up to index {}", import.change_range_end)
+                        } else {
+                            format!(
+                                "Imported up to index {} at the next start of Liana",
+                                import.change_range_end
+                            )
+                        },
+                    ),
+                    (
+                        "Rescan",
+                        match context.rescan {
+                            Some(rescan) if rescan.wait => format!(
+                                "From block {}, waited for before completing the installation",
+                                rescan.height
+                            ),
+                            Some(rescan) => format!(
+                                "From block {}, in the background once installed",
+                                rescan.height
+                            ),
+                            None => "None".to_string(),
+                        },
+                    ),
+                    (
+                        "Daemon",
+                        match &context.daemon_rpc_path {
+                            Some(path) => {
+                                format!("External, listening on {}", path.to_string_lossy())
+                            }
+                            None => "Started by Liana".to_string(),
+                        },
+                    ),
+                ],
+            ))
+            .push_maybe(if network == bitcoin::Network::Bitcoin {
+                Some(
+                    text(prompt::MAINNET_INSTALL_WARNING)
+                        .small()
+                        .style(color::ORANGE),
+                )
+            } else {
+                None
+            })
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .push(
+                        button::secondary(None, "Back")
+                            .on_press(Message::Final(message::Final::Review(false)))
+                            .width(Length::Units(200)),
+                    )
+                    .push(button::debounced(
+                        || button::primary(None, "Confirm and install").width(Length::Units(200)),
+                        Message::Install,
+                    )),
+            )
+            .spacing(20)
+            .max_width(1000)
+            .width(Length::Fill),
+        true,
+    )
+}
+
+/// How the installer authenticates to bitcoind, without the password.
+fn bitcoind_authentication(config: &liana::config::BitcoindConfig) -> String {
+    match &config.rpc_auth {
+        Some(auth) => format!("User {} and password", auth.user),
+        None => format!("Cookie file at {}", config.cookie_path.to_string_lossy()),
+    }
+}
+
+fn summary_section<'a>(
+    title: &'static str,
+    edit: Message,
+    rows: Vec<(&'static str, String)>,
+) -> Element<'a, Message> {
+    card::simple(
+        rows.into_iter().fold(
+            Column::new().spacing(5).push(
+                Row::new()
+                    .align_items(Alignment::Center)
+                    .push(Container::new(text(title).small().bold()).width(Length::Fill))
+                    .push(button::border(None, "Edit").on_press(edit)),
+            ),
+            |col, (label, value)| {
+                col.push(if label.is_empty() {
+                    Row::new().push(text(value).small())
+                } else {
+                    Row::new()
+                        .spacing(5)
+                        .push(text(format!("{}:", label)).small().bold())
+                        .push(text(value).small())
+                })
+            },
+        ),
+    )
+    .width(Length::Fill)
+    .into()
+}
+
+/// The export of the generated configuration to a headless server, an advanced option hidden
+/// by default.
+/// The wallets installed during the session and the set up of another one, on the same network
+/// and bitcoind.
+pub fn installed_wallets<'a>(
+    context: &Context,
+    another_wallet: Option<(&'a form::Value<String>, Option<&'static str>)>,
+    waiting_rescan: bool,
+) -> Element<'a, Message> {
+    if context.installed_wallets.len() < 2 && another_wallet.is_none() {
+        return Column::new().into();
+    }
+    let mut col = Column::new().spacing(10);
+    if context.installed_wallets.len() > 1 {
+        col = col.push(text("Wallets set up in this session:").bold());
+        for wallet in &context.installed_wallets {
+            col = col.push(
+                Row::new()
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .push(text(wallet.name.clone()).bold())
+                    .push(
+                        Container::new(text(wallet.data_dir.to_string_lossy()).small())
+                            .width(Length::Fill),
+                    )
+                    .push(if waiting_rescan {
+                        button::border(None, "Open")
+                    } else {
+                        button::border(None, "Open")
+                            .on_press(Message::Exit(wallet.config_path.clone(), None))
+                    }),
+            );
+        }
+    }
+    if let Some((name, error)) = another_wallet {
+        col = col.push(text(prompt::ANOTHER_WALLET_HELP).small()).push(
+            Row::new()
+                .spacing(10)
+                .align_items(Alignment::Center)
+                .push(
+                    form::Form::new("Name of the other wallet", name, |value| {
+                        Message::Final(message::Final::AnotherWalletNameEdited(value))
+                    })
+                    .warning(error.unwrap_or_default())
+                    .size(20)
+                    .padding(5),
+                )
+                .push(
+                    if waiting_rescan || name.value.trim().is_empty() || error.is_some() {
+                        button::secondary(None, "Set up another wallet")
+                    } else {
+                        button::secondary(None, "Set up another wallet")
+                            .on_press(Message::SetUpAnotherWallet(name.value.clone()))
+                    }
+                    .width(Length::Units(250)),
+                ),
+        );
+    }
+    card::simple(col).width(Length::Fill).into()
+}
+
+pub fn remote_export_options<'a>(
+    remote_export: Option<(
+        &'a form::Value<String>,
+        &'a form::Value<String>,
+        bool,
+        bool,
+        Option<&'a Result<&'static str, Error>>,
+    )>,
+) -> Element<'a, Message> {
+    let (host, directory, descriptor_import, processing, result) = match remote_export {
+        Some(remote_export) => remote_export,
+        None => {
+            return Container::new(
+                button::border(None, "Advanced: export the configuration to a server")
+                    .on_press(Message::Final(message::Final::RemoteExportToggled(true))),
+            )
+            .into();
+        }
+    };
+    card::simple(
+        Column::new()
+            .spacing(10)
+            .push(
+                Row::new()
+                    .align_items(Alignment::Center)
+                    .push(
+                        Container::new(
+                            text("Advanced: export the configuration to a server").bold(),
+                        )
+                        .width(Length::Fill),
+                    )
+                    .push(
+                        button::border(None, "Hide")
+                            .on_press(Message::Final(message::Final::RemoteExportToggled(false))),
+                    ),
+            )
+            .push(text(prompt::REMOTE_EXPORT_HELP).small())
+            .push(
+                form::Form::new("user@host[:port]", host, |value| {
+                    Message::Final(message::Final::RemoteHostEdited(value))
+                })
+                .warning("Please enter the server as user@host")
+                .size(20)
+                .padding(5),
+            )
+            .push(
+                form::Form::new("Directory on the server", directory, |value| {
+                    Message::Final(message::Final::RemoteDirectoryEdited(value))
+                })
+                .warning("Please enter a directory")
+                .size(20)
+                .padding(5),
+            )
+            .push(checkbox(
+                "Also export the import of the descriptor in the watchonly wallet",
+                descriptor_import,
+                |import| Message::Final(message::Final::RemoteDescriptorImportToggled(import)),
+            ))
+            .push_maybe(result.map(|res| match res {
+                Ok(msg) => text(*msg).small().style(color::GREEN),
+                Err(e) => text(e.to_string()).small().style(color::RED),
+            }))
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .push(if processing {
+                        button::secondary(None, "Test connection")
+                    } else {
+                        button::secondary(None, "Test connection")
+                            .on_press(Message::Final(message::Final::CheckRemote))
+                    })
+                    .push(if processing {
+                        button::primary(None, "Exporting ...")
+                    } else {
+                        button::primary(None, "Export")
+                            .on_press(Message::Final(message::Final::ExportToRemote))
+                    }),
+            ),
+    )
+    .max_width(1000)
+    .width(Length::Fill)
+    .into()
+}
+
+pub fn post_install_checklist<'a>(
+    config_path: &std::path::Path,
+    checklist: Vec<(ChecklistItem, bool)>,
+) -> Element<'a, Message> {
+    card::simple(
+        checklist.into_iter().fold(
+            Column::new()
+                .spacing(10)
+                .push(text("Before relying on your wallet:").bold()),
+            |col, (item, checked)| {
+                col.push(
+                    Row::new()
+                        .spacing(10)
+                        .align_items(Alignment::Center)
+                        .push(
+                            Container::new(checkbox(item.label(), checked, move |checked| {
+                                Message::Final(message::Final::ChecklistToggled(item, checked))
+                            }))
+                            .width(Length::Fill),
+                        )
+                        .push_maybe(item.menu().map(|menu| {
+                            button::border(None, "Open")
+                                .on_press(Message::Exit(config_path.to_path_buf(), Some(menu)))
+                        })),
+                )
+            },
+        ),
+    )
+    .max_width(1000)
+    .width(Length::Fill)
+    .into()
+}
+
+pub fn descriptor_import_options<'a>(
+    import_change: bool,
+    receive_range_end: &form::Value<String>,
+    change_range_end: &form::Value<String>,
+    import_range_warning: Option<String>,
+) -> Element<'a, Message> {
+    card::simple(
+        Column::new()
+            .spacing(10)
+            .push(text("Import of the descriptor in bitcoind:").small().bold())
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .push(text("Last receive address index:").small())
+                    .push(
+                        form::Form::new("1000", receive_range_end, |value| {
+                            Message::Final(message::Final::ReceiveRangeEdited(value))
+                        })
+                        .warning("Please enter a number between 1000 and 999999")
+                        .size(20)
+                        .padding(5),
+                    ),
+            )
+            .push(checkbox(
+                "Import the change descriptor now",
+                import_change,
+                |import_change| Message::Final(message::Final::ImportChangeToggled(import_change)),
+            ))
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .push(text("Last change address index:").small())
+                    .push(
+                        form::Form::new("1000", change_range_end, |value| {
+                            Message::Final(message::Final::ChangeRangeEdited(value))
+                        })
+                        .warning("Please enter a number between 1000 and 999999")
+                        .size(20)
+                        .padding(5),
+                    ),
+            )
+            .push_maybe(
+                import_range_warning.map(|warning| text(warning).small().style(color::ORANGE)),
+            )
+            .push_maybe(if import_change {
+                None
+            } else {
+                Some(
+                    text("The change descriptor will only be imported at the next start of Liana. Until then, the change of your transactions will not be detected.")
+                        .small()
+                        .style(color::ORANGE),
+                )
+            }),
+    )
+    .width(Length::Fill)
+    .into()
+}
+
+pub fn daemon_interface_options<'a>(
+    external_daemon: bool,
+    daemon_rpc_path: &form::Value<String>,
+) -> Element<'a, Message> {
+    card::simple(
+        Column::new()
+            .spacing(10)
+            .push(text("Liana daemon (advanced):").small().bold())
+            .push(
+                text("By default, Liana starts its daemon itself and connects to it through the socket in its data directory.")
+                    .small(),
+            )
+            .push(checkbox(
+                "Connect to a daemon managed separately",
+                external_daemon,
+                |external_daemon| {
+                    Message::Final(message::Final::ExternalDaemonToggled(external_daemon))
+                },
+            ))
+            .push_maybe(if external_daemon {
+                Some(
+                    Row::new()
+                        .spacing(10)
+                        .align_items(Alignment::Center)
+                        .push(text("Socket path:").small())
+                        .push(
+                            form::Form::new("Socket path", daemon_rpc_path, |value| {
+                                Message::Final(message::Final::DaemonRpcPathEdited(value))
+                            })
+                            .warning("Please enter an absolute path in an existing directory")
+                            .size(20)
+                            .padding(5),
+                        ),
+                )
+            } else {
+                None
+            }),
+    )
+    .width(Length::Fill)
+    .into()
+}
+
+pub fn address_export_options<'a>(
+    count: &form::Value<String>,
+    branch: AddressBranch,
+    path: &form::Value<String>,
+    result: Option<&'a Result<std::path::PathBuf, Error>>,
+) -> Element<'a, Message> {
+    card::simple(
+        Column::new()
+            .spacing(10)
+            .push(text("Export of the addresses:").small().bold())
+            .push(text(prompt::ADDRESS_EXPORT_HELP).small())
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .push(text("Number of addresses:").small())
+                    .push(
+                        form::Form::new("100", count, |value| {
+                            Message::Final(message::Final::AddressExportCountEdited(value))
+                        })
+                        .warning("Please enter a number between 1 and 10000")
+                        .size(20)
+                        .padding(5),
+                    )
+                    .push(
+                        pick_list(&AddressBranch::ALL[..], Some(branch), |branch| {
+                            Message::Final(message::Final::AddressExportBranchSelected(branch))
+                        })
+                        .padding(5),
+                    ),
+            )
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .push(text("File:").small())
+                    .push(
+                        form::Form::new("/path/to/addresses.csv", path, |value| {
+                            Message::Final(message::Final::AddressExportPathEdited(value))
+                        })
+                        .warning("Please enter an absolute path in an existing directory")
+                        .size(20)
+                        .padding(5),
+                    )
+                    .push(
+                        button::secondary(None, "Export")
+                            .on_press(Message::Final(message::Final::ExportAddresses)),
+                    ),
             )
-            .spacing(50),
-        true,
+            .push_maybe(result.map(|res| {
+                match res {
+                    Ok(path) => text(format!("Addresses exported to {}", path.to_string_lossy()))
+                        .small()
+                        .style(color::GREEN),
+                    Err(e) => text(e.to_string()).small().style(color::RED),
+                }
+            })),
     )
+    .width(Length::Fill)
+    .into()
 }
 
-pub fn install<'a>(
-    progress: (usize, usize),
-    context: &Context,
-    descriptor: String,
-    generating: bool,
-    config_path: Option<&std::path::PathBuf>,
-    warning: Option<&'a String>,
-    signer: Option<Fingerprint>,
+pub fn rescan_options<'a>(
+    rescan: bool,
+    wait: bool,
+    [year, month, day]: [&'a form::Value<String>; 3],
+    start: Option<&'a Result<RescanStart, String>>,
 ) -> Element<'a, Message> {
-    layout(
-        progress,
-        "Final step",
+    let date_field = |label: &'static str, placeholder: &'static str, value, field| {
+        Row::new()
+            .spacing(10)
+            .align_items(Alignment::Center)
+            .push(text(label).small().bold())
+            .push(
+                form::Form::new(placeholder, value, move |msg| {
+                    Message::Final(message::Final::RescanDateEdited(field, msg))
+                })
+                .size(20)
+                .padding(5),
+            )
+    };
+    card::simple(
         Column::new()
-            .push(text(
-                "Check your information before finalizing the install process:",
+            .spacing(10)
+            .push(text("Rescan:").small().bold())
+            .push(text(prompt::RESCAN_HELP).small())
+            .push(checkbox(
+                "Rescan the chain for the coins already received",
+                rescan,
+                |rescan| Message::Final(message::Final::RescanToggled(rescan)),
             ))
-            .push(
-                Container::new(
+            .push_maybe(if rescan {
+                Some(
                     Column::new()
                         .spacing(10)
+                        .push(text("Start the rescan at the date:").small())
                         .push(
-                            card::simple(
-                                Column::new()
-                                    .spacing(5)
-                                    .push(text("Descriptor:").small().bold())
-                                    .push(text(descriptor).small()),
-                            )
-                            .width(Length::Fill),
+                            Row::new()
+                                .spacing(20)
+                                .push(date_field("Year:", "2022", year, "year"))
+                                .push(date_field("Month:", "11", month, "month"))
+                                .push(date_field("Day:", "1", day, "day")),
                         )
-                        .push_maybe(if context.hws.is_empty() && signer.is_none() {
-                            None
-                        } else {
-                            Some(
-                                card::simple(
-                                    Column::new()
-                                        .spacing(5)
-                                        .push(text("Registered signing devices:").small().bold())
-                                        .push_maybe(if context.hws.is_empty() {
-                                            None
-                                        } else {
-                                            Some(context.hws.iter().fold(
-                                                Column::new(),
-                                                |acc, hw| {
-                                                    acc.push(
-                                                        Row::new()
-                                                            .spacing(5)
-                                                            .push_maybe(
-                                                                context.keys.iter().find_map(|k| {
-                                                                    if k.master_fingerprint == hw.1
-                                                                    {
-                                                                        Some(
-                                                                            text(k.name.clone())
-                                                                                .small()
-                                                                                .bold(),
-                                                                        )
-                                                                    } else {
-                                                                        None
-                                                                    }
-                                                                }),
-                                                            )
-                                                            .push(
-                                                                text(format!("#{}", hw.1)).small(),
-                                                            )
-                                                            .push(text(hw.0.to_string()).small()),
-                                                    )
-                                                },
-                                            ))
-                                        })
-                                        .push_maybe(signer.as_ref().map(|fingerprint| {
-                                            Row::new()
-                                                .spacing(5)
-                                                .push_maybe(context.keys.iter().find_map(|k| {
-                                                    if k.master_fingerprint == *fingerprint {
-                                                        Some(text(k.name.clone()).small().bold())
-                                                    } else {
-                                                        None
-                                                    }
-                                                }))
-                                                .push(text(format!("#{}", fingerprint)).small())
-                                                .push(text("This computer").small())
-                                        })),
-                                )
-                                .width(Length::Fill),
-                            )
+                        .push(match start {
+                            None => text("Looking for the first block of this date...").small(),
+                            Some(Ok(start)) if start.before_launch => text(format!(
+                                "The network launched after this date: the rescan starts at its first block, at height {}.",
+                                start.height
+                            ))
+                            .small()
+                            .style(color::ORANGE),
+                            Some(Ok(start)) => text(format!(
+                                "The rescan starts at the block at height {}.",
+                                start.height
+                            ))
+                            .small(),
+                            Some(Err(e)) => text(e).small().style(color::RED),
                         })
-                        .push(
-                            card::simple(
-                                Column::new()
-                                    .push(text("Bitcoind:").small().bold())
-                                    .push(
-                                        Row::new()
-                                            .spacing(5)
-                                            .align_items(Alignment::Center)
-                                            .push(text("Cookie path:").small())
-                                            .push(
-                                                text(format!(
-                                                    "{}",
-                                                    context
-                                                        .bitcoind_config
-                                                        .as_ref()
-                                                        .unwrap()
-                                                        .cookie_path
-                                                        .to_string_lossy()
-                                                ))
-                                                .small(),
-                                            ),
-                                    )
-                                    .push(
-                                        Row::new()
-                                            .spacing(5)
-                                            .align_items(Alignment::Center)
-                                            .push(text("Address:").small())
-                                            .push(
-                                                text(format!(
-                                                    "{}",
-                                                    context.bitcoind_config.as_ref().unwrap().addr
-                                                ))
-                                                .small(),
-                                            ),
-                                    ),
-                            )
-                            .width(Length::Fill),
-                        ),
-                )
-                .max_width(1000),
-            )
-            .push(Space::with_height(Length::Units(50)))
-            .push_maybe(warning.map(|e| card::invalid(text(e))))
-            .push(if generating {
-                Container::new(button::primary(None, "Installing ...").width(Length::Units(200)))
-            } else if let Some(path) = config_path {
-                Container::new(
-                    Column::new()
-                        .push(Container::new(text("Installed !")))
-                        .push(Container::new(
-                            button::primary(None, "Start")
-                                .on_press(Message::Exit(path.clone()))
-                                .width(Length::Units(200)),
-                        ))
-                        .align_items(Alignment::Center)
-                        .spacing(20),
+                        .push(checkbox(
+                            "Wait for the rescan to complete the installation",
+                            wait,
+                            |wait| Message::Final(message::Final::RescanWaitToggled(wait)),
+                        )),
                 )
-                .padding(50)
+            } else {
+                None
+            }),
+    )
+    .width(Length::Fill)
+    .into()
+}
+
+pub fn node_descriptor_check<'a>(
+    checking: bool,
+    descriptor_info: Option<&'a Result<Vec<NodeDescriptorInfo>, Error>>,
+) -> Element<'a, Message> {
+    let header = Row::new()
+        .spacing(10)
+        .align_items(Alignment::Center)
+        .push(
+            Column::new()
                 .width(Length::Fill)
-                .center_x()
+                .push(text("Descriptor check by bitcoind (optional):").small().bold())
+                .push(
+                    text("Ask bitcoind to compute the checksum of the descriptors and whether it can solve them.")
+                        .small(),
+                ),
+        )
+        .push(if checking {
+            button::border(None, "Checking...")
+        } else {
+            button::border(Some(icon::reload_icon()), "Check")
+                .on_press(Message::Final(message::Final::CheckDescriptor))
+        });
+    match descriptor_info {
+        None => card::simple(header).width(Length::Fill).into(),
+        Some(Err(e)) => card::simple(
+            Column::new()
+                .spacing(10)
+                .push(header)
+                .push(text(e.to_string()).small().style(color::RED)),
+        )
+        .width(Length::Fill)
+        .into(),
+        Some(Ok(infos)) => {
+            let content = infos
+                .iter()
+                .fold(Column::new().spacing(10).push(header), |col, info| {
+                    col.push(
+                        Column::new()
+                            .spacing(5)
+                            .push(text(&info.descriptor).small())
+                            .push(
+                                Row::new()
+                                    .spacing(5)
+                                    .push(text("Liana checksum:").small())
+                                    .push(text(&info.checksum).small().bold()),
+                            )
+                            .push(match &info.node_info {
+                                Ok((checksum, solvable)) => Row::new()
+                                    .spacing(5)
+                                    .push(text("Bitcoind checksum:").small())
+                                    .push(if checksum == &info.checksum {
+                                        text(checksum).small().bold()
+                                    } else {
+                                        text(checksum).small().bold().style(color::RED)
+                                    })
+                                    .push(if *solvable {
+                                        text("(solvable)").small()
+                                    } else {
+                                        text("(not solvable)").small().style(color::RED)
+                                    }),
+                                Err(e) => Row::new().push(
+                                    text(format!("Rejected by bitcoind: {}", e))
+                                        .small()
+                                        .style(color::RED),
+                                ),
+                            }),
+                    )
+                });
+            if infos.iter().all(|info| info.is_consistent()) {
+                card::simple(content).width(Length::Fill).into()
             } else {
-                Container::new(
-                    button::primary(None, "Finalize installation")
-                        .on_press(Message::Install)
-                        .width(Length::Units(200)),
+                card::invalid(
+                    content.push(
+                        Row::new()
+                            .spacing(10)
+                            .align_items(Alignment::Center)
+                            .push(icon::warning_icon())
+                            .push(text("Bitcoind does not agree with Liana on the descriptors. You can still install, but you should investigate before using this wallet.").small()),
+                    ),
                 )
-            })
-            .spacing(10)
-            .width(Length::Fill),
-        true,
-    )
+                .width(Length::Fill)
+                .into()
+            }
+        }
+    }
 }
 
 pub fn defined_sequence<'a>(
@@ -1235,6 +3453,7 @@ pub fn edit_key_modal<'a>(
     hot_signer_fingerprint: &Fingerprint,
     signer_alias: Option<&'a String>,
     form_xpub: &form::Value<String>,
+    key_origin: &Result<Option<String>, String>,
     form_name: &'a form::Value<String>,
     edit_name: bool,
 ) -> Element<'a, Message> {
@@ -1310,7 +3529,15 @@ pub fn edit_key_modal<'a>(
                                 )
                                 .spacing(10)
                                 .push(Container::new(text("/<0;1>/*")).padding(5)),
-                        ),
+                        )
+                        .push_maybe(match key_origin {
+                            Err(e) => Some(text(e.clone()).small().style(color::RED)),
+                            Ok(Some(warning)) => {
+                                Some(text(warning.clone()).small().style(color::ORANGE))
+                            }
+                            Ok(None) => None,
+                        })
+                        .push(text(prompt::KEY_PAYLOAD_HELP).small()),
                 )
                 .push(
                     if !edit_name && !form_xpub.value.is_empty() && form_xpub.valid {
@@ -1512,14 +3739,44 @@ fn hw_list_view(
 
 pub fn backup_mnemonic<'a>(
     progress: (usize, usize),
-    words: &'a [&'static str; 12],
+    words: &'a [&'static str],
     done: bool,
+    can_regenerate: bool,
+    fingerprint: Fingerprint,
+    (passphrase, passphrase_confirm, passphrase_applied, has_passphrase, passphrase_error): (
+        &'a form::Value<String>,
+        &'a form::Value<String>,
+        bool,
+        bool,
+        Option<&'a String>,
+    ),
 ) -> Element<'a, Message> {
     layout(
         progress,
         "Backup your mnemonic",
         Column::new()
             .push(text(prompt::MNEMONIC_HELP))
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .push(
+                        text(if has_passphrase {
+                            format!("Fingerprint (with the passphrase): {}", fingerprint)
+                        } else {
+                            format!("Fingerprint: {}", fingerprint)
+                        })
+                        .bold(),
+                    )
+                    .push_maybe(if can_regenerate {
+                        Some(
+                            button::border(Some(icon::reload_icon()), "Generate another")
+                                .on_press(Message::RegenerateHotSigner),
+                        )
+                    } else {
+                        None
+                    }),
+            )
             .push(
                 words
                     .iter()
@@ -1536,17 +3793,61 @@ pub fn backup_mnemonic<'a>(
                         )
                     }),
             )
+            .push_maybe(if can_regenerate {
+                Some(
+                    Column::new()
+                        .spacing(10)
+                        .push(text(prompt::MNEMONIC_PASSPHRASE_HELP).small())
+                        .push(
+                            Row::new()
+                                .spacing(10)
+                                .align_items(Alignment::Center)
+                                .push(
+                                    form::Form::new(
+                                        "Passphrase (optional)",
+                                        passphrase,
+                                        Message::MnemonicPassphrase,
+                                    )
+                                    .padding(10),
+                                )
+                                .push(
+                                    form::Form::new(
+                                        "Confirm the passphrase",
+                                        passphrase_confirm,
+                                        Message::MnemonicPassphraseConfirm,
+                                    )
+                                    .warning("The passphrases do not match")
+                                    .padding(10),
+                                )
+                                .push(if !passphrase_applied && passphrase_confirm.valid {
+                                    button::primary(None, "Apply")
+                                        .on_press(Message::ApplyPassphrase)
+                                        .width(Length::Units(100))
+                                } else {
+                                    button::primary(None, "Apply").width(Length::Units(100))
+                                }),
+                        )
+                        .push_maybe(
+                            passphrase_error.map(|e| card::invalid(text(e).style(color::RED))),
+                        ),
+                )
+            } else {
+                None
+            })
             .push(checkbox(
                 "I have backed up my mnemonic",
                 done,
                 Message::UserActionDone,
             ))
-            .push(if done {
+            .push(if done && passphrase_applied {
+                button::debounced(
+                    || button::primary(None, "Next").width(Length::Units(200)),
+                    Message::Next,
+                )
+            } else {
                 button::primary(None, "Next")
-                    .on_press(Message::Next)
                     .width(Length::Units(200))
-            } else {
-                button::primary(None, "Next").width(Length::Units(200))
+                    .into()
             })
             .spacing(50),
         true,
@@ -1555,7 +3856,8 @@ pub fn backup_mnemonic<'a>(
 
 pub fn recover_mnemonic<'a>(
     progress: (usize, usize),
-    words: &'a [(String, bool); 12],
+    words: &'a [(String, bool)],
+    passphrase: &'a form::Value<String>,
     current: usize,
     suggestions: &'a Vec<String>,
     recover: bool,
@@ -1570,6 +3872,20 @@ pub fn recover_mnemonic<'a>(
                 Some(
                     Column::new()
                         .align_items(Alignment::Center)
+                        .push(text(prompt::RECOVER_MNEMONIC_PASTE_HELP).small())
+                        .push(
+                            MNEMONIC_LENGTHS
+                                .iter()
+                                .zip(["12 words", "15 words", "18 words", "21 words", "24 words"])
+                                .fold(Row::new().spacing(5), |row, (length, label)| {
+                                    row.push(if *length == words.len() {
+                                        button::primary(None, label)
+                                    } else {
+                                        button::border(None, label)
+                                            .on_press(Message::MnemonicLength(*length))
+                                    })
+                                }),
+                        )
                         .push(
                             Container::new(if !suggestions.is_empty() {
                                 suggestions.iter().fold(Row::new().spacing(5), |row, sugg| {
@@ -1613,6 +3929,19 @@ pub fn recover_mnemonic<'a>(
                                 )
                             },
                         ))
+                        .push(Space::with_height(Length::Units(20)))
+                        .push(text(prompt::RECOVER_PASSPHRASE_HELP).small())
+                        .push(
+                            Container::new(
+                                form::Form::new(
+                                    "Passphrase (optional)",
+                                    passphrase,
+                                    Message::MnemonicPassphrase,
+                                )
+                                .padding(10),
+                            )
+                            .width(Length::Units(400)),
+                        )
                         .push(Space::with_height(Length::Units(50)))
                         .push_maybe(error.map(|e| card::invalid(text(e).style(color::RED)))),
                 )
@@ -1642,11 +3971,14 @@ pub fn recover_mnemonic<'a>(
                     )
                     .push(
                         if words.iter().any(|(_, valid)| !valid) || error.is_some() {
-                            button::primary(None, "Next").width(Length::Units(200))
-                        } else {
                             button::primary(None, "Next")
-                                .on_press(Message::Next)
                                 .width(Length::Units(200))
+                                .into()
+                        } else {
+                            button::debounced(
+                                || button::primary(None, "Next").width(Length::Units(200)),
+                                Message::Next,
+                            )
                         },
                     )
             })
@@ -1655,6 +3987,14 @@ pub fn recover_mnemonic<'a>(
     )
 }
 
+/// Clear the inputs of the current step.
+fn reset_button<'a>() -> Element<'a, Message> {
+    button::border(None, "Reset")
+        .on_press(Message::Reset)
+        .width(Length::Units(200))
+        .into()
+}
+
 fn layout<'a>(
     progress: (usize, usize),
     title: &'static str,
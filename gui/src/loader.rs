@@ -4,7 +4,7 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use iced::{Alignment, Command, Length, Subscription};
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use liana::{
     config::{Config, ConfigError},
@@ -22,9 +22,11 @@ use crate::{
     app::{
         cache::Cache,
         config::Config as GUIConfig,
+        menu::Menu,
         wallet::{Wallet, WalletError},
     },
     daemon::{client, embedded::EmbeddedDaemon, model::*, Daemon, DaemonError},
+    installer,
 };
 
 type Lianad = client::Lianad<client::jsonrpc::JsonRPCClient>;
@@ -34,6 +36,8 @@ pub struct Loader {
     pub network: bitcoin::Network,
     pub gui_config: GUIConfig,
     pub daemon_started: bool,
+    /// The panel of the application to open once loaded, if not the home one.
+    pub initial_menu: Option<Menu>,
 
     step: Step,
 }
@@ -57,6 +61,7 @@ pub enum Message {
     Started(Result<Arc<dyn Daemon + Sync + Send>, Error>),
     Loaded(Result<Arc<dyn Daemon + Sync + Send>, Error>),
     Failure(DaemonError),
+    OnionServicePublished(Result<(), installer::Error>),
 }
 
 impl Loader {
@@ -70,6 +75,14 @@ impl Loader {
             .clone()
             .unwrap_or_else(|| socket_path(&datadir_path, network));
         let network = network;
+        // Tor forgets the onion service of the RPC interface when it restarts.
+        let publish = match gui_config.tor.clone() {
+            Some(tor) => Command::perform(
+                installer::republish_onion_service(tor, path.clone()),
+                Message::OnionServicePublished,
+            ),
+            None => Command::none(),
+        };
         (
             Loader {
                 network,
@@ -77,8 +90,12 @@ impl Loader {
                 gui_config,
                 step: Step::Connecting,
                 daemon_started: false,
+                initial_menu: None,
             },
-            Command::perform(connect(path), Message::Loaded),
+            Command::batch(vec![
+                Command::perform(connect(path), Message::Loaded),
+                publish,
+            ]),
         )
     }
 
@@ -202,6 +219,13 @@ impl Loader {
                 self.daemon_started = false;
                 Command::none()
             }
+            Message::OnionServicePublished(Err(e)) => {
+                warn!(
+                    "Failed to publish the onion service of the RPC interface: {}",
+                    e
+                );
+                Command::none()
+            }
             _ => Command::none(),
         }
     }
@@ -196,7 +196,7 @@ impl Application for GUI {
                 _ => l.update(*msg).map(|msg| Message::Launch(Box::new(msg))),
             },
             (State::Installer(i), Message::Install(msg)) => {
-                if let installer::Message::Exit(path) = *msg {
+                if let installer::Message::Exit(path, menu) = *msg {
                     let cfg = app::Config::from_file(&path).unwrap();
                     let daemon_cfg =
                         DaemonConfig::from_file(cfg.daemon_config_path.clone()).unwrap();
@@ -212,8 +212,9 @@ impl Application for GUI {
                         cfg.log_level().unwrap_or(LevelFilter::INFO),
                     );
                     self.logger.remove_install_log_file(datadir_path.clone());
-                    let (loader, command) =
+                    let (mut loader, command) =
                         Loader::new(datadir_path, cfg, daemon_cfg.bitcoin_config.network);
+                    loader.initial_menu = menu;
                     self.state = State::Loader(Box::new(loader));
                     command.map(|msg| Message::Load(Box::new(msg)))
                 } else {
@@ -227,13 +228,16 @@ impl Application for GUI {
                     Command::none()
                 }
                 loader::Message::Synced(Ok((wallet, cache, daemon))) => {
-                    let (app, command) = App::new(
+                    let (mut app, mut command) = App::new(
                         cache,
                         wallet,
                         loader.gui_config.clone(),
                         daemon,
                         loader.datadir_path.clone(),
                     );
+                    if let Some(menu) = &loader.initial_menu {
+                        command = app.load_state(menu);
+                    }
                     self.state = State::App(app);
                     command.map(|msg| Message::Run(Box::new(msg)))
                 }
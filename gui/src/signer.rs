@@ -39,7 +39,7 @@ impl Signer {
         self.key.set_network(network)
     }
 
-    pub fn mnemonic(&self) -> [&'static str; 12] {
+    pub fn mnemonic(&self) -> Vec<&'static str> {
         self.key.words()
     }
 
@@ -47,6 +47,15 @@ impl Signer {
         Ok(Self::new(HotSigner::generate(network)?))
     }
 
+    /// The signer of the same mnemonic with its seed derived with the given BIP39 passphrase.
+    pub fn with_passphrase(&self, passphrase: &str) -> Result<Self, SignerError> {
+        Ok(Self::new(self.key.with_passphrase(passphrase)?))
+    }
+
+    pub fn has_passphrase(&self) -> bool {
+        self.key.has_passphrase()
+    }
+
     pub fn fingerprint(&self) -> Fingerprint {
         self.fingerprint
     }
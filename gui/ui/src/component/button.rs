@@ -1,6 +1,12 @@
 use crate::{color, theme, widget::*};
 use iced::widget::{button, container, row};
 use iced::{Alignment, Length};
+use iced_native::{
+    event, layout, mouse, renderer,
+    widget::{self, tree, Tree},
+    window, Clipboard, Event, Layout, Point, Rectangle, Shell, Widget,
+};
+use std::time::{Duration, Instant};
 
 use super::text::text;
 
@@ -52,3 +58,177 @@ fn content<'a, T: 'a>(icon: Option<Text<'a>>, t: &'static str) -> Container<'a,
         .padding(5),
     }
 }
+
+/// The presses of a debounced button following a first one, or its display, within this delay are
+/// ignored.
+const DEBOUNCE_DELAY: Duration = Duration::from_millis(800);
+
+/// A button emitting its message only once for a burst of presses, for instance a double-click
+/// on a button advancing a form. It is inert, and shown as disabled, for the duration of the
+/// delay. It is also inert for the delay once displayed, for the second press of a double-click on
+/// the button of the previous screen not to land on it. Keyboard shortcuts do not go through the
+/// button and are not affected.
+pub struct Debounced<'a, M> {
+    /// The button emitting the message, and the same one without message displayed during the
+    /// delay.
+    button: Element<'a, M>,
+    inert: Element<'a, M>,
+}
+
+/// Whether the delay following the display or the last press of the button is running. The state
+/// of the tree is when the button was displayed or last emitted its message.
+fn is_debouncing(tree: &Tree) -> bool {
+    tree.state.downcast_ref::<Instant>().elapsed() < DEBOUNCE_DELAY
+}
+
+impl<'a, M> Debounced<'a, M> {
+    /// The button displayed, with the index of its tree.
+    fn current(&self, tree: &Tree) -> (usize, &Element<'a, M>) {
+        if is_debouncing(tree) {
+            (1, &self.inert)
+        } else {
+            (0, &self.button)
+        }
+    }
+}
+
+impl<'a, M> Widget<M, Renderer> for Debounced<'a, M> {
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<Instant>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(Instant::now())
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(&self.button), Tree::new(&self.inert)]
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        tree.diff_children(&[&self.button, &self.inert]);
+    }
+
+    fn width(&self) -> iced::Length {
+        self.button.as_widget().width()
+    }
+
+    fn height(&self) -> iced::Length {
+        self.button.as_widget().height()
+    }
+
+    fn layout(&self, renderer: &Renderer, limits: &layout::Limits) -> layout::Node {
+        self.button.as_widget().layout(renderer, limits)
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, M>,
+    ) -> event::Status {
+        if is_debouncing(tree) {
+            // Drawn again once the delay is over, for the button not to look inert anymore.
+            if let Event::Window(window::Event::RedrawRequested(_)) = event {
+                let since = *tree.state.downcast_ref::<Instant>();
+                shell.request_redraw(window::RedrawRequest::At(since + DEBOUNCE_DELAY));
+            }
+            return self.inert.as_widget_mut().on_event(
+                &mut tree.children[1],
+                event,
+                layout,
+                cursor_position,
+                renderer,
+                clipboard,
+                shell,
+            );
+        }
+
+        let mut messages = Vec::new();
+        let mut local_shell = Shell::new(&mut messages);
+        let status = self.button.as_widget_mut().on_event(
+            &mut tree.children[0],
+            event,
+            layout,
+            cursor_position,
+            renderer,
+            clipboard,
+            &mut local_shell,
+        );
+        local_shell.revalidate_layout(|| shell.invalidate_layout());
+        if !messages.is_empty() {
+            *tree.state.downcast_mut::<Instant>() = Instant::now();
+            for message in messages {
+                shell.publish(message);
+            }
+        }
+        status
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &theme::Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+    ) {
+        let (i, button) = self.current(tree);
+        button.as_widget().draw(
+            &tree.children[i],
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor_position,
+            viewport,
+        );
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        let (i, button) = self.current(tree);
+        button.as_widget().mouse_interaction(
+            &tree.children[i],
+            layout,
+            cursor_position,
+            viewport,
+            renderer,
+        )
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn widget::Operation<M>,
+    ) {
+        self.button
+            .as_widget()
+            .operate(&mut tree.children[0], layout, renderer, operation);
+    }
+}
+
+pub fn debounced<'a, M, B>(button: B, on_press: M) -> Element<'a, M>
+where
+    M: 'a,
+    B: Fn() -> Button<'a, M>,
+{
+    Element::new(Debounced {
+        inert: button().style(theme::Button::Border).into(),
+        button: button().on_press(on_press).into(),
+    })
+}
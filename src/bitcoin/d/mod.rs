@@ -5,7 +5,7 @@ mod utils;
 use crate::{
     bitcoin::{Block, BlockChainTip},
     config,
-    descriptors::LianaDescriptor,
+    descriptors::{LianaDescriptor, SinglePathLianaDesc},
 };
 use utils::{block_before_date, roundup_progress};
 
@@ -201,8 +201,11 @@ impl BitcoinD {
         config: &config::BitcoindConfig,
         watchonly_wallet_path: String,
     ) -> Result<BitcoinD, BitcoindError> {
-        let cookie_string =
-            fs::read_to_string(&config.cookie_path).map_err(BitcoindError::CookieFile)?;
+        // The cookie is itself the user and password the requests are authenticated with.
+        let cookie_string = match &config.rpc_auth {
+            Some(auth) => format!("{}:{}", auth.user, auth.password),
+            None => fs::read_to_string(&config.cookie_path).map_err(BitcoindError::CookieFile)?,
+        };
         let watchonly_url = format!("http://{}/wallet/{}", config.addr, watchonly_wallet_path);
 
         // Create a dummy bitcoind with clients using a low timeout to sanity check the connection.
@@ -502,15 +505,35 @@ impl BitcoinD {
         Ok(())
     }
 
-    // Import the receive and change descriptors from the multipath descriptor to bitcoind.
-    fn import_descriptor(&self, desc: &LianaDescriptor) -> Option<String> {
-        let descriptors = [desc.receive_descriptor(), desc.change_descriptor()]
+    // Import the receive and change descriptors from the multipath descriptor to bitcoind. The
+    // change descriptor is not imported if the import settings say it must be deferred.
+    fn import_descriptor(
+        &self,
+        desc: &LianaDescriptor,
+        import: &config::DescriptorImportConfig,
+    ) -> Option<String> {
+        let mut descriptors = vec![(desc.receive_descriptor(), import.receive_range_end)];
+        if import.import_change {
+            descriptors.push((desc.change_descriptor(), import.change_range_end));
+        }
+        self.import_single_path_descriptors(&descriptors, None)
+    }
+
+    // Import the descriptors from the given timestamp, or from now if none is given.
+    fn import_single_path_descriptors(
+        &self,
+        descs: &[(&SinglePathLianaDesc, u32)],
+        timestamp: Option<u32>,
+    ) -> Option<String> {
+        let timestamp = timestamp.map(Json::from).unwrap_or_else(|| "now".into());
+        let descriptors = descs
             .iter()
-            .map(|desc| {
+            .map(|(desc, range_end)| {
                 serde_json::json!({
                     "desc": desc.to_string(),
-                    "timestamp": "now",
+                    "timestamp": timestamp,
                     "active": false,
+                    "range": range_end,
                 })
             })
             .collect();
@@ -572,6 +595,7 @@ impl BitcoinD {
     pub fn create_watchonly_wallet(
         &self,
         main_descriptor: &LianaDescriptor,
+        import: &config::DescriptorImportConfig,
     ) -> Result<(), BitcoindError> {
         // Remove any leftover. This can happen if we delete the watchonly wallet but don't restart
         // bitcoind.
@@ -592,7 +616,46 @@ impl BitcoinD {
                 BitcoindError::Wallet(self.watchonly_wallet_path.clone(), WalletError::Creating(e))
             })?;
         // TODO: make it return an error instead of an option.
-        if let Some(err) = self.import_descriptor(main_descriptor) {
+        if let Some(err) = self.import_descriptor(main_descriptor, import) {
+            return Err(BitcoindError::Wallet(
+                self.watchonly_wallet_path.clone(),
+                WalletError::ImportingDescriptor(err),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Import the change descriptor to the watchonly wallet if its import was deferred at
+    /// creation and it is still missing. It is imported from the timestamp of the receive
+    /// descriptor, the creation of the wallet or the start of its last rescan, for bitcoind to
+    /// find the change outputs created in the meantime.
+    pub fn maybe_import_change_descriptor(
+        &self,
+        main_descriptor: &LianaDescriptor,
+        import: &config::DescriptorImportConfig,
+    ) -> Result<(), BitcoindError> {
+        let change_desc = main_descriptor.change_descriptor();
+        let receive_desc = main_descriptor.receive_descriptor().to_string();
+        let current_descs = self.list_descriptors();
+        if current_descs
+            .iter()
+            .any(|entry| entry.desc == change_desc.to_string())
+        {
+            return Ok(());
+        }
+        let timestamp = current_descs
+            .iter()
+            .find(|entry| entry.desc == receive_desc)
+            .map(|entry| entry.timestamp);
+
+        log::info!(
+            "Importing the change descriptor whose import was deferred at creation. The block \
+             chain is rescanned since the wallet was created or last rescanned."
+        );
+        if let Some(err) = self
+            .import_single_path_descriptors(&[(change_desc, import.change_range_end)], timestamp)
+        {
             return Err(BitcoindError::Wallet(
                 self.watchonly_wallet_path.clone(),
                 WalletError::ImportingDescriptor(err),
@@ -665,6 +728,7 @@ impl BitcoinD {
     pub fn wallet_sanity_checks(
         &self,
         main_descriptor: &LianaDescriptor,
+        change_deferred: bool,
     ) -> Result<(), BitcoindError> {
         // Check our watchonly wallet is loaded
         if self
@@ -689,7 +753,7 @@ impl BitcoinD {
             .map(|entry| entry.desc)
             .collect();
         if !desc_list.contains(&receive_desc.to_string())
-            || !desc_list.contains(&change_desc.to_string())
+            || (!change_deferred && !desc_list.contains(&change_desc.to_string()))
         {
             return Err(BitcoindError::Wallet(
                 self.watchonly_wallet_path.clone(),
@@ -964,17 +1028,24 @@ impl BitcoinD {
         // The range of the newly imported descriptors supposed to update the existing ones must
         // have a range inclusive of the existing ones. We always use 0 as the initial index so
         // this is just determining the maximum index to use.
-        let max_range = self
-            .list_descriptors()
-            .into_iter()
+        let current_descs = self.list_descriptors();
+        let max_range = current_descs
+            .iter()
             // 1_000 is bitcoind's default and what we use at initial import.
             .fold(1_000, |range, entry| {
                 cmp::max(range, entry.range.map(|r| r[1]).unwrap_or(0))
             });
-        let desc_str = [
-            desc.receive_descriptor().to_string(),
-            desc.change_descriptor().to_string(),
-        ];
+        // The import of the change descriptor may have been deferred to the next startup, in
+        // which case it is not imported for the rescan either. It is then imported from the
+        // timestamp of the receive descriptor, updated by this rescan.
+        let mut desc_str = vec![desc.receive_descriptor().to_string()];
+        let change_desc_str = desc.change_descriptor().to_string();
+        if current_descs
+            .iter()
+            .any(|entry| entry.desc == change_desc_str)
+        {
+            desc_str.push(change_desc_str);
+        }
         let desc_json: Vec<Json> = desc_str
             .iter()
             .map(|desc_str| {
@@ -48,13 +48,78 @@ fn default_daemon() -> bool {
     false
 }
 
+fn default_import_range_end() -> u32 {
+    // This is bitcoind's default.
+    1_000
+}
+
+fn default_import_change() -> bool {
+    true
+}
+
+/// How the main descriptor is imported in the watchonly wallet upon its creation.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DescriptorImportConfig {
+    /// The last derivation index imported for the receive descriptor
+    #[serde(default = "default_import_range_end")]
+    pub receive_range_end: u32,
+    /// Whether to import the change descriptor along with the receive one. If not, it is imported
+    /// at the next startup.
+    #[serde(default = "default_import_change")]
+    pub import_change: bool,
+    /// The last derivation index imported for the change descriptor
+    #[serde(default = "default_import_range_end")]
+    pub change_range_end: u32,
+}
+
+impl Default for DescriptorImportConfig {
+    fn default() -> Self {
+        Self {
+            receive_range_end: default_import_range_end(),
+            import_change: default_import_change(),
+            change_range_end: default_import_range_end(),
+        }
+    }
+}
+
+impl DescriptorImportConfig {
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// The `rpcuser` and `rpcpassword` set in bitcoind's configuration
+#[derive(Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct BitcoindRpcAuth {
+    pub user: String,
+    pub password: String,
+}
+
+// The configuration may be printed, in logs or error messages: never print the password.
+impl std::fmt::Debug for BitcoindRpcAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BitcoindRpcAuth")
+            .field("user", &self.user)
+            .field("password", &"<redacted>")
+            .finish()
+    }
+}
+
 /// Everything we need to know for talking to bitcoind serenely
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct BitcoindConfig {
-    /// Path to bitcoind's cookie file, to authenticate the RPC connection
+    /// Path to bitcoind's cookie file, to authenticate the RPC connection. Unused if the user and
+    /// password are set.
+    #[serde(default)]
     pub cookie_path: PathBuf,
     /// The IP:port bitcoind's RPC is listening on
     pub addr: SocketAddr,
+    /// The user and password to authenticate the RPC connection with, instead of the cookie file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rpc_auth: Option<BitcoindRpcAuth>,
+    /// How to import the main descriptor in the watchonly wallet
+    #[serde(default, skip_serializing_if = "DescriptorImportConfig::is_default")]
+    pub descriptor_import: DescriptorImportConfig,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -217,7 +282,7 @@ impl Config {
 
 #[cfg(test)]
 mod tests {
-    use super::{config_file_path, Config};
+    use super::{config_file_path, BitcoindRpcAuth, Config, DescriptorImportConfig};
 
     // Test the format of the configuration file
     #[test]
@@ -259,6 +324,78 @@ mod tests {
         #[cfg(unix)] // On non-UNIX there is no 'daemon' member.
         assert_eq!(toml_str, serialized);
 
+        // A valid config with custom descriptor import settings
+        let toml_str = r#"
+            data_dir = "/home/wizardsardine/custom/folder/"
+            daemon = false
+            log_level = "debug"
+            main_descriptor = "wsh(andor(pk([aabbccdd]tpubDEN9WSToTyy9ZQfaYqSKfmVqmq1VVLNtYfj3Vkqh67et57eJ5sTKZQBkHqSwPUsoSskJeaYnPttHe2VrkCsKA27kUaN9SDc5zhqeLzKa1rr/<0;1>/*),older(10000),pk([aabbccdd]tpubD8LYfn6njiA2inCoxwM7EuN3cuLVcaHAwLYeups13dpevd3nHLRdK9NdQksWXrhLQVxcUZRpnp5CkJ1FhE61WRAsHxDNAkvGkoQkAeWDYjV/<0;1>/*)))#dw4ulnrs"
+
+            [bitcoin_config]
+            network = "bitcoin"
+            poll_interval_secs = 18
+
+            [bitcoind_config]
+            cookie_path = "/home/user/.bitcoin/.cookie"
+            addr = "127.0.0.1:8332"
+
+            [bitcoind_config.descriptor_import]
+            receive_range_end = 5000
+            import_change = false
+            "#.trim_start().replace("            ", "");
+        let parsed = toml::from_str::<Config>(&toml_str).expect("Deserializing toml_str");
+        let import = parsed
+            .bitcoind_config
+            .as_ref()
+            .unwrap()
+            .descriptor_import
+            .clone();
+        assert_eq!(
+            import,
+            DescriptorImportConfig {
+                receive_range_end: 5000,
+                import_change: false,
+                change_range_end: 1_000,
+            }
+        );
+        let serialized = toml::to_string_pretty(&parsed).expect("Serializing to toml");
+        let reparsed = toml::from_str::<Config>(&serialized).expect("Deserializing serialized");
+        assert_eq!(reparsed.bitcoind_config.unwrap().descriptor_import, import);
+
+        // A valid config authenticating with a user and password rather than the cookie file
+        let toml_str = r#"
+            data_dir = "/home/wizardsardine/custom/folder/"
+            daemon = false
+            log_level = "debug"
+            main_descriptor = "wsh(andor(pk([aabbccdd]tpubDEN9WSToTyy9ZQfaYqSKfmVqmq1VVLNtYfj3Vkqh67et57eJ5sTKZQBkHqSwPUsoSskJeaYnPttHe2VrkCsKA27kUaN9SDc5zhqeLzKa1rr/<0;1>/*),older(10000),pk([aabbccdd]tpubD8LYfn6njiA2inCoxwM7EuN3cuLVcaHAwLYeups13dpevd3nHLRdK9NdQksWXrhLQVxcUZRpnp5CkJ1FhE61WRAsHxDNAkvGkoQkAeWDYjV/<0;1>/*)))#dw4ulnrs"
+
+            [bitcoin_config]
+            network = "bitcoin"
+            poll_interval_secs = 18
+
+            [bitcoind_config]
+            addr = "192.168.1.10:8332"
+
+            [bitcoind_config.rpc_auth]
+            user = "liana"
+            password = "hunter2"
+            "#.trim_start().replace("            ", "");
+        let parsed = toml::from_str::<Config>(&toml_str).expect("Deserializing toml_str");
+        let rpc_auth = parsed.bitcoind_config.as_ref().unwrap().rpc_auth.clone();
+        assert_eq!(
+            rpc_auth,
+            Some(BitcoindRpcAuth {
+                user: "liana".to_string(),
+                password: "hunter2".to_string(),
+            })
+        );
+        let serialized = toml::to_string_pretty(&parsed).expect("Serializing to toml");
+        let reparsed = toml::from_str::<Config>(&serialized).expect("Deserializing serialized");
+        assert_eq!(reparsed.bitcoind_config.unwrap().rpc_auth, rpc_auth);
+        // The password is not printed along the rest of the configuration.
+        let debug = format!("{:?}", parsed);
+        assert!(debug.contains("liana") && !debug.contains("hunter2"));
+
         // Invalid desc checksum
         let toml_str = r#"
             daemon = false
@@ -16,6 +16,8 @@ pub enum LianaPolicyError {
     MissingRecoveryPath,
     InsaneTimelock(u32),
     InvalidKey(Box<descriptor::DescriptorPublicKey>),
+    /// The key is multipath but not for (and only for) the receive and change branches, `<0;1>`.
+    InvalidMultipath(Box<descriptor::DescriptorPublicKey>),
     DuplicateKey(Box<descriptor::DescriptorPublicKey>),
     InvalidMultiThresh(usize),
     InvalidMultiKeys(usize),
@@ -39,6 +41,13 @@ impl std::fmt::Display for LianaPolicyError {
                     key
                     )
             }
+            Self::InvalidMultipath(key) => {
+                write!(
+                    f,
+                    "Invalid derivation branches for key '{}'. Liana derives the receive addresses from the branch 0 and the change addresses from the branch 1, and only supports keys with exactly these two branches. That is, a multipath step of the form '/<0;1>/*'.",
+                    key
+                    )
+            }
             Self::InvalidMultiThresh(thresh) => write!(f, "Invalid multisig threshold value '{}'. The threshold must be > to 0 and <= to the number of keys.", thresh),
             Self::InvalidMultiKeys(n_keys) => write!(f, "Invalid number of keys '{}'. Between 2 and 20 keys must be given to use multiple keys in a specific path.", n_keys),
             Self::DuplicateKey(key) => {
@@ -98,14 +107,16 @@ impl DescKeyChecker {
             // no unlikely (and easily fixable) while users shooting themselves in the foot by
             // forgetting to provide the origin is so likely that it's worth ruling out xpubs
             // without origin entirely.
-            let valid = xpub.origin.is_some()
-                && xpub.wildcard == descriptor::Wildcard::Unhardened
-                && der_paths.len() == 2
+            if xpub.origin.is_none() || xpub.wildcard != descriptor::Wildcard::Unhardened {
+                return Err(LianaPolicyError::InvalidKey(key.clone().into()));
+            }
+            let valid_branches = der_paths.len() == 2
                 && der_paths[0][len - 1] == 0.into()
                 && der_paths[1][len - 1] == 1.into();
-            if valid {
+            if valid_branches {
                 return Ok(());
             }
+            return Err(LianaPolicyError::InvalidMultipath(key.clone().into()));
         }
         Err(LianaPolicyError::InvalidKey(key.clone().into()))
     }
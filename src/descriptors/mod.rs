@@ -6,10 +6,13 @@ use miniscript::{
             psbt::{Input as PsbtIn, Psbt},
         },
     },
-    descriptor, translate_hash_clone, ForEachKey, TranslatePk, Translator,
+    descriptor, translate_hash_clone, ForEachKey, Satisfier, TranslatePk, Translator,
 };
 
-use std::{collections::BTreeMap, error, fmt, str};
+use std::{
+    collections::{BTreeMap, HashSet},
+    error, fmt, str,
+};
 
 use serde::{Deserialize, Serialize};
 
@@ -28,6 +31,15 @@ fn wu_to_vb(vb: usize) -> usize {
         .expect("Non 0")
 }
 
+/// Maximum size of the witness script of a P2WSH spend relayed by standard nodes.
+pub const MAX_STANDARD_P2WSH_SCRIPT_SIZE: usize = 3600;
+/// Maximum number of stack elements (not counting the witness script) of a P2WSH spend relayed
+/// by standard nodes.
+pub const MAX_STANDARD_P2WSH_STACK_ITEMS: usize = 100;
+/// Maximum size of the stack elements (not counting the witness script) of a P2WSH spend relayed
+/// by standard nodes.
+pub const MAX_STANDARD_P2WSH_STACK_ITEM_SIZE: usize = 80;
+
 #[derive(Debug)]
 pub enum LianaDescError {
     Miniscript(miniscript::Error),
@@ -196,6 +208,47 @@ impl LianaDescriptor {
             .expect("Cannot fail for P2WSH")
     }
 
+    /// Estimate the size of the witness for spending through each path of this descriptor. This
+    /// is computed from the satisfaction of the Miniscript with dummy signatures of the maximum
+    /// size. Returns the estimate for the primary path and for each recovery path by timelock.
+    pub fn witness_sizes(&self) -> (PathWitnessSize, BTreeMap<u16, PathWitnessSize>) {
+        let secp = secp256k1::Secp256k1::verification_only();
+        let derived_desc = self.receive_desc.derive(0.into(), &secp);
+        let path_size = |path_info: &PathInfo, timelock: Option<u16>| {
+            let keys = match path_info {
+                PathInfo::Single(key) => vec![key.clone()],
+                PathInfo::Multi(_, keys) => keys.clone(),
+            };
+            // Derive the keys the same way as the receive descriptor above.
+            let keys = keys
+                .into_iter()
+                .map(|key| {
+                    key.into_single_keys()
+                        .remove(0)
+                        .at_derivation_index(0)
+                        .expect("Never hardened.")
+                        .derive_public_key(&secp)
+                        .expect("Never hardened.")
+                })
+                .collect();
+            let (witness, _) = derived_desc
+                .0
+                .get_satisfaction(PathSatisfier { keys, timelock })
+                .expect("All the signatures and the timelock for this path are available.");
+            PathWitnessSize::from_witness(&witness)
+        };
+
+        let policy = self.policy();
+        (
+            path_size(&policy.primary_path, None),
+            policy
+                .recovery_paths
+                .iter()
+                .map(|(timelock, path_info)| (*timelock, path_size(path_info, Some(*timelock))))
+                .collect(),
+        )
+    }
+
     /// Get the maximum size in vbytes (rounded up) of a satisfaction for this descriptor.
     pub fn max_sat_vbytes(&self) -> usize {
         self.multi_desc
@@ -298,6 +351,73 @@ impl LianaDescriptor {
     }
 }
 
+/// The estimated size of the witness for spending a coin through a given spending path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PathWitnessSize {
+    /// Size of the whole witness, including the witness script, in weight units.
+    pub weight: usize,
+    /// Number of stack elements, not counting the witness script.
+    pub stack_items: usize,
+    /// Size of the largest stack element, not counting the witness script.
+    pub max_item_size: usize,
+    /// Size of the witness script.
+    pub script_size: usize,
+}
+
+impl PathWitnessSize {
+    fn from_witness(witness: &[Vec<u8>]) -> PathWitnessSize {
+        let (script, stack) = witness
+            .split_last()
+            .expect("The witness of a P2WSH spend always contains the witness script");
+        PathWitnessSize {
+            weight: bitcoin::VarInt(witness.len() as u64).len()
+                + witness
+                    .iter()
+                    .map(|item| bitcoin::VarInt(item.len() as u64).len() + item.len())
+                    .sum::<usize>(),
+            stack_items: stack.len(),
+            max_item_size: stack.iter().map(|item| item.len()).max().unwrap_or(0),
+            script_size: script.len(),
+        }
+    }
+
+    /// Whether a transaction spending through this path would be relayed by standard nodes.
+    pub fn is_standard(&self) -> bool {
+        self.script_size <= MAX_STANDARD_P2WSH_SCRIPT_SIZE
+            && self.stack_items <= MAX_STANDARD_P2WSH_STACK_ITEMS
+            && self.max_item_size <= MAX_STANDARD_P2WSH_STACK_ITEM_SIZE
+    }
+}
+
+// Satisfies a spending path with dummy signatures of the maximum size for its keys, and its
+// timelock.
+struct PathSatisfier {
+    keys: HashSet<bitcoin::PublicKey>,
+    timelock: Option<u16>,
+}
+
+impl Satisfier<DerivedPublicKey> for PathSatisfier {
+    fn lookup_ecdsa_sig(&self, key: &DerivedPublicKey) -> Option<bitcoin::EcdsaSig> {
+        if !self.keys.contains(&key.key) {
+            return None;
+        }
+        // Both the r and s values have their high bit set: this is the largest DER encoding
+        // (72 bytes, 73 with the sighash type).
+        let sig = secp256k1::ecdsa::Signature::from_compact(&[0x80; 64])
+            .expect("Valid compact signature");
+        Some(bitcoin::EcdsaSig {
+            sig,
+            hash_ty: bitcoin::EcdsaSighashType::All,
+        })
+    }
+
+    fn check_older(&self, seq: bitcoin::Sequence) -> bool {
+        self.timelock
+            .map(|timelock| seq.is_height_locked() && seq.0 <= timelock as u32)
+            .unwrap_or(false)
+    }
+}
+
 impl SinglePathLianaDesc {
     /// Derive this descriptor at a given index for a receiving address.
     ///
@@ -628,6 +748,115 @@ mod tests {
         );
     }
 
+    #[test]
+    fn descriptor_witness_sizes() {
+        let desc = LianaDescriptor::from_str("wsh(or_d(pk([92162c45]tpubD6NzVbkrYhZ4WzTf9SsD6h7AH7oQEippXK2KP8qvhMMqFoNeN5YFVi7vRyeRSDGtgd2bPyMxUNmHui8t5yCgszxPPxMafu1VVzDpg9aruYW/<0;1>/*),and_v(v:pkh([abcdef01]tpubD6NzVbkrYhZ4Wdgu2yfdmrce5g4fiH1ZLmKhewsnNKupbi4sxjH1ZVAorkBLWSkhsjhg8kiq8C4BrBjMy3SjAKDyDdbuvUa1ToAHbiR98js/<0;1>/*),older(2))))#ravw7jw5").unwrap();
+        let secp = secp256k1::Secp256k1::verification_only();
+        let script_size = desc
+            .receive_descriptor()
+            .derive(0.into(), &secp)
+            .witness_script()
+            .len();
+
+        let (primary, recovery) = desc.witness_sizes();
+        // The stack size, a signature and the script.
+        assert_eq!(
+            primary,
+            PathWitnessSize {
+                weight: 1 + 1 + 73 + 1 + script_size,
+                stack_items: 1,
+                max_item_size: 73,
+                script_size,
+            }
+        );
+        // The stack size, an empty vector for dissatisfying the primary key, the recovery key, a
+        // signature for the recovery key and the script.
+        assert_eq!(recovery.len(), 1);
+        assert_eq!(
+            recovery[&2],
+            PathWitnessSize {
+                weight: 1 + 1 + 34 + 1 + 73 + 1 + script_size,
+                stack_items: 3,
+                max_item_size: 73,
+                script_size,
+            }
+        );
+        assert!(primary.is_standard() && recovery[&2].is_standard());
+
+        // A large policy: 20 keys for the primary path and two recovery paths with 20 keys each.
+        // The witness script gets close to the standardness limit.
+        let secp = secp256k1::Secp256k1::signing_only();
+        let keys = |n: usize| (0..n).map(|_| random_desc_key(&secp)).collect::<Vec<_>>();
+        let policy = LianaPolicy::new(
+            PathInfo::Multi(20, keys(20)),
+            [
+                (26352, PathInfo::Multi(15, keys(20))),
+                (52704, PathInfo::Multi(10, keys(20))),
+            ]
+            .iter()
+            .cloned()
+            .collect(),
+        )
+        .unwrap();
+        let desc = LianaDescriptor::new(policy);
+        let (primary, recovery) = desc.witness_sizes();
+        assert!(primary.script_size > 60 * 33);
+        assert!(primary.stack_items >= 20);
+        assert!(primary.weight > 20 * 73 + primary.script_size);
+        assert!(recovery[&26352].stack_items >= 15);
+        assert!(recovery[&52704].stack_items >= 10);
+        for size in std::iter::once(&primary).chain(recovery.values()) {
+            assert_eq!(size.script_size, primary.script_size);
+            assert_eq!(size.max_item_size, 73);
+            assert!(size.is_standard());
+        }
+
+        // Too many stack elements to be relayed.
+        let non_standard = PathWitnessSize {
+            stack_items: MAX_STANDARD_P2WSH_STACK_ITEMS + 1,
+            ..primary
+        };
+        assert!(!non_standard.is_standard());
+        let non_standard = PathWitnessSize {
+            script_size: MAX_STANDARD_P2WSH_SCRIPT_SIZE + 1,
+            ..primary
+        };
+        assert!(!non_standard.is_standard());
+    }
+
+    #[test]
+    fn unusual_multipath_branches() {
+        // Liana derives receive and change addresses from exactly the branches 0 and 1.
+        for branches in &["<2;3>", "<1;0>", "<0;2>"] {
+            let desc_str = format!("wsh(or_d(pk([abcdef01]xpub6Eze7yAT3Y1wGrnzedCNVYDXUqa9NmHVWck5emBaTbXtURbe1NWZbK9bsz1TiVE7Cz341PMTfYgFw1KdLWdzcM1UMFTcdQfCYhhXZ2HJvTW/{}/*),and_v(v:pkh([abcdef01]xpub688Hn4wScQAAiYJLPg9yH27hUpfZAUnmJejRQBCiwfP5PEDzjWMNW1wChcninxr5gyavFqbbDjdV1aK5USJz8NDVjUy7FRQaaqqXHh5SbXe/<0;1>/*),older(52560))))", branches);
+            let err = LianaDescriptor::from_str(&desc_str).unwrap_err();
+            assert!(
+                matches!(
+                    err,
+                    LianaDescError::Policy(LianaPolicyError::InvalidMultipath(_))
+                ),
+                "{}: {}",
+                branches,
+                err
+            );
+            let msg = err.to_string();
+            assert!(msg.contains("Invalid derivation branches"), "{}", msg);
+            assert!(msg.contains("'/<0;1>/*'"), "{}", msg);
+        }
+
+        // The branches may follow other derivation steps.
+        let desc = LianaDescriptor::from_str("wsh(or_d(pk([abcdef01]xpub6Eze7yAT3Y1wGrnzedCNVYDXUqa9NmHVWck5emBaTbXtURbe1NWZbK9bsz1TiVE7Cz341PMTfYgFw1KdLWdzcM1UMFTcdQfCYhhXZ2HJvTW/7/<0;1>/*),and_v(v:pkh([abcdef01]xpub688Hn4wScQAAiYJLPg9yH27hUpfZAUnmJejRQBCiwfP5PEDzjWMNW1wChcninxr5gyavFqbbDjdV1aK5USJz8NDVjUy7FRQaaqqXHh5SbXe/<0;1>/*),older(52560))))").unwrap();
+        assert!(desc.receive_descriptor().to_string().contains("/7/0/*"));
+        assert!(desc.change_descriptor().to_string().contains("/7/1/*"));
+
+        // A key without the multipath step is still reported as such.
+        let err = LianaDescriptor::from_str("wsh(or_d(pk([abcdef01]xpub6Eze7yAT3Y1wGrnzedCNVYDXUqa9NmHVWck5emBaTbXtURbe1NWZbK9bsz1TiVE7Cz341PMTfYgFw1KdLWdzcM1UMFTcdQfCYhhXZ2HJvTW/0/*),and_v(v:pkh([abcdef01]xpub688Hn4wScQAAiYJLPg9yH27hUpfZAUnmJejRQBCiwfP5PEDzjWMNW1wChcninxr5gyavFqbbDjdV1aK5USJz8NDVjUy7FRQaaqqXHh5SbXe/<0;1>/*),older(52560))))").unwrap_err();
+        assert!(matches!(
+            err,
+            LianaDescError::Policy(LianaPolicyError::InvalidKey(_))
+        ));
+    }
+
     #[test]
     fn liana_desc_keys() {
         let secp = secp256k1::Secp256k1::signing_only();
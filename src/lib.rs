@@ -280,19 +280,32 @@ fn setup_bitcoind(
         // Because of the hack above, the assumption that whenever the data directory is fresh a
         // watchonly wallet doesn't exist doesn't hold for Windows. Make sure it does by removing
         // any leftover Liana watchonly wallet from bitcoind's data dir.
+        // The data dir is guessed from the cookie file, not known if bitcoind is authenticated
+        // with a user and password.
         #[cfg(windows)]
-        maybe_delete_watchonly_wallet(
-            &bitcoind,
-            &bitcoind_config.cookie_path,
-            config.bitcoin_config.network,
-            wo_name,
-        )?;
+        if bitcoind_config.rpc_auth.is_none() {
+            maybe_delete_watchonly_wallet(
+                &bitcoind,
+                &bitcoind_config.cookie_path,
+                config.bitcoin_config.network,
+                wo_name,
+            )?;
+        }
 
-        bitcoind.create_watchonly_wallet(&config.main_descriptor)?;
+        bitcoind
+            .create_watchonly_wallet(&config.main_descriptor, &bitcoind_config.descriptor_import)?;
         log::info!("Created a new watchonly wallet on bitcoind.");
     }
     bitcoind.maybe_load_watchonly_wallet()?;
-    bitcoind.wallet_sanity_checks(&config.main_descriptor)?;
+    // The import of the change descriptor may have been deferred to the next startup.
+    let change_deferred = !bitcoind_config.descriptor_import.import_change;
+    if change_deferred && !fresh_data_dir {
+        bitcoind.maybe_import_change_descriptor(
+            &config.main_descriptor,
+            &bitcoind_config.descriptor_import,
+        )?;
+    }
+    bitcoind.wallet_sanity_checks(&config.main_descriptor, change_deferred && fresh_data_dir)?;
     log::info!("Connection to bitcoind established and checked.");
 
     Ok(bitcoind)
@@ -690,6 +703,8 @@ mod tests {
         let bitcoind_config = BitcoindConfig {
             addr,
             cookie_path: cookie,
+            rpc_auth: None,
+            descriptor_import: Default::default(),
         };
 
         // Create a dummy config with this bitcoind
@@ -6,7 +6,6 @@
 use crate::random;
 
 use std::{
-    convert::TryInto,
     error, fmt, fs,
     io::{self, Write},
     path,
@@ -57,10 +56,14 @@ impl error::Error for SignerError {}
 pub const MNEMONICS_FOLDER_NAME: &str = "mnemonics";
 
 // TODO: zeroize, mlock, etc.. For now we don't even encrypt the seed on disk so that'd be
-// overkill.
+// overkill. Note the secrets are not wiped from memory when the signer is dropped: the mnemonic
+// and the keys are `Copy` types, copied by the bip39 and bitcoin libraries (and by
+// `with_passphrase`) where they cannot be tracked, and an all-zero secret key is not valid.
 /// A signer that keeps the key on the laptop. Based on BIP39.
 pub struct HotSigner {
     mnemonic: bip39::Mnemonic,
+    /// The BIP39 passphrase the seed is derived with, empty if none.
+    passphrase: String,
     master_xpriv: bip32::ExtendedPrivKey,
 }
 
@@ -105,11 +108,14 @@ impl HotSigner {
     fn from_mnemonic(
         network: bitcoin::Network,
         mnemonic: bip39::Mnemonic,
+        passphrase: &str,
     ) -> Result<Self, SignerError> {
-        let master_xpriv = bip32::ExtendedPrivKey::new_master(network, &mnemonic.to_seed(""))
-            .map_err(SignerError::Bip32)?;
+        let master_xpriv =
+            bip32::ExtendedPrivKey::new_master(network, &mnemonic.to_seed(passphrase))
+                .map_err(SignerError::Bip32)?;
         Ok(Self {
             mnemonic,
+            passphrase: passphrase.to_string(),
             master_xpriv,
         })
     }
@@ -120,12 +126,32 @@ impl HotSigner {
         let random_32bytes = random::random_bytes().map_err(SignerError::Randomness)?;
         let mnemonic =
             bip39::Mnemonic::from_entropy(&random_32bytes[..16]).map_err(SignerError::Mnemonic)?;
-        Self::from_mnemonic(network, mnemonic)
+        Self::from_mnemonic(network, mnemonic, "")
     }
 
     pub fn from_str(network: bitcoin::Network, s: &str) -> Result<Self, SignerError> {
+        Self::from_str_with_passphrase(network, s, "")
+    }
+
+    /// Create a hot signer from the mnemonic and the BIP39 passphrase (the "25th word") its seed
+    /// is derived with. An empty passphrase gives the same signer as [`HotSigner::from_str`].
+    pub fn from_str_with_passphrase(
+        network: bitcoin::Network,
+        s: &str,
+        passphrase: &str,
+    ) -> Result<Self, SignerError> {
         let mnemonic = bip39::Mnemonic::from_str(s).map_err(SignerError::Mnemonic)?;
-        Self::from_mnemonic(network, mnemonic)
+        Self::from_mnemonic(network, mnemonic, passphrase)
+    }
+
+    /// The signer of the same mnemonic with its seed derived with the given BIP39 passphrase.
+    pub fn with_passphrase(&self, passphrase: &str) -> Result<Self, SignerError> {
+        Self::from_mnemonic(self.master_xpriv.network, self.mnemonic, passphrase)
+    }
+
+    /// Whether the seed of this signer is derived with a BIP39 passphrase.
+    pub fn has_passphrase(&self) -> bool {
+        !self.passphrase.is_empty()
     }
 
     fn mnemonics_folder(datadir_root: &path::Path, network: bitcoin::Network) -> path::PathBuf {
@@ -138,34 +164,55 @@ impl HotSigner {
         .collect()
     }
 
-    /// Read all the mnemonics from the datadir for the given network.
+    /// Read all the mnemonics from the datadir for the given network. Their seed is derived
+    /// without passphrase, see [`HotSigner::from_datadir_with_fingerprints`].
     pub fn from_datadir(
         datadir_root: &path::Path,
         network: bitcoin::Network,
     ) -> Result<Vec<Self>, SignerError> {
+        Ok(Self::from_datadir_with_fingerprints(datadir_root, network)?
+            .into_iter()
+            .map(|(signer, _)| signer)
+            .collect())
+    }
+
+    /// Read all the mnemonics from the datadir for the given network, along with the
+    /// fingerprint of the master key each was stored for if its filename has one. The BIP39
+    /// passphrase is never stored: the seed is derived without it, and a fingerprint different
+    /// from the one of the signer means [`HotSigner::with_passphrase`] must be used.
+    pub fn from_datadir_with_fingerprints(
+        datadir_root: &path::Path,
+        network: bitcoin::Network,
+    ) -> Result<Vec<(Self, Option<bip32::Fingerprint>)>, SignerError> {
         let mut signers = Vec::new();
 
         let mnemonic_paths = fs::read_dir(Self::mnemonics_folder(datadir_root, network))
             .map_err(SignerError::MnemonicStorage)?;
         for entry in mnemonic_paths {
-            let mnemonic = fs::read_to_string(entry.map_err(SignerError::MnemonicStorage)?.path())
-                .map_err(SignerError::MnemonicStorage)?;
-            signers.push(Self::from_str(network, &mnemonic)?);
+            let path = entry.map_err(SignerError::MnemonicStorage)?.path();
+            let mnemonic = fs::read_to_string(&path).map_err(SignerError::MnemonicStorage)?;
+            let fingerprint = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_prefix("mnemonic-"))
+                .and_then(|name| name.strip_suffix(".txt"))
+                .and_then(|fingerprint| bip32::Fingerprint::from_str(fingerprint).ok());
+            signers.push((Self::from_str(network, &mnemonic)?, fingerprint));
         }
 
         Ok(signers)
     }
 
-    /// The BIP39 mnemonics from which the master key of this signer is derived.
-    pub fn words(&self) -> [&'static str; 12] {
-        let words: Vec<&'static str> = self.mnemonic.word_iter().collect();
-        words.try_into().expect("Always 12 words")
+    /// The BIP39 mnemonics from which the master key of this signer is derived. 12 words for a
+    /// generated signer, up to 24 for a recovered one.
+    pub fn words(&self) -> Vec<&'static str> {
+        self.mnemonic.word_iter().collect()
     }
 
     /// The BIP39 mnemonic words as a string.
     pub fn mnemonic_str(&self) -> String {
-        let mut mnemonic_str = String::with_capacity(12 * 7);
         let words = self.words();
+        let mut mnemonic_str = String::with_capacity(words.len() * 7);
 
         for (i, word) in words.iter().enumerate() {
             mnemonic_str += word;
@@ -187,7 +234,9 @@ impl HotSigner {
 
     /// Store the mnemonic in a file within the given "data directory".
     /// The file is stored within a "mnemonics" folder, with the filename set to the fingerprint of
-    /// the master xpub corresponding to this mnemonic.
+    /// the master xpub corresponding to this mnemonic. The passphrase, if any, is not stored: the
+    /// file only contains the words, and the user must enter the passphrase again to use the
+    /// signer read back.
     pub fn store(
         &self,
         datadir_root: &path::Path,
@@ -360,6 +409,94 @@ mod tests {
         fs::remove_dir_all(tmp_dir).unwrap();
     }
 
+    #[test]
+    fn hot_signer_passphrase() {
+        let secp = secp256k1::Secp256k1::signing_only();
+        let network = bitcoin::Network::Bitcoin;
+        let mnemonic = "burger ball theme dog light account produce chest warrior swarm flip equip";
+
+        // An empty passphrase gives the same signer as none.
+        let signer = HotSigner::from_str(network, mnemonic).unwrap();
+        let no_passphrase = HotSigner::from_str_with_passphrase(network, mnemonic, "").unwrap();
+        assert!(!no_passphrase.has_passphrase());
+        assert_eq!(no_passphrase.fingerprint(&secp), signer.fingerprint(&secp));
+        assert_eq!(
+            signer.with_passphrase("").unwrap().fingerprint(&secp),
+            signer.fingerprint(&secp)
+        );
+
+        // Another one gives another seed, from the same words.
+        let with_passphrase =
+            HotSigner::from_str_with_passphrase(network, mnemonic, "correct horse").unwrap();
+        assert!(with_passphrase.has_passphrase());
+        assert_eq!(with_passphrase.words(), signer.words());
+        assert_ne!(
+            with_passphrase.fingerprint(&secp),
+            signer.fingerprint(&secp)
+        );
+        assert_eq!(
+            signer
+                .with_passphrase("correct horse")
+                .unwrap()
+                .fingerprint(&secp),
+            with_passphrase.fingerprint(&secp)
+        );
+
+        // An invalid mnemonic is still refused.
+        assert!(matches!(
+            HotSigner::from_str_with_passphrase(network, "burger ball theme", "correct horse"),
+            Err(SignerError::Mnemonic(_))
+        ));
+
+        // The passphrase is not stored, only the words are. The mnemonic is read back with the
+        // fingerprint it was stored for, to be derived again with the passphrase.
+        let tmp_dir = tmp_dir();
+        fs::create_dir_all(&tmp_dir).unwrap();
+        with_passphrase.store(&tmp_dir, network, &secp).unwrap();
+        let stored = fs::read_to_string(tmp_dir.join(format!(
+            "bitcoin/mnemonics/mnemonic-{:x}.txt",
+            with_passphrase.fingerprint(&secp)
+        )))
+        .unwrap();
+        assert_eq!(stored, mnemonic);
+        let read = HotSigner::from_datadir_with_fingerprints(&tmp_dir, network).unwrap();
+        assert_eq!(read.len(), 1);
+        let (read_signer, stored_fingerprint) = &read[0];
+        assert!(!read_signer.has_passphrase());
+        assert_eq!(read_signer.fingerprint(&secp), signer.fingerprint(&secp));
+        assert_eq!(
+            *stored_fingerprint,
+            Some(with_passphrase.fingerprint(&secp))
+        );
+        assert_eq!(
+            read_signer
+                .with_passphrase("correct horse")
+                .unwrap()
+                .fingerprint(&secp),
+            with_passphrase.fingerprint(&secp)
+        );
+
+        // The mnemonic without passphrase is stored in the same format.
+        signer.store(&tmp_dir, network, &secp).unwrap();
+        let mut fingerprints: Vec<_> = HotSigner::from_datadir_with_fingerprints(&tmp_dir, network)
+            .unwrap()
+            .iter()
+            .map(|(signer, stored)| (signer.fingerprint(&secp), stored.unwrap()))
+            .collect();
+        fingerprints.sort();
+        let mut expected = vec![
+            (signer.fingerprint(&secp), signer.fingerprint(&secp)),
+            (
+                signer.fingerprint(&secp),
+                with_passphrase.fingerprint(&secp),
+            ),
+        ];
+        expected.sort();
+        assert_eq!(fingerprints, expected);
+
+        fs::remove_dir_all(tmp_dir).unwrap();
+    }
+
     #[test]
     fn hot_signer_sign() {
         let secp = secp256k1::Secp256k1::new();